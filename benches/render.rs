@@ -0,0 +1,49 @@
+//! Benchmarks for drawing the big digits: building the glyph lines, and
+//! working out which cells changed between one frame and the next
+
+use clockit::{
+    config::Config,
+    digit::{render_time, DigitOptions},
+    ui::{BigDigits, DigitState},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+fn options() -> DigitOptions {
+    DigitOptions::from_config(&Config::default())
+}
+
+/// A full frame with `time` drawn in the middle, as on an 80x24 terminal
+fn frame(time: &str, options: DigitOptions, state: &DigitState) -> Buffer {
+    let area = Rect::new(0, 0, 80, 24);
+    let mut buffer = Buffer::empty(area);
+    BigDigits::new(time, options, state).render(area, &mut buffer);
+    buffer
+}
+
+fn bench_render_time(c: &mut Criterion) {
+    let options = options();
+    c.bench_function("render_time countdown", |b| b.iter(|| render_time(black_box("24:59"), &options)));
+    c.bench_function("render_time stopwatch", |b| b.iter(|| render_time(black_box("1:02:03.45"), &options)));
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let options = options();
+    let state = DigitState::new();
+    // A second ticking by changes one digit, the hundredths change two
+    let countdown = frame("24:59", options, &state);
+    let next = frame("24:58", options, &state);
+    c.bench_function("diff one digit", |b| b.iter(|| black_box(&countdown).diff(black_box(&next)).len()));
+
+    let stopwatch = frame("1:02:03.45", options, &state);
+    let next = frame("1:02:03.46", options, &state);
+    c.bench_function("diff fraction", |b| b.iter(|| black_box(&stopwatch).diff(black_box(&next)).len()));
+
+    // Everything a countdown tick costs before anything is written
+    c.bench_function("draw and diff frame", |b| {
+        b.iter(|| frame(black_box("24:58"), options, &state).diff(&countdown).len())
+    });
+}
+
+criterion_group!(benches, bench_render_time, bench_diff);
+criterion_main!(benches);
@@ -0,0 +1,180 @@
+// src/accessible.rs
+//! Module for the screen-reader friendly mode, which narrates the timer as
+//! plain lines of text instead of drawing ASCII art
+
+use crate::{config::AccessibilitySettings, format_duration, modes::Status};
+use std::{
+    io::{self, stdout, Write},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+/// The parts of a status that make up a distinct state worth announcing
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StateKey {
+    label: Option<String>,
+    paused: bool,
+    finished: bool,
+    prompt: Option<String>,
+}
+
+/// Prints an update when the timer changes state, and periodic time updates in between
+pub struct Announcer {
+    interval: u64,
+    speech: bool,
+    last_state: Option<StateKey>,
+    /// Interval mark of the last periodic announcement
+    last_mark: Option<u64>,
+}
+
+impl Announcer {
+    pub fn new(settings: &AccessibilitySettings) -> Self {
+        Announcer {
+            interval: settings.announce_interval.max(1),
+            speech: settings.speech,
+            last_state: None,
+            last_mark: None,
+        }
+    }
+
+    /// Announce whatever has changed since the previous status
+    pub fn update(&mut self, status: &Status) -> io::Result<()> {
+        let state = StateKey {
+            label: status.label.clone(),
+            paused: status.paused,
+            finished: status.finished,
+            prompt: status.prompt.clone(),
+        };
+
+        if self.last_state.as_ref() != Some(&state) {
+            let message = describe(status);
+            self.last_state = Some(state);
+            self.last_mark = self.mark(status);
+            if self.speech {
+                speak(&message);
+            }
+            return say(&message);
+        }
+
+        // Only running timers get periodic updates
+        if status.paused || status.prompt.is_some() {
+            return Ok(());
+        }
+
+        let mark = self.mark(status);
+        if mark != self.last_mark {
+            self.last_mark = mark;
+            if let Some(message) = time_update(status, self.interval) {
+                return say(&message);
+            }
+        }
+        Ok(())
+    }
+
+    /// Which announcement interval the timer is in
+    fn mark(&self, status: &Status) -> Option<u64> {
+        match (status.remaining, status.elapsed) {
+            (Some(remaining), _) => Some(round_up(remaining).div_ceil(self.interval)),
+            (None, Some(elapsed)) => Some(elapsed.as_secs() / self.interval),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Full description of a new state, e.g. "Work Session #1: 25 minutes remaining"
+fn describe(status: &Status) -> String {
+    if let Some(prompt) = &status.prompt {
+        return prompt.clone();
+    }
+
+    let name = status.label.clone().unwrap_or_else(|| capitalize(status.mode));
+    let time = match (status.remaining, status.elapsed) {
+        (Some(remaining), _) => format!("{} remaining", format_duration(round_up(remaining))),
+        (None, Some(elapsed)) => format!("{} elapsed", format_duration(elapsed.as_secs())),
+        (None, None) => status.time.clone(),
+    };
+
+    if status.paused {
+        format!("{} paused, {}.", name, time)
+    } else {
+        format!("{}: {}.", name, time)
+    }
+}
+
+/// Periodic update, e.g. "10 minutes remaining"
+fn time_update(status: &Status, interval: u64) -> Option<String> {
+    match (status.remaining, status.elapsed) {
+        (Some(remaining), _) => {
+            let secs = round_up(remaining);
+            // The final crossing is covered by the completion announcement
+            (secs > 0).then(|| format!("{} remaining", format_duration(secs.div_ceil(interval) * interval)))
+        }
+        (None, Some(elapsed)) => {
+            let secs = elapsed.as_secs() / interval * interval;
+            Some(format!("{} elapsed", format_duration(secs)))
+        }
+        (None, None) => None,
+    }
+}
+
+/// Whole seconds left, counting a partial second as a full one
+fn round_up(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Print one announcement line (raw mode needs an explicit carriage return)
+fn say(message: &str) -> io::Result<()> {
+    let mut stdout = stdout();
+    write!(stdout, "{}\r\n", message)?;
+    stdout.flush()
+}
+
+/// Speak a message with the first text-to-speech tool that is installed
+fn speak(message: &str) {
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("say", &[])]
+    } else if cfg!(windows) {
+        &[(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "Add-Type -AssemblyName System.Speech; \
+                 (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+            ],
+        )]
+    } else {
+        &[("spd-say", &[]), ("espeak-ng", &[]), ("espeak", &[])]
+    };
+
+    for (program, args) in candidates {
+        let mut command = Command::new(program);
+        command.args(*args).stdout(Stdio::null()).stderr(Stdio::null());
+
+        // PowerShell reads the text from stdin, the others take it as an argument
+        let spawned = if cfg!(windows) {
+            command.stdin(Stdio::piped()).spawn().inspect(|child| {
+                if let Some(stdin) = &child.stdin {
+                    let _ = (&*stdin).write_all(message.as_bytes());
+                }
+            })
+        } else {
+            command.arg(message).stdin(Stdio::null()).spawn()
+        };
+
+        if let Ok(mut child) = spawned {
+            // Reap the speech process in the background once it finishes
+            drop(child.stdin.take());
+            std::thread::spawn(move || child.wait());
+            return;
+        }
+    }
+}
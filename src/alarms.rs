@@ -0,0 +1,342 @@
+// src/alarms.rs
+//! Module for `clockit alarms`: alarms at times of day set in the config,
+//! rung by a process left running in a terminal
+//!
+//! `clockit alarms run` waits for the next alarm, takes over the terminal to
+//! show it while its sound plays, and goes back to waiting once a key is
+//! pressed or it has rung for a minute. A ringing alarm runs in the same event
+//! loop as the timers.
+
+use crate::{
+    app::{self, ExitSummary, RunOptions},
+    clock::{self, Clock, SystemClock},
+    config::{AlarmSettings, Config},
+    control::Command,
+    digit::DigitOptions,
+    locale::messages,
+    modes::{instruction_lines, instructions, Flow, Mode, Status},
+    ntp,
+    sound::{SoundPlayer, SoundSource},
+    theme::Theme,
+    ui::{self, DigitState, Screen},
+};
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeDelta, TimeZone, Weekday};
+use crossterm::event::KeyEvent;
+use ratatui::text::Line;
+use std::{
+    io,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Longest stretch slept at once, so a suspended laptop or a changed clock
+/// delays an alarm by no more than this
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the sound repeats while an alarm rings
+const RING_EVERY: Duration = Duration::from_secs(5);
+
+/// How often a ringing alarm checks whether to ring again or give up
+const RING_TICK: Duration = Duration::from_secs(1);
+
+/// How long an alarm rings before giving up on being noticed
+const RING_FOR: Duration = Duration::from_secs(60);
+
+/// An alarm more than this late, e.g. after the computer slept through it,
+/// is reported as missed instead of rung
+const MISSED_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// The days and time of day an alarm rings at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Whether it rings on each day, Monday first
+    days: [bool; 7],
+    time: NaiveTime,
+}
+
+impl Schedule {
+    /// The first time after `now` the alarm rings
+    pub fn next_after(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        (0..=7).find_map(|offset| {
+            let date = now.date_naive() + TimeDelta::days(offset);
+            if !self.days[date.weekday().num_days_from_monday() as usize] {
+                return None;
+            }
+            // A time skipped by a daylight saving change rings on the next matching day
+            Local
+                .from_local_datetime(&date.and_time(self.time))
+                .earliest()
+                .filter(|time| *time > now)
+        })
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    /// Parse a time of day ("7:30"), after the days it rings on: `daily`,
+    /// `weekdays`, `weekends` or a list like `mon,thu`, with an optional `at`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowered = s.trim().to_lowercase();
+        let mut words: Vec<&str> = lowered.split_whitespace().collect();
+        let Some(time_text) = words.pop() else {
+            return Err("no time given".to_string());
+        };
+        let time = NaiveTime::parse_from_str(time_text, "%H:%M")
+            .map_err(|_| format!("`{}` is not a time of day like 17:30", time_text))?;
+        if words.last() == Some(&"at") {
+            words.pop();
+        }
+
+        let days = match words.join(" ").as_str() {
+            "" | "daily" | "every day" => [true; 7],
+            "weekdays" => [true, true, true, true, true, false, false],
+            "weekends" => [false, false, false, false, false, true, true],
+            list => {
+                let mut days = [false; 7];
+                for name in list.split([',', ' ']).filter(|name| !name.is_empty()) {
+                    let day = name
+                        .parse::<Weekday>()
+                        .map_err(|_| format!("`{}` is not daily, weekdays, weekends or a day of the week", name))?;
+                    days[day.num_days_from_monday() as usize] = true;
+                }
+                days
+            }
+        };
+        Ok(Schedule { days, time })
+    }
+}
+
+/// An alarm from the config, ready to ring
+struct Alarm {
+    schedule: Schedule,
+    label: String,
+    sound: SoundSource,
+}
+
+impl Alarm {
+    fn from_settings(settings: &AlarmSettings) -> Result<Self, String> {
+        let schedule = settings.at.parse::<Schedule>().map_err(|e| format!("{}: {}", settings.at, e))?;
+        let sound = SoundSource::from_setting(&settings.sound).unwrap_or_else(|e| {
+            warn!(at = settings.at, error = %e, "unusable alarm sound, ringing the bell");
+            eprintln!("Alarm at {}: {}. Ringing the bell instead.", settings.at, e);
+            SoundSource::Bell
+        });
+        let label = settings.label.clone().unwrap_or_else(|| messages().alarm.clone());
+        Ok(Alarm { schedule, label, sound })
+    }
+}
+
+/// The alarms in the config, skipping any whose time can't be understood
+fn load_alarms(config: &Config) -> Vec<Alarm> {
+    config
+        .alarms
+        .iter()
+        .filter_map(|settings| match Alarm::from_settings(settings) {
+            Ok(alarm) => Some(alarm),
+            Err(e) => {
+                warn!(error = %e, "invalid alarm");
+                eprintln!("Ignoring alarm: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The next time any alarm rings after `after`, with every alarm due then
+fn next_alarms(alarms: &[Alarm], after: DateTime<Local>) -> Option<(DateTime<Local>, Vec<&Alarm>)> {
+    let next = alarms.iter().filter_map(|alarm| alarm.schedule.next_after(after)).min()?;
+    let due = alarms
+        .iter()
+        .filter(|alarm| alarm.schedule.next_after(after) == Some(next))
+        .collect();
+    Some((next, due))
+}
+
+fn labels(alarms: &[&Alarm]) -> String {
+    alarms.iter().map(|alarm| alarm.label.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Print each alarm in the config and when it next rings
+pub fn list(config: &Config) -> io::Result<()> {
+    let now = Local::now();
+    if config.alarms.is_empty() {
+        println!("No alarms set. Add them under alarms in the config.");
+        return Ok(());
+    }
+
+    for settings in &config.alarms {
+        match Alarm::from_settings(settings) {
+            Ok(alarm) => match alarm.schedule.next_after(now) {
+                Some(next) => println!("{:<24} {:<20} next {}", alarm.label, settings.at, next.format("%a %Y-%m-%d %H:%M")),
+                None => println!("{:<24} {:<20} never", alarm.label, settings.at),
+            },
+            Err(e) => println!("Invalid alarm: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Wait for each alarm in turn and ring it, until Ctrl+C
+pub async fn run(config: &Config) -> io::Result<()> {
+    let alarms = load_alarms(config);
+    if alarms.is_empty() {
+        println!("No alarms set. Add them under alarms in the config, e.g.\n\nalarms:\n  - at: weekdays at 17:30\n    label: Leave work");
+        return Ok(());
+    }
+
+    // How far to move the system clock to tell the time, when it is off and
+    // clock_check.mode is adjust
+    let correction = ntp::check(&config.clock_check);
+    let now = || Local::now() + correction;
+
+    let sound = SoundPlayer::spawn(true, &config.sounds);
+    let mut checked_until = now();
+    println!("Waiting for alarms. Press Ctrl+C to stop.");
+
+    while let Some((when, due)) = next_alarms(&alarms, checked_until) {
+        println!("Next: {} at {}", labels(&due), when.format("%a %H:%M"));
+        info!(at = %when, alarms = due.len(), "waiting for alarm");
+
+        loop {
+            let left = (when - now()).to_std().unwrap_or_default();
+            if left.is_zero() {
+                break;
+            }
+            tokio::select! {
+                _ = SystemClock.sleep_until(clock::now() + left.min(CHECK_INTERVAL)) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+
+        checked_until = when;
+        let late = (now() - when).to_std().unwrap_or_default();
+        if late > MISSED_AFTER {
+            warn!(at = %when, late_secs = late.as_secs(), "missed alarm");
+            println!("Missed: {} at {}", labels(&due), when.format("%a %H:%M"));
+            continue;
+        }
+
+        info!(at = %when, "ringing alarm");
+        let mut ringing = Ringing::new(config, &due, when, &sound, clock::now());
+        let options = RunOptions { quiet: true, summary: ExitSummary::Off, ..RunOptions::default() };
+        app::run(&mut ringing, options).await?;
+        if !ringing.answered {
+            println!("Unanswered: {} at {}", labels(&due), when.format("%a %H:%M"));
+        }
+    }
+    Ok(())
+}
+
+/// The alarms due at `when`, ringing: their time in big digits and what they
+/// are for, with their sounds repeating until a key is pressed or they have
+/// rung for a while
+struct Ringing<'a> {
+    due: &'a [&'a Alarm],
+    when: DateTime<Local>,
+    sound: &'a SoundPlayer,
+    theme: Theme,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    instructions: String,
+    started: Instant,
+    rung_at: Instant,
+    /// Whether a key was pressed before the alarms gave up
+    answered: bool,
+}
+
+impl<'a> Ringing<'a> {
+    fn new(
+        config: &Config,
+        due: &'a [&'a Alarm],
+        when: DateTime<Local>,
+        sound: &'a SoundPlayer,
+        now: Instant,
+    ) -> Self {
+        let ringing = Ringing {
+            due,
+            when,
+            sound,
+            theme: Theme::from_config(config),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            instructions: instructions(&config.instructions.alarm, &[]),
+            started: now,
+            rung_at: now,
+            answered: false,
+        };
+        ringing.ring();
+        ringing
+    }
+
+    fn ring(&self) {
+        for alarm in self.due {
+            self.sound.play_source(alarm.sound.clone());
+        }
+    }
+}
+
+impl Mode for Ringing<'_> {
+    fn refresh_rate(&self) -> Duration {
+        RING_TICK
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if now.saturating_duration_since(self.started) >= RING_FOR {
+            return Flow::Quit;
+        }
+        if now.saturating_duration_since(self.rung_at) >= RING_EVERY {
+            self.ring();
+            self.rung_at = now;
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, _key: KeyEvent, _now: Instant) -> Flow {
+        self.answered = true;
+        Flow::Quit
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, _now: Instant) -> Result<Flow, String> {
+        Err(format!("{} is not supported by a ringing alarm", command))
+    }
+
+    fn status(&self, _now: Instant) -> Status {
+        Status {
+            mode: "alarm",
+            label: Some(labels(self.due)),
+            time: self.when.format("%H:%M").to_string(),
+            remaining: None,
+            elapsed: None,
+            paused: false,
+            finished: false,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, _now: Instant) {
+        let header = instruction_lines(self.instructions.clone());
+        let time = self.when.format("%H:%M").to_string();
+        let panel = self
+            .due
+            .iter()
+            .map(|alarm| Line::styled(alarm.label.clone(), self.theme.times_up))
+            .collect();
+        let (digits, state) = (self.digits, &self.digit_state);
+        ui::draw_timer_with_panel(frame, &header, self.theme.ui_text, &time, digits, state, self.theme.times_up, panel);
+    }
+
+    fn exit_message(&self) -> String {
+        String::new()
+    }
+}
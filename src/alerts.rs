@@ -0,0 +1,164 @@
+// src/alerts.rs
+//! Module for alerting at chosen points in a countdown, like halfway through
+//! or with five minutes left
+
+use crate::{config::AlertSettings, parse_time_string};
+use ratatui::style::Color;
+use std::{str::FromStr, time::Duration};
+use tracing::{info, warn};
+
+/// Where in a countdown an alert goes off
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// Once this share of the countdown has elapsed, from 0 to 1
+    Elapsed(f64),
+    /// Once this much time is left
+    Remaining(Duration),
+}
+
+impl Threshold {
+    /// Time left in a countdown of `length` when the alert goes off
+    fn remaining_at(self, length: Duration) -> Duration {
+        match self {
+            Threshold::Elapsed(share) => length.mul_f64(1.0 - share),
+            Threshold::Remaining(remaining) => remaining,
+        }
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    /// Parse a percentage ("50%") or a time remaining ("5:00")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            return match percent.trim().parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Threshold::Elapsed(percent / 100.0)),
+                _ => Err(format!("`{}` is not a percentage from 0% to 100%", s)),
+            };
+        }
+
+        parse_time_string(s)
+            .map(|secs| Threshold::Remaining(Duration::from_secs(secs)))
+            .map_err(|e| format!("`{}` is not a percentage or time remaining: {}", s, e))
+    }
+}
+
+/// Parse a checkpoint like "45:00=Section A": where it is, as for an alert,
+/// then what it is called
+pub fn parse_checkpoint(text: &str) -> Result<AlertSettings, String> {
+    let Some((at, label)) = text.split_once('=') else {
+        return Err(format!("`{}` has no label after =", text));
+    };
+    at.parse::<Threshold>()?;
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(format!("`{}` has no label after =", text));
+    }
+    Ok(AlertSettings { message: Some(label.to_string()), checkpoint: true, ..AlertSettings::at(at.trim()) })
+}
+
+/// An alert and what it does when crossed
+struct Alert {
+    threshold: Threshold,
+    sound: bool,
+    color: Option<Color>,
+    message: Option<String>,
+    checkpoint: bool,
+    /// Whether the countdown is past the alert
+    crossed: bool,
+}
+
+/// What a newly crossed alert asks for
+pub struct Triggered {
+    pub sound: bool,
+    pub message: Option<String>,
+}
+
+/// The alerts of one countdown, tracking which have been crossed
+pub struct Alerts {
+    alerts: Vec<Alert>,
+    /// Whether the alerts have been checked yet
+    started: bool,
+}
+
+impl Alerts {
+    /// Build the alerts from settings, skipping any that can't be understood
+    pub fn new(settings: &[AlertSettings], parse_color: impl Fn(&str) -> Color) -> Self {
+        let alerts = settings
+            .iter()
+            .filter_map(|alert| match alert.at.parse::<Threshold>() {
+                Ok(threshold) => Some(Alert {
+                    threshold,
+                    sound: alert.sound,
+                    color: alert.color.as_deref().map(&parse_color),
+                    message: alert.message.clone(),
+                    checkpoint: alert.checkpoint,
+                    crossed: false,
+                }),
+                Err(e) => {
+                    warn!(at = alert.at, error = %e, "invalid alert");
+                    eprintln!("Ignoring alert: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        Alerts { alerts, started: false }
+    }
+
+    /// Update which alerts the countdown is past, returning those crossed since the
+    /// last check. Alerts already behind a countdown when it starts stay quiet,
+    /// and alerts are re-armed if added time takes the countdown back before them.
+    pub fn check(&mut self, length: Duration, remaining: Duration) -> Vec<Triggered> {
+        let mut triggered = Vec::new();
+        for alert in &mut self.alerts {
+            let crossed = remaining <= alert.threshold.remaining_at(length);
+            if crossed && !alert.crossed && self.started {
+                info!(threshold = ?alert.threshold, "alert crossed");
+                triggered.push(Triggered { sound: alert.sound, message: alert.message.clone() });
+            }
+            alert.crossed = crossed;
+        }
+
+        self.started = true;
+        triggered
+    }
+
+    /// Arm every alert again for a countdown starting afresh
+    pub fn reset(&mut self) {
+        for alert in &mut self.alerts {
+            alert.crossed = false;
+        }
+        self.started = false;
+    }
+
+    /// The crossed alert closest to the end of the countdown that matches `wanted`
+    fn latest(&self, length: Duration, wanted: impl Fn(&Alert) -> bool) -> Option<&Alert> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.crossed && wanted(alert))
+            .min_by_key(|alert| alert.threshold.remaining_at(length))
+    }
+
+    /// Color set by the latest crossed alert that has one
+    pub fn color(&self, length: Duration) -> Option<Color> {
+        self.latest(length, |alert| alert.color.is_some())?.color
+    }
+
+    /// Message of the latest crossed alert that has one
+    pub fn message(&self, length: Duration) -> Option<&str> {
+        self.latest(length, |alert| alert.message.is_some())?.message.as_deref()
+    }
+
+    /// The next checkpoint the countdown will cross, with its label and the
+    /// time left when it does
+    pub fn next_checkpoint(&self, length: Duration, remaining: Duration) -> Option<(&str, Duration)> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.checkpoint && alert.threshold.remaining_at(length) < remaining)
+            .filter_map(|alert| Some((alert.message.as_deref()?, alert.threshold.remaining_at(length))))
+            .max_by_key(|(_, at)| *at)
+    }
+}
@@ -0,0 +1,271 @@
+// src/animation.rs
+//! Module for the animations played when a countdown finishes
+//!
+//! Each animation draws frames straight over the cell buffer, on top of the
+//! "TIME'S UP!" banner, for a short while before leaving the banner on its own.
+
+use crate::config::Config;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+use std::{
+    f64::consts::TAU,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// Names accepted for `completion_animation` in the configuration
+pub const NAMES: [&str; 5] = ["flash", "confetti", "fireworks", "matrix", "none"];
+
+/// Time between frames while an animation is playing
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Colors for confetti and fireworks
+const COLORS: [Color; 6] = [Color::Red, Color::Yellow, Color::Green, Color::Cyan, Color::Magenta, Color::Blue];
+
+/// A completion animation drawn over the cell buffer
+pub trait Animation {
+    /// How long the animation plays
+    fn duration(&self) -> Duration;
+
+    /// Draw the frame `elapsed` into the animation over what is already in `area`
+    fn render(&self, elapsed: Duration, area: Rect, buf: &mut Buffer);
+}
+
+/// Look up the configured completion animation; `None` means no animation
+pub fn from_config(config: &Config) -> Option<Box<dyn Animation>> {
+    // Vary the pattern between runs
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64);
+
+    match config.completion_animation.to_lowercase().as_str() {
+        "flash" => Some(Box::new(Flash)),
+        "confetti" => Some(Box::new(Confetti { seed })),
+        "fireworks" => Some(Box::new(Fireworks { seed })),
+        "matrix" => Some(Box::new(MatrixRain { seed })),
+        "none" => None,
+        other => {
+            warn!(animation = other, "unknown completion animation, using flash");
+            Some(Box::new(Flash))
+        }
+    }
+}
+
+/// Names accepted for `flash_screen` in the configuration
+pub const SCREEN_FLASH_NAMES: [&str; 3] = ["off", "invert", "flood"];
+
+/// How many times the whole screen flashes
+const SCREEN_FLASHES: u32 = 3;
+
+/// Length of one flash and of the gap after it
+const SCREEN_FLASH_PHASE: Duration = Duration::from_millis(400);
+
+/// Flashing the whole terminal when a countdown finishes, to catch the eye
+/// from across the room
+#[derive(Debug, Clone, Copy)]
+pub enum ScreenFlash {
+    /// Swap the foreground and background colors of every cell
+    Invert,
+    /// Fill the background with the alert color
+    Flood,
+}
+
+impl ScreenFlash {
+    /// Look up the configured screen flash; `None` means the screen doesn't flash
+    pub fn from_config(config: &Config) -> Option<Self> {
+        match config.flash_screen.to_lowercase().as_str() {
+            "off" => None,
+            "invert" => Some(ScreenFlash::Invert),
+            "flood" => Some(ScreenFlash::Flood),
+            other => {
+                warn!(flash = other, "unknown screen flash, not flashing");
+                None
+            }
+        }
+    }
+
+    /// How long the flashing lasts
+    pub fn duration(self) -> Duration {
+        SCREEN_FLASH_PHASE * SCREEN_FLASHES * 2
+    }
+
+    /// Flash `area` if `elapsed` falls on one of the flashes
+    pub fn render(self, elapsed: Duration, color: Color, area: Rect, buf: &mut Buffer) {
+        let phase = elapsed.as_millis() / SCREEN_FLASH_PHASE.as_millis();
+        if !phase.is_multiple_of(2) || elapsed >= self.duration() {
+            return;
+        }
+
+        for position in area.positions() {
+            if let Some(cell) = buf.cell_mut(position) {
+                match self {
+                    ScreenFlash::Invert => {
+                        cell.modifier.insert(Modifier::REVERSED);
+                    }
+                    ScreenFlash::Flood => {
+                        cell.set_bg(color);
+                        // Keep text in the alert color readable
+                        if cell.fg == color {
+                            cell.set_fg(Color::Black);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Blink the banner on and off
+struct Flash;
+
+impl Animation for Flash {
+    fn duration(&self) -> Duration {
+        Duration::from_millis(2500)
+    }
+
+    fn render(&self, elapsed: Duration, area: Rect, buf: &mut Buffer) {
+        if !(elapsed.as_millis() / 500).is_multiple_of(2) {
+            for position in area.positions() {
+                if let Some(cell) = buf.cell_mut(position) {
+                    cell.reset();
+                }
+            }
+        }
+    }
+}
+
+/// Colored scraps drifting down the screen
+struct Confetti {
+    seed: u64,
+}
+
+impl Animation for Confetti {
+    fn duration(&self) -> Duration {
+        Duration::from_secs(4)
+    }
+
+    fn render(&self, elapsed: Duration, area: Rect, buf: &mut Buffer) {
+        const SCRAPS: [char; 6] = ['*', 'o', '+', 'x', '~', '%'];
+        let t = elapsed.as_secs_f64();
+        let pieces = (area.width as u64 * area.height as u64 / 12).max(1);
+
+        for piece in 0..pieces {
+            let noise = hash(self.seed ^ piece);
+            let speed = 4.0 + (noise >> 8 & 7) as f64;
+            let start_y = -(((noise >> 16) % area.height.max(1) as u64) as f64);
+            let sway = (t * 3.0 + (noise >> 24 & 15) as f64).sin();
+            let x = (noise % area.width.max(1) as u64) as f64 + sway;
+            let y = start_y + speed * t;
+            let glyph = SCRAPS[(noise >> 32) as usize % SCRAPS.len()];
+            let color = COLORS[(noise >> 40) as usize % COLORS.len()];
+            plot(buf, area, x, y, glyph, color);
+        }
+    }
+}
+
+/// Bursts of sparks spreading out and falling away
+struct Fireworks {
+    seed: u64,
+}
+
+impl Animation for Fireworks {
+    fn duration(&self) -> Duration {
+        Duration::from_secs(4)
+    }
+
+    fn render(&self, elapsed: Duration, area: Rect, buf: &mut Buffer) {
+        const BURSTS: u64 = 7;
+        const SPARKS: u64 = 16;
+        const BURST_INTERVAL: f64 = 0.45;
+        const BURST_LIFE: f64 = 1.2;
+        let t = elapsed.as_secs_f64();
+
+        for burst in 0..BURSTS {
+            let age = t - burst as f64 * BURST_INTERVAL;
+            if !(0.0..BURST_LIFE).contains(&age) {
+                continue;
+            }
+
+            let noise = hash(self.seed ^ burst);
+            let center_x = area.width as f64 * (0.15 + 0.7 * (noise & 0xff) as f64 / 255.0);
+            let center_y = area.height as f64 * (0.15 + 0.5 * (noise >> 8 & 0xff) as f64 / 255.0);
+            let color = COLORS[(noise >> 16) as usize % COLORS.len()];
+            let glyph = match age {
+                age if age < 0.4 => '*',
+                age if age < 0.8 => '+',
+                _ => '.',
+            };
+
+            let radius = age * 10.0;
+            for spark in 0..SPARKS {
+                let angle = spark as f64 * TAU / SPARKS as f64;
+                // Cells are about twice as tall as they are wide
+                let x = center_x + radius * angle.cos() * 2.0;
+                let y = center_y + radius * angle.sin() + 3.0 * age * age;
+                plot(buf, area, x, y, glyph, color);
+            }
+        }
+    }
+}
+
+/// Green characters raining down the screen
+struct MatrixRain {
+    seed: u64,
+}
+
+impl Animation for MatrixRain {
+    fn duration(&self) -> Duration {
+        Duration::from_secs(4)
+    }
+
+    fn render(&self, elapsed: Duration, area: Rect, buf: &mut Buffer) {
+        const GLYPHS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz@#$%&*+=<>";
+        let t = elapsed.as_secs_f64();
+        // Characters in the trails change a few times a second
+        let flicker = (t * 8.0) as u64;
+
+        for column in 0..area.width as u64 {
+            let noise = hash(self.seed ^ column);
+            let speed = 10.0 + (noise & 15) as f64;
+            let head = speed * t - ((noise >> 8) % (area.height as u64 * 2 + 1)) as f64;
+            let trail = 4.0 + (noise >> 16 & 7) as f64;
+
+            for row in 0..area.height as u64 {
+                let behind = head - row as f64;
+                if !(0.0..trail).contains(&behind) {
+                    continue;
+                }
+                let glyph = GLYPHS[hash(noise ^ row << 20 ^ flicker) as usize % GLYPHS.len()] as char;
+                let color = if behind < 1.0 {
+                    Color::White
+                } else if behind < trail / 2.0 {
+                    Color::LightGreen
+                } else {
+                    Color::Green
+                };
+                plot(buf, area, column as f64, row as f64, glyph, color);
+            }
+        }
+    }
+}
+
+/// Draw `glyph` at a position relative to `area`, if it falls inside it
+fn plot(buf: &mut Buffer, area: Rect, x: f64, y: f64, glyph: char, color: Color) {
+    if x < 0.0 || y < 0.0 || x >= area.width as f64 || y >= area.height as f64 {
+        return;
+    }
+    if let Some(cell) = buf.cell_mut((area.x + x as u16, area.y + y as u16)) {
+        cell.set_char(glyph).set_fg(color);
+    }
+}
+
+/// Scramble a number into pseudo-random bits (SplitMix64)
+fn hash(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
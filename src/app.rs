@@ -0,0 +1,516 @@
+// src/app.rs
+//! Module for the async event loop shared by all timer modes
+//!
+//! Ticks, keyboard events and control commands each arrive on their own
+//! source and are joined by a single `select!` loop, so nothing blocks the
+//! display while waiting for input.
+//!
+//! The loop itself only sees an [`EventSource`] and a [`Render`] target, so
+//! tests can drive a mode with scripted input at virtual times and draw it
+//! into an in-memory terminal.
+
+use crate::{
+    accessible::Announcer,
+    caps,
+    clock::{self, Clock, SystemClock, Ticker},
+    config::{AccessibilitySettings, Config, SignalSettings},
+    control::{Command, ControlFifo, ControlRequest, ControlServer, Reply, SignalControl},
+    kiosk::{self, Chord},
+    locale::messages,
+    modes::{Flow, Mode, Status, Summary, QUIT_KEY},
+    profile::FrameProfile,
+    theme::{ThemeSchedule, ThemeSwitch},
+    tui::{self, Tui},
+    ui::{self, Chrome, ChromeFade, Screen},
+};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use ratatui::{backend::Backend, Terminal};
+use tracing::{debug, error, info, warn};
+use std::{
+    future::Future,
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, watch};
+
+/// Longest the summary stays on screen without a key press
+const SUMMARY_SHOWN: Duration = Duration::from_secs(10);
+
+/// How a run is summed up once it ends (`exit_summary`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitSummary {
+    /// Show the summary on screen until a key is pressed, then print it
+    #[default]
+    Screen,
+    /// Only print the summary
+    Print,
+    /// Only print the mode's closing message
+    Off,
+}
+
+impl ExitSummary {
+    /// Names accepted for `exit_summary` in the configuration
+    pub const NAMES: [&'static str; 3] = ["screen", "print", "off"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "screen" => Some(ExitSummary::Screen),
+            "print" => Some(ExitSummary::Print),
+            "off" => Some(ExitSummary::Off),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        ExitSummary::from_name(&config.exit_summary).unwrap_or_else(|| {
+            warn!(setting = config.exit_summary, "unknown exit summary");
+            eprintln!("Unknown exit_summary: {}. Using screen.", config.exit_summary);
+            ExitSummary::default()
+        })
+    }
+}
+
+/// Options that apply to the event loop regardless of mode
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    /// Listen for control commands on this socket
+    pub control_socket: Option<PathBuf>,
+    
+    /// Read control commands written to this named pipe
+    pub control_fifo: Option<PathBuf>,
+    
+    /// Control commands run on SIGUSR1 and SIGUSR2
+    pub signals: SignalSettings,
+    
+    /// Narrate the timer as plain text instead of drawing the full-screen interface
+    pub accessible: Option<AccessibilitySettings>,
+    
+    /// Fade the instructions after a while without a key press
+    pub fade_chrome: Option<ChromeFade>,
+    
+    /// Time each frame and report the render times on exit
+    pub profile: bool,
+    
+    /// Print nothing on exit but the frame profile (`--quiet`)
+    pub quiet: bool,
+    
+    /// Print the summary as a JSON object on exit, and nothing else (`--json-summary`)
+    pub json_summary: bool,
+    
+    /// Switch themes with the `t` key
+    pub themes: Option<ThemeSwitch>,
+    
+    /// Switch between the day and night themes as the time of day passes
+    pub theme_schedule: Option<ThemeSchedule>,
+    
+    /// Ask before `q` quits a running countdown or work session
+    pub confirm_quit: bool,
+    
+    /// How to sum the run up once it ends
+    pub summary: ExitSummary,
+    
+    /// Ignore every key but this chord, which quits for good (`--kiosk`)
+    pub kiosk: Option<Chord>,
+    
+    /// Serve status and commands over HTTP on this address
+    #[cfg(feature = "http")]
+    pub serve: Option<std::net::SocketAddr>,
+}
+
+/// Something for the event loop to react to
+pub enum Input {
+    /// Time to update the mode and redraw
+    Tick,
+    /// A key press, resize or other terminal event
+    Terminal(Event),
+    /// A command from the control socket, fifo, signals or HTTP server
+    Control(ControlRequest),
+}
+
+/// Where the event loop's input comes from: the real terminal and clock, or a
+/// script played at virtual times in tests
+pub trait EventSource {
+    /// The time as the event loop should see it
+    fn now(&self) -> Instant;
+
+    /// Wait for the next input, ticking every `refresh_rate`. None once the
+    /// input has run out.
+    fn next(&mut self, refresh_rate: Duration) -> impl Future<Output = io::Result<Option<Input>>>;
+}
+
+/// Terminal events, ticks and control commands from the real world
+struct LiveEvents<C: Clock> {
+    clock: C,
+    ticker: Ticker,
+    events: EventStream,
+    control_rx: mpsc::Receiver<ControlRequest>,
+}
+
+impl<C: Clock> LiveEvents<C> {
+    fn new(clock: C, refresh_rate: Duration, control_rx: mpsc::Receiver<ControlRequest>) -> Self {
+        let ticker = Ticker::new(clock.now(), refresh_rate);
+        LiveEvents { clock, ticker, events: EventStream::new(), control_rx }
+    }
+}
+
+impl<C: Clock> EventSource for LiveEvents<C> {
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    async fn next(&mut self, refresh_rate: Duration) -> io::Result<Option<Input>> {
+        // Modes can tick faster for a while, e.g. to play an animation
+        let tick_due = self.ticker.due(refresh_rate, self.clock.now());
+
+        tokio::select! {
+            _ = self.clock.sleep_until(tick_due) => {
+                self.ticker.ticked(self.clock.now());
+                Ok(Some(Input::Tick))
+            }
+            event = self.events.next() => match event {
+                Some(Ok(event)) => Ok(Some(Input::Terminal(event))),
+                Some(Err(e)) => Err(e),
+                None => Ok(None),
+            },
+            Some(request) = self.control_rx.recv() => Ok(Some(Input::Control(request))),
+        }
+    }
+}
+
+/// What the event loop adds to a frame beyond what the mode draws
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderContext {
+    /// How the instructions and other text around the timer are drawn
+    pub chrome: Chrome,
+    /// Whether to ask if quitting is meant
+    pub confirming_quit: bool,
+}
+
+/// Where each frame is drawn
+pub trait Render {
+    fn render<M: Mode>(&mut self, mode: &M, now: Instant, context: RenderContext) -> io::Result<()>;
+
+    /// Bytes sent to the terminal so far, where they are counted, for `--profile`
+    fn bytes_written(&self) -> u64 {
+        0
+    }
+}
+
+impl<B: Backend> Render for Terminal<B> {
+    fn render<M: Mode>(&mut self, mode: &M, now: Instant, context: RenderContext) -> io::Result<()> {
+        self.draw(|frame| {
+            mode.draw(&mut Screen::new(frame, context.chrome), now);
+            if context.confirming_quit {
+                ui::draw_quit_prompt(frame);
+            }
+            caps::downgrade_buffer(frame.buffer_mut());
+        })
+        .map(|_| ())
+    }
+}
+
+/// Where each frame goes: the full-screen interface or plain-text announcements
+enum Output {
+    Tui(Tui),
+    Announcer(Announcer),
+}
+
+impl Output {
+    fn init(accessible: Option<&AccessibilitySettings>) -> io::Result<Self> {
+        match accessible {
+            Some(settings) => {
+                // Raw mode is still needed to read single key presses
+                crossterm::terminal::enable_raw_mode()?;
+                Ok(Output::Announcer(Announcer::new(settings)))
+            }
+            None => Ok(Output::Tui(tui::init()?)),
+        }
+    }
+
+    fn restore(&self) -> io::Result<()> {
+        match self {
+            Output::Tui(_) => tui::restore(),
+            Output::Announcer(_) => crossterm::terminal::disable_raw_mode(),
+        }
+    }
+}
+
+impl Render for Output {
+    fn render<M: Mode>(&mut self, mode: &M, now: Instant, context: RenderContext) -> io::Result<()> {
+        match self {
+            Output::Tui(terminal) => terminal.render(mode, now, context),
+            Output::Announcer(announcer) => announcer.update(&mode.status(now)),
+        }
+    }
+
+    fn bytes_written(&self) -> u64 {
+        match self {
+            Output::Tui(terminal) => terminal.backend().writer().written(),
+            Output::Announcer(_) => 0,
+        }
+    }
+}
+
+/// What the event loop keeps up to date besides the display
+#[derive(Default)]
+pub struct Observers {
+    /// Receives the state after each frame, for clients watching the control socket
+    pub status: Option<watch::Sender<Status>>,
+    
+    /// Fades the instructions after a while without a key press
+    pub fade_chrome: Option<ChromeFade>,
+    
+    /// Collects frame timings for `--profile`
+    pub profile: Option<FrameProfile>,
+    
+    /// Switches themes when `t` is pressed
+    pub themes: Option<ThemeSwitch>,
+    
+    /// Switches between the day and night themes
+    pub theme_schedule: Option<ThemeSchedule>,
+    
+    /// Asks before `q` cuts a running timer short
+    pub confirm_quit: bool,
+    
+    /// The only key that does anything, in a kiosk
+    pub kiosk: Option<Chord>,
+    
+    /// Whether the kiosk's quit chord was pressed
+    pub kiosk_released: bool,
+}
+
+impl Observers {
+    /// What to draw along with the mode, `idle` after the last key press
+    fn render_context(&self, idle: Duration, confirming_quit: bool) -> RenderContext {
+        RenderContext {
+            chrome: self.fade_chrome.map_or(Chrome::Shown, |fade| fade.chrome(idle)),
+            confirming_quit,
+        }
+    }
+}
+
+/// Run a mode in the full-screen interface until it quits
+pub async fn run<M: Mode>(mode: &mut M, options: RunOptions) -> io::Result<()> {
+    let (control_tx, control_rx) = mpsc::channel(16);
+    // The state is published after each frame for clients watching the socket
+    let (status_tx, status_rx) = watch::channel(mode.status(clock::now()));
+    // Keep the server alive for the duration of the run; dropping it removes the socket
+    #[cfg(feature = "http")]
+    let _http_server = match options.serve {
+        Some(addr) => Some(crate::http::HttpServer::bind(addr, control_tx.clone()).await?),
+        None => None,
+    };
+    let _signal_control = SignalControl::listen(&options.signals, control_tx.clone())?;
+    let _control_fifo = match options.control_fifo {
+        Some(path) => Some(ControlFifo::open(path, control_tx.clone())?),
+        None => None,
+    };
+    let _control_server = match options.control_socket {
+        Some(path) => Some(ControlServer::bind(path, control_tx, status_rx)?),
+        None => None,
+    };
+
+    let mut output = Output::init(options.accessible.as_ref())?;
+    let key_release = mode.wants_key_release();
+    if key_release {
+        tui::report_key_release()?;
+    }
+    let mut events = LiveEvents::new(SystemClock, mode.refresh_rate(), control_rx);
+    let mut observers = Observers {
+        status: Some(status_tx),
+        fade_chrome: options.fade_chrome,
+        profile: options.profile.then(FrameProfile::default),
+        themes: options.themes,
+        theme_schedule: options.theme_schedule,
+        confirm_quit: options.confirm_quit,
+        kiosk: options.kiosk,
+        kiosk_released: false,
+    };
+    let started = clock::now();
+    let mut result = event_loop(&mut output, mode, &mut events, &mut observers).await;
+    // Give control connections a chance to deliver their final reply
+    tokio::task::yield_now().await;
+
+    let summary = Summary { duration: clock::now() - started, ..mode.summary(clock::now()) };
+    if let (Ok(()), ExitSummary::Screen, Output::Tui(terminal)) = (&result, options.summary, &mut output) {
+        result = show_summary(terminal, &mut events, &mode.exit_message(), &summary).await;
+    }
+
+    // Cleanup
+    if key_release {
+        tui::stop_key_release()?;
+    }
+    output.restore()?;
+    if let Err(e) = &result {
+        error!(error = %e, "terminal error");
+    }
+    result?;
+
+    info!(status = %mode.status(clock::now()), "timer exited");
+    if observers.kiosk_released {
+        // Tells the kiosk not to start the timer again
+        std::process::exit(kiosk::RELEASED);
+    }
+
+    if options.json_summary {
+        let status = mode.status(clock::now());
+        println!("{}", summary.to_json(status.mode, status.finished));
+    } else if !options.quiet {
+        println!("{}", mode.exit_message());
+        if options.summary != ExitSummary::Off {
+            for line in summary.lines(messages()) {
+                println!("  {}", line);
+            }
+        }
+    }
+    if let Some(themes) = &observers.themes {
+        themes.offer_save()?;
+    }
+    if let Some(profile) = observers.profile {
+        info!(
+            frames = profile.frames(),
+            average_us = profile.average_time().as_micros() as u64,
+            average_bytes = profile.average_bytes(),
+            "frame profile"
+        );
+        eprintln!("{}", profile);
+    }
+    Ok(())
+}
+
+/// Show the run's summary until a key is pressed, or for [`SUMMARY_SHOWN`]
+async fn show_summary<S: EventSource>(
+    terminal: &mut Tui,
+    events: &mut S,
+    title: &str,
+    summary: &Summary,
+) -> io::Result<()> {
+    let figures = summary.lines(messages());
+    let until = events.now() + SUMMARY_SHOWN;
+    while events.now() < until {
+        terminal.draw(|frame| ui::draw_summary(frame, title, &figures))?;
+        match events.next(SUMMARY_SHOWN).await? {
+            Some(Input::Terminal(Event::Key(key))) if key.kind == KeyEventKind::Press => break,
+            None => break,
+            // Ticks, resizes and commands only need a redraw
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Feed input from `events` to the mode and draw it to `output` until the
+/// mode quits or the input runs out
+pub async fn event_loop<M: Mode, S: EventSource, R: Render>(
+    output: &mut R,
+    mode: &mut M,
+    events: &mut S,
+    observers: &mut Observers,
+) -> io::Result<()> {
+    let mut last_key = events.now();
+    let mut confirming_quit = false;
+
+    loop {
+        // Draw changing digits in between the mode's own ticks
+        let refresh_rate = if mode.animating() {
+            mode.refresh_rate().min(ui::TRANSITION_FRAME)
+        } else {
+            mode.refresh_rate()
+        };
+        let Some(input) = events.next(refresh_rate).await? else {
+            return Ok(());
+        };
+        let now = events.now();
+        let flow = match input {
+            Input::Tick => mode.on_tick(now),
+            Input::Terminal(Event::Key(key)) if key.kind == KeyEventKind::Press && observers.kiosk.is_some() => {
+                if observers.kiosk.is_some_and(|chord| chord.matches(&key)) {
+                    observers.kiosk_released = true;
+                    Flow::Quit
+                } else {
+                    Flow::Continue
+                }
+            }
+            Input::Terminal(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                last_key = now;
+                if confirming_quit {
+                    // y quits, as does Ctrl+C; any other key carries on
+                    confirming_quit = false;
+                    if is_quit_answer(&key) {
+                        Flow::Quit
+                    } else {
+                        Flow::Continue
+                    }
+                } else if observers.confirm_quit
+                    && key.code == KeyCode::Char(QUIT_KEY)
+                    && !mode.typing()
+                    && mode.guards_quit(now)
+                {
+                    confirming_quit = true;
+                    Flow::Continue
+                } else {
+                    match &mut observers.themes {
+                        Some(themes) if is_theme_key(&key) && !mode.typing() => {
+                            mode.set_theme(themes.next_theme());
+                            Flow::Continue
+                        }
+                        _ => mode.on_key(key, now),
+                    }
+                }
+            }
+            Input::Terminal(Event::Key(key)) if key.kind == KeyEventKind::Release => mode.on_key_release(key, now),
+            // Resizes and other events only need a redraw
+            Input::Terminal(_) => Flow::Continue,
+            Input::Control(request) => handle_control(mode, request, now),
+        };
+
+        if flow == Flow::Quit {
+            return Ok(());
+        }
+
+        let schedule = observers.theme_schedule.as_mut();
+        if let Some(theme) = schedule.and_then(|schedule| schedule.update(now, observers.themes.as_mut())) {
+            mode.set_theme(theme);
+        }
+        let context = observers.render_context(now - last_key, confirming_quit);
+        let started = Instant::now();
+        let written = output.bytes_written();
+        output.render(mode, now, context)?;
+        if let Some(profile) = &mut observers.profile {
+            profile.record(started.elapsed(), output.bytes_written() - written);
+        }
+        if let Some(status) = &observers.status {
+            status.send_replace(mode.status(now));
+        }
+    }
+}
+
+/// Whether `key` answers yes to quitting: `y`, or Ctrl+C, which always quits
+fn is_quit_answer(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('y') || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Whether `key` switches to the next theme
+fn is_theme_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('t') && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+/// Apply a control command to the mode and send the reply back to the client
+fn handle_control<M: Mode>(mode: &mut M, request: ControlRequest, now: Instant) -> Flow {
+    debug!(command = %request.command, "control command received");
+    let (flow, reply) = match request.command {
+        Command::Status => (Flow::Continue, Reply::Status(mode.status(now))),
+        Command::Quit => (Flow::Quit, Reply::Ok),
+        command => match mode.on_command(command, now) {
+            // Let the mode react to the new state straight away
+            Ok(Flow::Continue) => (mode.on_tick(now), Reply::Ok),
+            Ok(Flow::Quit) => (Flow::Quit, Reply::Ok),
+            Err(e) => (Flow::Continue, Reply::Error(e)),
+        },
+    };
+
+    let _ = request.reply.send(reply);
+    flow
+}
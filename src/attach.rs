@@ -0,0 +1,173 @@
+// src/attach.rs
+//! Module for `clockit attach`: a read-only mirror of a timer running in
+//! another terminal
+//!
+//! The mirror connects to the timer's control socket, asks it to `watch`,
+//! and redraws every state it is sent. The terminal running the timer stays
+//! the one in control; here every key but q is ignored.
+
+use crate::{
+    config::Config,
+    digit::DigitOptions,
+    locale::messages,
+    modes::{instruction_lines, instructions, is_quit_key},
+    theme::Theme,
+    tui,
+    ui::{self, Badge, Chrome, DigitState, Screen},
+};
+use ratatui::{style::Style, text::Line};
+use serde::Deserialize;
+use std::{io, path::Path};
+
+/// The state of the mirrored timer, as sent by its control socket
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Mirrored {
+    mode: String,
+    label: Option<String>,
+    time: String,
+    paused: bool,
+    finished: bool,
+    prompt: Option<String>,
+}
+
+/// How the mirror ended
+#[cfg(unix)]
+enum Detach {
+    /// The user pressed q
+    Quit,
+    /// The timer exited, closing the connection
+    TimerExited,
+}
+
+/// Mirror the timer listening on the control socket at `path` until q is
+/// pressed or the timer exits
+#[cfg(unix)]
+pub async fn attach(path: &Path, config: &Config) -> io::Result<()> {
+    use crossterm::event::{Event, EventStream, KeyEventKind};
+    use futures::StreamExt;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixStream,
+    };
+    use tracing::{info, warn};
+
+    let stream = match UnixStream::connect(path).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            println!(
+                "No timer is listening on {} ({}). Start one with --control-socket.",
+                path.display(),
+                e
+            );
+            return Ok(());
+        }
+    };
+    let (reader, mut writer) = stream.into_split();
+    writer.write_all(b"watch\n").await?;
+    let mut lines = BufReader::new(reader).lines();
+    info!(path = ?path, "attached to timer");
+
+    let view = View::new(config);
+    let mut terminal = tui::init()?;
+    let mut events = EventStream::new();
+    let mut state = None;
+
+    let result = loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => match serde_json::from_str::<Mirrored>(&line) {
+                    Ok(mirrored) => state = Some(mirrored),
+                    Err(e) => warn!(line = %line, error = %e, "unreadable timer state"),
+                },
+                Ok(None) => break Ok(Detach::TimerExited),
+                Err(e) => break Err(e),
+            },
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press && is_quit_key(&key) => {
+                    break Ok(Detach::Quit);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e),
+                None => break Ok(Detach::Quit),
+            },
+        }
+
+        if let Some(state) = &state {
+            if let Err(e) = terminal.draw(|frame| view.draw(&mut Screen::new(frame, Chrome::Shown), state)) {
+                break Err(e);
+            }
+        }
+    };
+
+    tui::restore()?;
+    match result? {
+        Detach::Quit => println!("Detached."),
+        Detach::TimerExited => println!("The timer has exited."),
+    }
+    Ok(())
+}
+
+/// Mirroring needs the control socket, which relies on Unix domain sockets
+#[cfg(not(unix))]
+pub async fn attach(_path: &Path, _config: &Config) -> io::Result<()> {
+    println!("clockit attach is only supported on Unix.");
+    Ok(())
+}
+
+/// Draws the mirrored state with the local theme and digits
+struct View {
+    theme: Theme,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    instructions: String,
+}
+
+impl View {
+    fn new(config: &Config) -> Self {
+        View {
+            theme: Theme::from_config(config),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            instructions: instructions(&config.instructions.attach, &[]),
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, state: &Mirrored) {
+        let mut header = instruction_lines(self.instructions.clone());
+        if let Some(label) = &state.label {
+            header.push(Line::raw(label.clone()));
+        }
+        if state.paused {
+            header.extend(ui::status_line(&[Badge::Paused], &self.theme));
+        }
+        if let Some(prompt) = &state.prompt {
+            header.push(Line::raw(prompt.clone()));
+        }
+        ui::draw_timer(
+            frame,
+            &header,
+            self.theme.ui_text,
+            &state.time,
+            self.digits,
+            &self.digit_state,
+            self.time_style(state),
+        );
+    }
+
+    /// The digit style the mirrored mode would use
+    fn time_style(&self, state: &Mirrored) -> Style {
+        if state.finished {
+            return self.theme.times_up;
+        }
+        match state.mode.as_str() {
+            "stopwatch" | "cube" => self.theme.stopwatch,
+            "pomodoro" | "flowtime" if state.label.as_deref() == Some(messages().break_session.as_str()) => {
+                self.theme.pomodoro_break
+            }
+            "pomodoro" | "flowtime" => self.theme.pomodoro_work,
+            _ => self.theme.countdown,
+        }
+    }
+}
@@ -0,0 +1,217 @@
+// src/caps.rs
+//! Module for detecting what the terminal is able to display
+//!
+//! Everything here is guessed from the environment at startup, so it works
+//! before the terminal is taken over. `clockit --caps` prints what was found
+//! and what Clockit does about it.
+
+use ratatui::{buffer::Buffer, style::Color};
+use std::{env, io, sync::OnceLock};
+
+/// Best-effort check for whether box-drawing and other Unicode characters
+/// will render at their expected width
+pub fn supports_unicode() -> bool {
+    if cfg!(windows) {
+        // The legacy console host draws box characters at inconsistent widths;
+        // Windows Terminal, VS Code and ConEmu all handle them properly
+        return env::var_os("WT_SESSION").is_some()
+            || env::var("TERM_PROGRAM").is_ok_and(|program| program == "vscode")
+            || env::var("ConEmuANSI").is_ok_and(|value| value == "ON");
+    }
+
+    match locale() {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        // No locale configured at all; assume a modern terminal
+        None => true,
+    }
+}
+
+/// The first locale variable that is set, which decides the character encoding
+fn locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Whether the terminal says it can show 24-bit color, as most modern ones do
+pub fn supports_true_color() -> bool {
+    env::var("COLORTERM").is_ok_and(|value| {
+        let value = value.to_lowercase();
+        value == "truecolor" || value == "24bit"
+    }) || env::var_os("WT_SESSION").is_some()
+}
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// The 16 named colors
+    Basic,
+    /// The 256-color palette
+    Indexed,
+    /// Any 24-bit color
+    TrueColor,
+}
+
+/// Colors the terminal can show, detected once
+pub fn color_support() -> ColorSupport {
+    static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+    *SUPPORT.get_or_init(|| {
+        if supports_true_color() {
+            ColorSupport::TrueColor
+        } else if env::var("TERM").is_ok_and(|term| term.contains("256color")) {
+            ColorSupport::Indexed
+        } else {
+            ColorSupport::Basic
+        }
+    })
+}
+
+/// Whether the terminal has an alternate screen to draw on, leaving the
+/// shell's scrollback alone. Dumb terminals, Emacs shells and the Linux
+/// console don't.
+pub fn supports_alternate_screen() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+    if env::var_os("INSIDE_EMACS").is_some() {
+        return false;
+    }
+    match env::var("TERM") {
+        Ok(term) => !matches!(term.as_str(), "" | "dumb" | "linux" | "cons25" | "emacs"),
+        Err(_) => false,
+    }
+}
+
+/// `color` as close as the terminal can show it
+pub fn downgrade(color: Color, support: ColorSupport) -> Color {
+    match (color, support) {
+        (_, ColorSupport::TrueColor) => color,
+        (Color::Rgb(r, g, b), ColorSupport::Indexed) => Color::Indexed(cube_index(r, g, b)),
+        (Color::Rgb(r, g, b), ColorSupport::Basic) => nearest_named(r, g, b),
+        (Color::Indexed(index), ColorSupport::Basic) => {
+            let (r, g, b) = indexed_rgb(index);
+            nearest_named(r, g, b)
+        }
+        _ => color,
+    }
+}
+
+/// Swap any colors in a drawn frame that the terminal can't show for the
+/// closest ones it can
+pub fn downgrade_buffer(buf: &mut Buffer) {
+    let support = color_support();
+    if support == ColorSupport::TrueColor {
+        return;
+    }
+    for cell in &mut buf.content {
+        cell.fg = downgrade(cell.fg, support);
+        cell.bg = downgrade(cell.bg, support);
+    }
+}
+
+/// Levels of each channel in the 6×6×6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The palette index of the cube color nearest to an RGB color
+fn cube_index(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| (i32::from(CUBE_LEVELS[*i]) - i32::from(channel)).abs())
+            .unwrap_or(0) as u8
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+/// Approximate RGB values of a color in the 256-color palette
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => NAMED[usize::from(index)].1,
+        16..=231 => {
+            let index = index - 16;
+            let level = |n: u8| CUBE_LEVELS[usize::from(n)];
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// The 16 named colors in palette order, with the RGB values xterm uses
+const NAMED: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The named color nearest to an RGB color
+fn nearest_named(r: u8, g: u8, b: u8) -> Color {
+    let distance = |(nr, ng, nb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, nr) + d(g, ng) + d(b, nb)
+    };
+    NAMED.iter().min_by_key(|(_, rgb)| distance(*rgb)).map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// Print what was detected about the terminal and how Clockit adapts to it,
+/// for `clockit --caps`
+pub fn print_report() -> io::Result<()> {
+    let var = |name: &str| env::var(name).unwrap_or_else(|_| "(not set)".to_string());
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+
+    println!("TERM:             {}", var("TERM"));
+    println!("COLORTERM:        {}", var("COLORTERM"));
+    println!("Locale:           {}", locale().unwrap_or_else(|| "(not set)".to_string()));
+    match crossterm::terminal::size() {
+        Ok((columns, rows)) => println!("Size:             {}x{}", columns, rows),
+        Err(e) => println!("Size:             unknown ({})", e),
+    }
+    println!();
+
+    let unicode = supports_unicode();
+    println!("Unicode:          {}", yes_no(unicode));
+    let colors = match color_support() {
+        ColorSupport::TrueColor => "24-bit",
+        ColorSupport::Indexed => "256",
+        ColorSupport::Basic => "16",
+    };
+    println!("Colors:           {}", colors);
+    let alternate_screen = supports_alternate_screen();
+    println!("Alternate screen: {}", yes_no(alternate_screen));
+    println!("Key release:      {}", yes_no(crate::tui::supports_key_release()));
+    println!();
+
+    if !unicode {
+        println!("Drawing banners and separators with plain ASCII.");
+    }
+    match color_support() {
+        ColorSupport::TrueColor => {}
+        ColorSupport::Indexed => println!("Showing 24-bit colors with the nearest of the 256-color palette."),
+        ColorSupport::Basic => println!("Showing 24-bit and 256-palette colors with the nearest named color."),
+    }
+    if !alternate_screen {
+        println!("Drawing over the current screen and clearing it on exit.");
+    }
+    if !crate::tui::supports_key_release() {
+        println!("--cube starts on the next press of space instead of when it is let go.");
+    }
+    Ok(())
+}
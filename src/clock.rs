@@ -0,0 +1,123 @@
+// src/clock.rs
+//! Module for the clock the timers read
+//!
+//! Everything that starts or measures a timer asks [`now`] rather than
+//! `Instant::now()`, and the event loop waits for its ticks through a
+//! [`Clock`]. Tests install a [`MockClock`] and move it forward instantly,
+//! so blinking, alerts and session changes can be checked without sleeping.
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// A source of the current time that can be waited on
+pub trait Clock {
+    fn now(&self) -> Instant;
+
+    /// Wait until `deadline`
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()>;
+}
+
+/// The real clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        tokio::time::sleep_until(deadline.into()).await;
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, and
+/// waiting on it moves it straight to the deadline.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl MockClock {
+    /// A clock stopped at the current time
+    pub fn new() -> Self {
+        MockClock { now: Rc::new(Cell::new(Instant::now())) }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    /// Make [`now`] read this clock on the current thread, so timers created
+    /// afterwards start at its time
+    pub fn install(&self) {
+        INSTALLED.with(|installed| *installed.borrow_mut() = Some(self.clone()));
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        if deadline > self.now.get() {
+            self.now.set(deadline);
+        }
+    }
+}
+
+/// When the event loop should next tick. Ticks that fall behind, e.g.
+/// while the machine was asleep, are skipped rather than played catch-up.
+#[derive(Debug, Clone, Copy)]
+pub struct Ticker {
+    next: Instant,
+    period: Duration,
+}
+
+impl Ticker {
+    /// Tick at `start`, then every `period`
+    pub fn new(start: Instant, period: Duration) -> Self {
+        Ticker { next: start, period }
+    }
+
+    /// When the next tick is due. A new `period` starts over with a tick straight away.
+    pub fn due(&mut self, period: Duration, now: Instant) -> Instant {
+        if period != self.period {
+            *self = Ticker::new(now, period);
+        }
+        self.next
+    }
+
+    /// Note that the tick due was handled at `now`
+    pub fn ticked(&mut self, now: Instant) {
+        self.next += self.period;
+        if self.next <= now {
+            self.next = now + self.period;
+        }
+    }
+}
+
+thread_local! {
+    static INSTALLED: RefCell<Option<MockClock>> = const { RefCell::new(None) };
+}
+
+/// The time as the timers see it: the system clock, unless a [`MockClock`]
+/// has been installed on this thread
+pub fn now() -> Instant {
+    INSTALLED.with(|installed| match &*installed.borrow() {
+        Some(clock) => clock.now(),
+        None => Instant::now(),
+    })
+}
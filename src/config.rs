@@ -1,7 +1,13 @@
 // src/config.rs
+use crate::{config_cmd, history::SessionKind};
 use crossterm::style::Color;
 use serde::{Deserialize, Serialize};
-use std::{fs, io, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
 
 /// Represents the color scheme for different timer elements
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,6 +35,18 @@ pub struct ColorScheme {
     /// Color for Pomodoro break sessions
     #[serde(default = "default_pomodoro_break_color")]
     pub pomodoro_break: String,
+
+    /// Color for the colons and dots between digits (empty uses the digit color)
+    #[serde(default)]
+    pub separator: String,
+
+    /// Color for the hundredths of a second (empty uses the digit color)
+    #[serde(default)]
+    pub fraction: String,
+
+    /// Color for the state badges like PAUSED (empty uses the time's up color)
+    #[serde(default)]
+    pub badge: String,
 }
 
 fn default_countdown_color() -> String {
@@ -77,6 +95,73 @@ pub struct PomodoroSettings {
     /// Refresh rate in milliseconds for the pomodoro timer
     #[serde(default = "default_pomodoro_refresh_rate")]
     pub refresh_rate: u64,
+    
+    /// Pause media players during breaks and resume them for work (Linux, needs playerctl)
+    #[serde(default = "default_media_control")]
+    pub media_control: bool,
+    
+    /// Minutes added to a work session each time `e` is pressed
+    #[serde(default = "default_extend_by")]
+    pub extend_by: u64,
+    
+    /// Seconds before the end of a break to warn that it is ending (0 turns the warning off)
+    #[serde(default = "default_break_warning")]
+    pub break_warning: u64,
+    
+    /// Ring the terminal bell with the break warning
+    #[serde(default = "default_break_warning_sound")]
+    pub break_warning_sound: bool,
+    
+    /// Seconds the screens between sessions wait for a key before carrying on
+    /// by themselves (0 waits for a key)
+    #[serde(default = "default_auto_continue")]
+    pub auto_continue: u64,
+    
+    /// Time spent since the run began, shown in a corner: all (breaks
+    /// included), work (work sessions only) or off
+    #[serde(default = "default_total_time")]
+    pub total_time: String,
+    
+    /// Phases to run in each cycle, by name; empty means alternating work and break
+    #[serde(default)]
+    pub sequence: Vec<String>,
+    
+    /// Phases that can be named in `sequence`, besides the built-in `work` and `break`
+    #[serde(default)]
+    pub phases: BTreeMap<String, PhaseSettings>,
+}
+
+/// A named phase that can appear in a Pomodoro `sequence`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PhaseSettings {
+    /// Duration in minutes
+    pub duration: u64,
+    
+    /// Whether the phase is `work` or a `break`, for history, idle detection and media control
+    #[serde(default = "default_phase_kind")]
+    pub kind: SessionKind,
+    
+    /// Color of the digits, instead of the pomodoro_work or pomodoro_break color
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    
+    /// Ring the terminal bell when the phase ends, instead of following sound_enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound: Option<bool>,
+}
+
+impl PomodoroSettings {
+    /// Whether `name` can be used in `sequence`
+    pub fn has_phase(&self, name: &str) -> bool {
+        self.phases.contains_key(name) || BUILT_IN_PHASES.contains(&name)
+    }
+}
+
+/// Phase names that work in `sequence` without being defined in `phases`
+pub const BUILT_IN_PHASES: [&str; 2] = ["work", "break"];
+
+fn default_phase_kind() -> SessionKind {
+    SessionKind::Work
 }
 
 fn default_pomodoro_work_duration() -> u64 {
@@ -99,6 +184,558 @@ fn default_pomodoro_refresh_rate() -> u64 {
     200
 }
 
+fn default_media_control() -> bool {
+    false
+}
+
+fn default_extend_by() -> u64 {
+    5
+}
+
+fn default_break_warning() -> u64 {
+    60
+}
+
+fn default_break_warning_sound() -> bool {
+    true
+}
+
+fn default_auto_continue() -> u64 {
+    30
+}
+
+fn default_total_time() -> String {
+    "all".to_string()
+}
+
+/// Represents settings for the plain-text accessible mode
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccessibilitySettings {
+    /// Seconds between "time remaining" announcements
+    #[serde(default = "default_announce_interval")]
+    pub announce_interval: u64,
+    
+    /// Speak state changes with the system text-to-speech tool, if one is installed
+    #[serde(default = "default_speech")]
+    pub speech: bool,
+}
+
+fn default_announce_interval() -> u64 {
+    60
+}
+
+fn default_speech() -> bool {
+    true
+}
+
+/// Control commands run when the process receives SIGUSR1 or SIGUSR2
+/// (e.g. `pkill -USR1 clockit`). An empty command ignores the signal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignalSettings {
+    /// Run on SIGUSR1
+    #[serde(default = "default_usr1")]
+    pub usr1: String,
+    
+    /// Run on SIGUSR2
+    #[serde(default = "default_usr2")]
+    pub usr2: String,
+}
+
+fn default_usr1() -> String {
+    "toggle".to_string()
+}
+
+fn default_usr2() -> String {
+    "add 60".to_string()
+}
+
+/// What each kind of event plays: `bell` (the terminal bell), `none`, a
+/// built-in tone (beep, chime, ding or alarm) or the path of a sound file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SoundSettings {
+    /// Volume of tones and sound files, from 0 to 100
+    #[serde(default = "default_volume")]
+    pub volume: u8,
+    
+    /// A Pomodoro work session ends
+    #[serde(default = "default_event_sound")]
+    pub work_end: String,
+    
+    /// A Pomodoro or Flowtime break ends
+    #[serde(default = "default_event_sound")]
+    pub break_end: String,
+    
+    /// A kitchen timer, standup turn, plan block or stopwatch warm-up runs out, or
+    /// the last Pomodoro session ends
+    #[serde(default = "default_event_sound")]
+    pub complete: String,
+    
+    /// Another `--chime-every` interval passes
+    #[serde(default = "default_event_sound")]
+    pub chime: String,
+    
+    /// A stopwatch reaches its goal
+    #[serde(default = "default_event_sound")]
+    pub goal: String,
+    
+    /// A countdown crosses one of its alerts
+    #[serde(default = "default_event_sound")]
+    pub alert: String,
+    
+    /// A metronome beats, or a stopwatch warm-up counts down a second
+    #[serde(default = "default_beat_sound")]
+    pub beat: String,
+}
+
+impl SoundSettings {
+    /// Play nothing for any event, not even the bell
+    pub fn mute(&mut self) {
+        for sound in [
+            &mut self.work_end,
+            &mut self.break_end,
+            &mut self.complete,
+            &mut self.chime,
+            &mut self.goal,
+            &mut self.alert,
+            &mut self.beat,
+        ] {
+            *sound = "none".to_string();
+        }
+    }
+}
+
+fn default_volume() -> u8 {
+    100
+}
+
+fn default_event_sound() -> String {
+    "bell".to_string()
+}
+
+fn default_beat_sound() -> String {
+    "click".to_string()
+}
+
+/// Represents settings for Flowtime mode (`--flowtime`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FlowtimeSettings {
+    /// Length of a break as a share of the work time before it, e.g. 0.2 for 1/5
+    #[serde(default = "default_flowtime_break_fraction")]
+    pub break_fraction: f64,
+    
+    /// Ring the terminal bell when a break ends
+    #[serde(default = "default_flowtime_sound")]
+    pub sound_enabled: bool,
+}
+
+fn default_flowtime_break_fraction() -> f64 {
+    0.2
+}
+
+fn default_flowtime_sound() -> bool {
+    false
+}
+
+/// Represents settings for clock mode (`--clock`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClockSettings {
+    /// Show the date and ISO week beneath the time
+    #[serde(default = "default_clock_show_date")]
+    pub show_date: bool,
+    
+    /// How to write the date, in strftime format (%V is the ISO week)
+    #[serde(default = "default_clock_date_format")]
+    pub date_format: String,
+}
+
+fn default_clock_show_date() -> bool {
+    true
+}
+
+fn default_clock_date_format() -> String {
+    "%A %-d %B %Y, week %V".to_string()
+}
+
+/// Represents settings for the metronome (`--metronome`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetronomeSettings {
+    /// Beats in a bar, the first of which flashes in the `times_up` color
+    #[serde(default = "default_metronome_beats_per_bar")]
+    pub beats_per_bar: u32,
+    
+    /// Play `sounds.beat` on every beat, not just flash the screen
+    #[serde(default = "default_metronome_sound")]
+    pub sound_enabled: bool,
+}
+
+fn default_metronome_beats_per_bar() -> u32 {
+    4
+}
+
+fn default_metronome_sound() -> bool {
+    true
+}
+
+/// Represents where `clockit track` sends tracked time (`export` feature)
+#[cfg(feature = "export")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExportSettings {
+    /// "toggl" or "clockify"; nothing is sent while this is empty
+    #[serde(default)]
+    pub service: String,
+    
+    /// API token from the service's profile page
+    #[serde(default)]
+    pub api_token: String,
+    
+    /// Workspace to add the time entries to
+    #[serde(default)]
+    pub workspace_id: String,
+    
+    /// The service's project ID for each Clockit project name
+    #[serde(default)]
+    pub projects: BTreeMap<String, String>,
+    
+    /// Use this API address instead of the service's, e.g. a regional Clockify server
+    #[serde(default)]
+    pub api_url: String,
+}
+
+/// Represents settings for pausing automatically when the user is away
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IdleSettings {
+    /// How to tell the user is away: "off", "system" (X11/macOS idle time)
+    /// or "heartbeat" (no key pressed in clockit)
+    #[serde(default = "default_idle_detection")]
+    pub detection: String,
+    
+    /// Seconds of idleness before the stopwatch or work session pauses
+    #[serde(default = "default_idle_timeout")]
+    pub timeout: u64,
+}
+
+fn default_idle_detection() -> String {
+    "off".to_string()
+}
+
+fn default_idle_timeout() -> u64 {
+    300
+}
+
+/// Represents settings for checking the system clock before ringing alarms
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClockCheckSettings {
+    /// What to do when the system clock is off: "off" (don't check), "warn"
+    /// or "adjust" (follow the server's time)
+    #[serde(default = "default_clock_check_mode")]
+    pub mode: String,
+    
+    /// NTP server to ask for the time, optionally with a port
+    #[serde(default = "default_clock_check_server")]
+    pub server: String,
+    
+    /// Seconds the system clock may be off before it counts
+    #[serde(default = "default_clock_check_max_skew")]
+    pub max_skew: u64,
+}
+
+fn default_clock_check_mode() -> String {
+    "off".to_string()
+}
+
+fn default_clock_check_server() -> String {
+    "pool.ntp.org".to_string()
+}
+
+fn default_clock_check_max_skew() -> u64 {
+    2
+}
+
+/// Represents settings for switching themes by the time of day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThemeScheduleSettings {
+    /// Theme by day; empty keeps `theme`
+    #[serde(default)]
+    pub day: String,
+    
+    /// Theme by night; empty keeps `theme`
+    #[serde(default)]
+    pub night: String,
+    
+    /// When day and night start, like "07:00/19:00"; empty follows the sun
+    /// at `latitude` and `longitude`
+    #[serde(default = "default_theme_switch")]
+    pub switch: String,
+    
+    /// Where to follow the sun from, in degrees north
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    
+    /// Where to follow the sun from, in degrees east
+    #[serde(default)]
+    pub longitude: Option<f64>,
+}
+
+fn default_theme_switch() -> String {
+    "07:00/19:00".to_string()
+}
+
+/// Instruction lines shown above the timers. `{quit_key}` is replaced with
+/// the key that exits; an empty line hides the instructions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstructionSettings {
+    /// The countdown and its time's up screen
+    #[serde(default = "default_countdown_instructions")]
+    pub countdown: String,
+    
+    /// The stopwatch
+    #[serde(default = "default_stopwatch_instructions")]
+    pub stopwatch: String,
+    
+    /// Pomodoro work sessions ({extend_by} is the time e adds)
+    #[serde(default = "default_pomodoro_work_instructions")]
+    pub pomodoro_work: String,
+    
+    /// Pomodoro breaks
+    #[serde(default = "default_pomodoro_break_instructions")]
+    pub pomodoro_break: String,
+    
+    /// Between Pomodoro sessions
+    #[serde(default = "default_pomodoro_next_instructions")]
+    pub pomodoro_next: String,
+    
+    /// Flowtime work ({earned} is the break earned so far)
+    #[serde(default = "default_flowtime_work_instructions")]
+    pub flowtime_work: String,
+    
+    /// Flowtime breaks
+    #[serde(default = "default_flowtime_break_instructions")]
+    pub flowtime_break: String,
+    
+    /// After a Flowtime break
+    #[serde(default = "default_flowtime_next_instructions")]
+    pub flowtime_next: String,
+    
+    /// Standup meetings
+    #[serde(default = "default_standup_instructions")]
+    pub standup: String,
+    
+    /// Kitchen timers
+    #[serde(default = "default_kitchen_instructions")]
+    pub kitchen: String,
+    
+    /// Stopwatches run with `clockit multi-stopwatch`
+    #[serde(default = "default_multi_stopwatch_instructions")]
+    pub multi_stopwatch: String,
+    
+    /// The metronome
+    #[serde(default = "default_metronome_instructions")]
+    pub metronome: String,
+    
+    /// Clock mode
+    #[serde(default = "default_clock_instructions")]
+    pub clock: String,
+    
+    /// The speedcubing timer between solves
+    #[serde(default = "default_cube_instructions")]
+    pub cube: String,
+    
+    /// A timer mirrored with `clockit attach`
+    #[serde(default = "default_attach_instructions")]
+    pub attach: String,
+    
+    /// An alarm ringing under `clockit alarms run`
+    #[serde(default = "default_alarm_instructions")]
+    pub alarm: String,
+    
+    /// A day of time blocks run with `clockit plan`
+    #[serde(default = "default_plan_instructions")]
+    pub plan: String,
+}
+
+fn default_countdown_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_stopwatch_instructions() -> String {
+    "Press l to mark a lap, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_pomodoro_work_instructions() -> String {
+    "Press e to add {extend_by}, i to note an interruption, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_pomodoro_break_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_pomodoro_next_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit, any other key to continue".to_string()
+}
+
+fn default_flowtime_work_instructions() -> String {
+    "Press b to take a break ({earned} earned), {quit_key} to exit".to_string()
+}
+
+fn default_flowtime_break_instructions() -> String {
+    "Press b to end the break early, {quit_key} to exit".to_string()
+}
+
+fn default_flowtime_next_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit, any other key to start working".to_string()
+}
+
+fn default_standup_instructions() -> String {
+    "Press space for the next speaker, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_kitchen_instructions() -> String {
+    "Press a to add a timer, x to clear finished ones, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_multi_stopwatch_instructions() -> String {
+    "Press 1-9 to start or stop a stopwatch, space to pause all, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_metronome_instructions() -> String {
+    "Press space to tap the tempo, +/- to change it, p to pause, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_clock_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_cube_instructions() -> String {
+    "Press space to inspect, {quit_key} or Ctrl+C to exit".to_string()
+}
+
+fn default_attach_instructions() -> String {
+    "Read-only mirror, press {quit_key} or Ctrl+C to detach".to_string()
+}
+
+fn default_alarm_instructions() -> String {
+    "Press any key to stop the alarm".to_string()
+}
+
+fn default_plan_instructions() -> String {
+    "Press {quit_key} or Ctrl+C to exit".to_string()
+}
+
+/// How each mode writes out its time, e.g. "%M:%S.%c" (see `time_format`).
+/// Empty keeps the mode's usual format.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TimeFormatSettings {
+    #[serde(default)]
+    pub countdown: String,
+    
+    #[serde(default)]
+    pub stopwatch: String,
+    
+    #[serde(default)]
+    pub pomodoro: String,
+    
+    #[serde(default)]
+    pub flowtime: String,
+    
+    #[serde(default)]
+    pub standup: String,
+}
+
+/// Shell commands to run when timer events happen
+/// Each runs with CLOCKIT_EVENT, CLOCKIT_MODE, CLOCKIT_LABEL, CLOCKIT_DURATION
+/// and CLOCKIT_CYCLE set in its environment
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HooksSettings {
+    /// Run when a Pomodoro work session starts
+    #[serde(default)]
+    pub on_work_start: Option<String>,
+    
+    /// Run when a Pomodoro break starts
+    #[serde(default)]
+    pub on_break_start: Option<String>,
+    
+    /// Run when a Pomodoro work or break session ends
+    #[serde(default)]
+    pub on_session_end: Option<String>,
+    
+    /// Run when a countdown finishes, a stopwatch reaches its goal, or all Pomodoro cycles are done
+    #[serde(default)]
+    pub on_complete: Option<String>,
+    
+    /// Run when the timer is paused
+    #[serde(default)]
+    pub on_pause: Option<String>,
+    
+    /// Run when the timer is resumed
+    #[serde(default)]
+    pub on_resume: Option<String>,
+    
+    /// Run when a countdown crosses one of its alerts
+    #[serde(default)]
+    pub on_alert: Option<String>,
+}
+
+/// A point in a countdown to alert at, and what to do when it is crossed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertSettings {
+    /// Share of the countdown elapsed ("50%") or time remaining ("5:00")
+    pub at: String,
+    
+    /// Ring the terminal bell
+    #[serde(default = "default_alert_sound")]
+    pub sound: bool,
+    
+    /// Switch the digits to this color from then on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    
+    /// Show this message above the timer from then on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    
+    /// Show the message as the next checkpoint, with the time until it, before
+    /// the alert is crossed
+    #[serde(default)]
+    pub checkpoint: bool,
+}
+
+impl AlertSettings {
+    /// An alert that just rings the bell
+    pub fn at(at: &str) -> Self {
+        AlertSettings {
+            at: at.to_string(),
+            sound: default_alert_sound(),
+            color: None,
+            message: None,
+            checkpoint: false,
+        }
+    }
+}
+
+fn default_alert_sound() -> bool {
+    true
+}
+
+/// A time of day to ring an alarm at, for `clockit alarms run`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlarmSettings {
+    /// When to ring: a time ("7:30"), or days and a time ("daily at 17:30",
+    /// "weekdays at 9:00", "mon,thu at 18:15")
+    pub at: String,
+    
+    /// What the alarm is for, shown while it rings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    
+    /// What to play: bell, none, a built-in tone or the path of a sound file
+    #[serde(default = "default_alarm_sound")]
+    pub sound: String,
+}
+
+fn default_alarm_sound() -> String {
+    "alarm".to_string()
+}
+
 /// Configuration for the Clockit application
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -106,10 +743,54 @@ pub struct Config {
     #[serde(default)]
     pub colors: ColorScheme,
     
+    /// Built-in palette to use instead of `colors` ("default" uses `colors`)
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    
+    /// How the digit color changes over time: static, rainbow or pulse
+    #[serde(default = "default_color_mode")]
+    pub color_mode: String,
+    
     /// Whether to use a blinking effect for the time separator
     #[serde(default = "default_blink_separator")]
     pub blink_separator: bool,
     
+    /// Blank columns between the big digits
+    #[serde(default = "default_digit_spacing")]
+    pub digit_spacing: u16,
+    
+    /// How separators are drawn: colon, dots, blocks or thin
+    #[serde(default = "default_separator_style")]
+    pub separator_style: String,
+    
+    /// How digits change: none, roll (scroll up to the next) or flip
+    #[serde(default = "default_digit_transition")]
+    pub digit_transition: String,
+
+    /// How TIME'S UP and speaker names are lettered: small or large
+    #[serde(default = "default_banner_font")]
+    pub banner_font: String,
+    
+    /// Draw the big digits this many times larger, shrinking to fit the terminal
+    #[serde(default = "default_scale")]
+    pub scale: u16,
+    
+    /// Draw the digits as large as the terminal allows, ignoring `scale`
+    #[serde(default = "default_auto_fit")]
+    pub auto_fit: bool,
+    
+    /// Show the time elapsed and the share done beneath a countdown's digits
+    #[serde(default)]
+    pub show_elapsed: bool,
+    
+    /// Countdown times started with 1 to 9 once a countdown has finished
+    #[serde(default = "default_countdown_presets")]
+    pub countdown_presets: Vec<String>,
+    
+    /// How the share of a countdown or Pomodoro session run is shown: bar, ring or none
+    #[serde(default = "default_progress_style")]
+    pub progress_style: String,
+    
     /// Refresh rate in milliseconds for the countdown timer
     #[serde(default = "default_countdown_refresh_rate")]
     pub countdown_refresh_rate: u64,
@@ -118,15 +799,214 @@ pub struct Config {
     #[serde(default = "default_stopwatch_refresh_rate")]
     pub stopwatch_refresh_rate: u64,
     
+    /// Smallest unit the stopwatch shows: seconds, centis or millis
+    #[serde(default = "default_stopwatch_precision")]
+    pub stopwatch_precision: String,
+    
+    /// Whether starting one of `clockit multi-stopwatch`'s stopwatches stops
+    /// the one running
+    #[serde(default = "default_multi_stopwatch_exclusive")]
+    pub multi_stopwatch_exclusive: bool,
+    
+    /// Whether a countdown suspended with --name keeps counting while away:
+    /// frozen or wall_clock
+    #[serde(default = "default_countdown_resume")]
+    pub countdown_resume: String,
+    
     /// Pomodoro timer settings
     #[serde(default)]
     pub pomodoro: PomodoroSettings,
+    
+    /// Flowtime settings
+    #[serde(default)]
+    pub flowtime: FlowtimeSettings,
+    
+    /// Metronome settings
+    #[serde(default)]
+    pub metronome: MetronomeSettings,
+    
+    /// Clock mode settings
+    #[serde(default)]
+    pub clock: ClockSettings,
+    
+    /// Time tracking export settings
+    #[cfg(feature = "export")]
+    #[serde(default)]
+    pub export: ExportSettings,
+    
+    /// Accessible mode settings
+    #[serde(default)]
+    pub accessibility: AccessibilitySettings,
+    
+    /// Control commands for SIGUSR1 and SIGUSR2
+    #[serde(default)]
+    pub signals: SignalSettings,
+    
+    /// What each kind of event plays, and how loud
+    #[serde(default)]
+    pub sounds: SoundSettings,
+    
+    /// Only draw plain ASCII characters, even if the terminal seems to support Unicode
+    #[serde(default = "default_ascii_only")]
+    pub ascii_only: bool,
+    
+    /// Animation played when a countdown finishes: flash, confetti, fireworks, matrix or none
+    #[serde(default = "default_completion_animation")]
+    pub completion_animation: String,
+    
+    /// Flash the whole screen when a countdown finishes: off, invert or flood
+    #[serde(default = "default_flash_screen")]
+    pub flash_screen: String,
+    
+    /// Redraw less and leave out animations to save power: auto (on battery), on or off
+    #[serde(default = "default_power_save")]
+    pub power_save: String,
+    
+    /// What happens to the instructions after a while without a key press: off, dim or hide
+    #[serde(default = "default_fade_chrome")]
+    pub fade_chrome: String,
+    
+    /// Seconds without a key press before the instructions fade
+    #[serde(default = "default_fade_chrome_after")]
+    pub fade_chrome_after: u64,
+    
+    /// Ask before `q` quits a running countdown or Pomodoro work session
+    #[serde(default)]
+    pub confirm_quit: bool,
+    
+    /// How a run is summed up once it ends: screen, print or off
+    #[serde(default = "default_exit_summary")]
+    pub exit_summary: String,
+    
+    /// Keys that quit `--kiosk`, like ctrl+alt+q
+    #[serde(default = "default_kiosk_quit")]
+    pub kiosk_quit: String,
+    
+    /// Save completed Pomodoro sessions for `clockit history`
+    #[serde(default = "default_record_history")]
+    pub record_history: bool,
+    
+    /// Refuse to start when a configuration file has keys Clockit doesn't know
+    #[serde(default)]
+    pub strict_config: bool,
+    
+    /// Language of the phrases on screen, e.g. "de" (empty follows LANG)
+    #[serde(default)]
+    pub language: String,
+    
+    /// Wording of the instructions above each timer
+    #[serde(default)]
+    pub instructions: InstructionSettings,
+    
+    /// How each mode writes out its time
+    #[serde(default)]
+    pub time_format: TimeFormatSettings,
+    
+    /// Commands to run on timer events
+    #[serde(default)]
+    pub hooks: HooksSettings,
+    
+    /// Idle detection settings
+    #[serde(default)]
+    pub idle: IdleSettings,
+    
+    /// Points in a countdown to alert at
+    #[serde(default)]
+    pub alerts: Vec<AlertSettings>,
+    
+    /// Times of day to ring an alarm at under `clockit alarms run`
+    #[serde(default)]
+    pub alarms: Vec<AlarmSettings>,
+    
+    /// Checking the system clock before ringing alarms
+    #[serde(default)]
+    pub clock_check: ClockCheckSettings,
+    
+    /// Switching between a day and a night theme
+    #[serde(default)]
+    pub theme_schedule: ThemeScheduleSettings,
+}
+
+fn default_theme() -> String {
+    "default".to_string()
+}
+
+fn default_color_mode() -> String {
+    "static".to_string()
 }
 
 fn default_blink_separator() -> bool {
     false
 }
 
+fn default_digit_spacing() -> u16 {
+    0
+}
+
+fn default_separator_style() -> String {
+    "colon".to_string()
+}
+
+fn default_digit_transition() -> String {
+    "none".to_string()
+}
+
+fn default_banner_font() -> String {
+    "small".to_string()
+}
+
+fn default_scale() -> u16 {
+    1
+}
+
+fn default_auto_fit() -> bool {
+    false
+}
+
+fn default_ascii_only() -> bool {
+    false
+}
+
+fn default_completion_animation() -> String {
+    "flash".to_string()
+}
+
+fn default_flash_screen() -> String {
+    "off".to_string()
+}
+
+fn default_power_save() -> String {
+    "auto".to_string()
+}
+
+fn default_fade_chrome() -> String {
+    "off".to_string()
+}
+
+fn default_countdown_presets() -> Vec<String> {
+    ["5:00", "10:00", "15:00", "20:00", "25:00", "30:00"].map(str::to_string).to_vec()
+}
+
+fn default_progress_style() -> String {
+    "none".to_string()
+}
+
+fn default_exit_summary() -> String {
+    "screen".to_string()
+}
+
+fn default_kiosk_quit() -> String {
+    "ctrl+alt+q".to_string()
+}
+
+fn default_fade_chrome_after() -> u64 {
+    30
+}
+
+fn default_record_history() -> bool {
+    true
+}
+
 fn default_countdown_refresh_rate() -> u64 {
     200
 }
@@ -135,6 +1015,18 @@ fn default_stopwatch_refresh_rate() -> u64 {
     100
 }
 
+fn default_multi_stopwatch_exclusive() -> bool {
+    true
+}
+
+fn default_stopwatch_precision() -> String {
+    "centis".to_string()
+}
+
+fn default_countdown_resume() -> String {
+    "frozen".to_string()
+}
+
 impl Default for ColorScheme {
     fn default() -> Self {
         ColorScheme {
@@ -144,6 +1036,33 @@ impl Default for ColorScheme {
             ui_text: default_ui_text_color(),
             pomodoro_work: default_pomodoro_work_color(),
             pomodoro_break: default_pomodoro_break_color(),
+            separator: String::new(),
+            fraction: String::new(),
+            badge: String::new(),
+        }
+    }
+}
+
+impl Default for InstructionSettings {
+    fn default() -> Self {
+        InstructionSettings {
+            countdown: default_countdown_instructions(),
+            stopwatch: default_stopwatch_instructions(),
+            pomodoro_work: default_pomodoro_work_instructions(),
+            pomodoro_break: default_pomodoro_break_instructions(),
+            pomodoro_next: default_pomodoro_next_instructions(),
+            flowtime_work: default_flowtime_work_instructions(),
+            flowtime_break: default_flowtime_break_instructions(),
+            flowtime_next: default_flowtime_next_instructions(),
+            standup: default_standup_instructions(),
+            kitchen: default_kitchen_instructions(),
+            multi_stopwatch: default_multi_stopwatch_instructions(),
+            metronome: default_metronome_instructions(),
+            clock: default_clock_instructions(),
+            cube: default_cube_instructions(),
+            attach: default_attach_instructions(),
+            alarm: default_alarm_instructions(),
+            plan: default_plan_instructions(),
         }
     }
 }
@@ -156,6 +1075,105 @@ impl Default for PomodoroSettings {
             cycles: default_pomodoro_cycles(),
             sound_enabled: default_pomodoro_sound(),
             refresh_rate: default_pomodoro_refresh_rate(),
+            media_control: default_media_control(),
+            extend_by: default_extend_by(),
+            break_warning: default_break_warning(),
+            break_warning_sound: default_break_warning_sound(),
+            auto_continue: default_auto_continue(),
+            total_time: default_total_time(),
+            sequence: Vec::new(),
+            phases: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        SoundSettings {
+            volume: default_volume(),
+            work_end: default_event_sound(),
+            break_end: default_event_sound(),
+            complete: default_event_sound(),
+            chime: default_event_sound(),
+            goal: default_event_sound(),
+            alert: default_event_sound(),
+            beat: default_beat_sound(),
+        }
+    }
+}
+
+impl Default for SignalSettings {
+    fn default() -> Self {
+        SignalSettings {
+            usr1: default_usr1(),
+            usr2: default_usr2(),
+        }
+    }
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        AccessibilitySettings {
+            announce_interval: default_announce_interval(),
+            speech: default_speech(),
+        }
+    }
+}
+
+impl Default for FlowtimeSettings {
+    fn default() -> Self {
+        FlowtimeSettings {
+            break_fraction: default_flowtime_break_fraction(),
+            sound_enabled: default_flowtime_sound(),
+        }
+    }
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        ClockSettings {
+            show_date: default_clock_show_date(),
+            date_format: default_clock_date_format(),
+        }
+    }
+}
+
+impl Default for MetronomeSettings {
+    fn default() -> Self {
+        MetronomeSettings {
+            beats_per_bar: default_metronome_beats_per_bar(),
+            sound_enabled: default_metronome_sound(),
+        }
+    }
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        IdleSettings {
+            detection: default_idle_detection(),
+            timeout: default_idle_timeout(),
+        }
+    }
+}
+
+impl Default for ClockCheckSettings {
+    fn default() -> Self {
+        ClockCheckSettings {
+            mode: default_clock_check_mode(),
+            server: default_clock_check_server(),
+            max_skew: default_clock_check_max_skew(),
+        }
+    }
+}
+
+impl Default for ThemeScheduleSettings {
+    fn default() -> Self {
+        ThemeScheduleSettings {
+            day: String::new(),
+            night: String::new(),
+            switch: default_theme_switch(),
+            latitude: None,
+            longitude: None,
         }
     }
 }
@@ -164,52 +1182,135 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             colors: ColorScheme::default(),
+            theme: default_theme(),
+            color_mode: default_color_mode(),
             blink_separator: default_blink_separator(),
+            digit_spacing: default_digit_spacing(),
+            separator_style: default_separator_style(),
+            digit_transition: default_digit_transition(),
+            banner_font: default_banner_font(),
+            scale: default_scale(),
+            auto_fit: default_auto_fit(),
+            show_elapsed: false,
+            countdown_presets: default_countdown_presets(),
+            progress_style: default_progress_style(),
             countdown_refresh_rate: default_countdown_refresh_rate(),
             stopwatch_refresh_rate: default_stopwatch_refresh_rate(),
+            stopwatch_precision: default_stopwatch_precision(),
+            multi_stopwatch_exclusive: default_multi_stopwatch_exclusive(),
+            countdown_resume: default_countdown_resume(),
             pomodoro: PomodoroSettings::default(),
+            flowtime: FlowtimeSettings::default(),
+            metronome: MetronomeSettings::default(),
+            clock: ClockSettings::default(),
+            #[cfg(feature = "export")]
+            export: ExportSettings::default(),
+            accessibility: AccessibilitySettings::default(),
+            signals: SignalSettings::default(),
+            sounds: SoundSettings::default(),
+            ascii_only: default_ascii_only(),
+            completion_animation: default_completion_animation(),
+            flash_screen: default_flash_screen(),
+            power_save: default_power_save(),
+            fade_chrome: default_fade_chrome(),
+            fade_chrome_after: default_fade_chrome_after(),
+            confirm_quit: false,
+            exit_summary: default_exit_summary(),
+            kiosk_quit: default_kiosk_quit(),
+            record_history: default_record_history(),
+            strict_config: false,
+            language: String::new(),
+            instructions: InstructionSettings::default(),
+            time_format: TimeFormatSettings::default(),
+            hooks: HooksSettings::default(),
+            idle: IdleSettings::default(),
+            alerts: Vec::new(),
+            alarms: Vec::new(),
+            clock_check: ClockCheckSettings::default(),
+            theme_schedule: ThemeScheduleSettings::default(),
         }
     }
 }
 
 impl Config {
-    /// Load configuration from a file, or create a default one if not found
-    pub fn load() -> io::Result<Self> {
-        let config_path = get_config_path()?;
+    /// Load configuration from a file, or create a default one if not found.
+    /// `config_flag` is the file given with `--config`, if any; `quiet` keeps
+    /// the notice about a newly written default file to ourselves.
+    pub fn load(config_flag: Option<&Path>, quiet: bool) -> io::Result<Self> {
+        Self::load_with_layers(config_flag, quiet).map(|(config, _)| config)
+    }
+    
+    /// Load configuration, also returning the layers it was built from in order:
+    /// the configuration file, a project file and `CLOCKIT_*` environment variables.
+    /// Layers that were missing or unusable are left out and defaults apply.
+    pub fn load_with_layers(config_flag: Option<&Path>, quiet: bool) -> io::Result<(Self, Vec<ConfigLayer>)> {
+        let config_path = get_config_path(config_flag)?;
         
         if !config_path.exists() {
-            return Ok(Self::create_default_config()?);
+            Self::create_default_config(config_flag, quiet)?;
         }
         
-        match fs::read_to_string(&config_path) {
-            Ok(contents) => {
-                match serde_yaml::from_str(&contents) {
-                    Ok(config) => Ok(config),
-                    Err(e) => {
-                        eprintln!("Error parsing config file: {}. Using defaults.", e);
-                        Ok(Config::default())
+        let mut layers = Vec::new();
+        layers.extend(ConfigLayer::read(LayerKind::File, config_path));
+        if let Some(project_path) = get_project_config_path(config_flag) {
+            layers.extend(ConfigLayer::read(LayerKind::Project, project_path));
+        }
+        layers.extend(ConfigLayer::from_env());
+        
+        // Later layers override the settings they mention
+        let mut merged = serde_yaml::Value::Mapping(Default::default());
+        for layer in &layers {
+            merge_values(&mut merged, &layer.values);
+        }
+        
+        match serde_yaml::from_value::<Config>(merged) {
+            Ok(config) if config.strict_config => {
+                // Typos would otherwise leave settings silently at their defaults
+                let unknown = config_cmd::unknown_keys(config_flag)?;
+                if !unknown.is_empty() {
+                    for problem in &unknown {
+                        eprintln!("{}", problem);
                     }
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unknown keys in the configuration (strict_config is on)",
+                    ));
                 }
-            },
+                Ok((config, layers))
+            }
+            Ok(config) => Ok((config, layers)),
             Err(e) => {
-                eprintln!("Error reading config file: {}. Using defaults.", e);
-                Ok(Config::default())
+                warn!(error = %e, "could not combine config files");
+                eprintln!("Error combining config files: {}. Using defaults.", e);
+                Ok((Config::default(), Vec::new()))
             }
         }
     }
     
     /// Create a default configuration file and return the default config
-    fn create_default_config() -> io::Result<Self> {
-        let config_path = get_config_path()?;
+    fn create_default_config(config_flag: Option<&Path>, quiet: bool) -> io::Result<Self> {
+        let default_config = Config::default();
+        let config_path = default_config.write_file(config_flag)?;
+        info!(path = ?config_path, "created default configuration");
+        if !quiet {
+            println!("Created default configuration at: {:?}", config_path);
+        }
+        
+        Ok(default_config)
+    }
+    
+    /// Write these settings to the configuration file, with comments explaining
+    /// them, in place of whatever it held. Returns the file's path.
+    pub fn write_file(&self, config_flag: Option<&Path>) -> io::Result<PathBuf> {
+        let config_path = get_config_path(config_flag)?;
         
         // Create parent directories if they don't exist
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
         
-        let default_config = Config::default();
-        let yaml = serde_yaml::to_string(&default_config)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let yaml = serde_yaml::to_string(self)
+            .map_err(io::Error::other)?;
         
         // Add helpful comments to the YAML file
         let commented_yaml = format!(
@@ -219,83 +1320,349 @@ impl Config {
             # dark_grey, dark_green, dark_magenta, dark_red, dark_yellow, green, grey,\n\
             # magenta, red, white, yellow\n\
             #\n\
+            # theme: default (uses the colors below), deuteranopia, protanopia, tritanopia\n\
+            #        or night\n\
+            # color_mode: static, rainbow (cycle through the hues) or pulse (fade bright\n\
+            #             and dim), smoothest in terminals with 24-bit color\n\
+            #\n\
             # countdown_refresh_rate: Time in ms between updates for countdown timer\n\
             # stopwatch_refresh_rate: Time in ms between updates for stopwatch\n\
+            # stopwatch_precision: seconds, centis (hundredths) or millis, which also\n\
+            #                      redraws faster\n\
+            # multi_stopwatch_exclusive: Starting one of `clockit multi-stopwatch`'s\n\
+            #                            stopwatches stops the one running\n\
+            # countdown_resume: frozen (pick up with the time that was left) or\n\
+            #                   wall_clock (keep counting down while suspended)\n\
             # blink_separator: Whether to make the colon/separators blink\n\
+            # digit_spacing: Blank columns between the big digits\n\
+            # separator_style: colon, dots, blocks or thin (narrower)\n\
+            # digit_transition: none, roll (digits scroll up to the next) or flip\n\
+            # banner_font: small or large (TIME'S UP and speaker names in big letters\n\
+            #              like the digits)\n\
+            # scale: Draw the digits 2x, 3x... larger (shrinks to fit the terminal)\n\
+            # auto_fit: Draw the digits as large as the terminal allows, ignoring scale\n\
+            # show_elapsed: Show the time elapsed and percentage done under a countdown\n\
+            # countdown_presets: Countdown times started with 1 to 9 once time is up\n\
+            # progress_style: bar, ring (braille dots around the digits) or none; how\n\
+            #                 much of a countdown or Pomodoro session has run\n\
+            # ascii_only: Draw banners with plain ASCII instead of box-drawing characters\n\
+            # completion_animation: flash, confetti, fireworks, matrix or none\n\
+            # flash_screen: Flash the whole screen when time runs out: off, invert, or\n\
+            #               flood (fill it with the times_up color)\n\
+            # power_save: auto (while on battery), on or off. Saving power redraws once\n\
+            #             a second and turns off blinking and animations\n\
+            # fade_chrome: off, dim or hide the instructions after fade_chrome_after\n\
+            #              seconds without a key press (any key brings them back)\n\
+            # confirm_quit: Ask \"Really quit? y/n\" before q stops a running countdown or\n\
+            #               work session (Ctrl+C still quits straight away)\n\
+            # exit_summary: screen (show how the run went until a key is pressed, then\n\
+            #               print it), print (only print it) or off\n\
+            # kiosk_quit: Keys that quit --kiosk, the only ones it doesn't ignore\n\
+            # record_history: Save completed Pomodoro sessions for `clockit history`\n\
+            # strict_config: Refuse to start when this file has a key Clockit doesn't know\n\
+            # language: Language of the phrases on screen: en, de, es, fr, or any with a\n\
+            #           catalog in locales/ next to this file (empty follows LANG)\n\
             #\n\
             # Pomodoro settings:\n\
             # work_duration: Duration of work sessions in minutes\n\
             # break_duration: Duration of break sessions in minutes\n\
             # cycles: Number of cycles to run (0 means infinite)\n\
-            # sound_enabled: Play sound when sessions end (not implemented yet)\n\
+            # sound_enabled: Ring the terminal bell when sessions end\n\
             # refresh_rate: Update frequency in milliseconds\n\
+            # media_control: Pause media players during breaks (Linux, needs playerctl)\n\
+            # extend_by: Minutes added to a work session each time e is pressed\n\
+            # break_warning: Seconds before a break ends to warn you (0 = off)\n\
+            # break_warning_sound: Ring the terminal bell with the break warning\n\
+            # auto_continue: Seconds the screens between sessions wait for a key\n\
+            #                before carrying on by themselves (0 = wait for a key)\n\
+            # total_time: Time since the run began, in the corner: all (breaks\n\
+            #             included), work (work sessions only) or off\n\
+            # sequence: Phases to run in each cycle, e.g. [work, short, work, long]\n\
+            #           (empty means alternating work and break)\n\
+            # phases: Named phases for the sequence, each with a duration in minutes,\n\
+            #         kind (work or break), and optional color and sound\n\
+            #\n\
+            # Flowtime settings (used with --flowtime):\n\
+            # break_fraction: Length of a break as a share of the work before it (0.2 = 1/5)\n\
+            # sound_enabled: Ring the terminal bell when a break ends\n\
+            #\n\
+            # Clock settings (used with --clock):\n\
+            # show_date: Show the date and ISO week beneath the time\n\
+            # date_format: How to write the date (strftime, e.g. %A %-d %B %Y, week %V)\n\
+            #\n\
+            # Metronome settings (used with --metronome):\n\
+            # beats_per_bar: Beats in a bar; the first flashes in the times_up color\n\
+            # sound_enabled: Play sounds.beat on every beat\n\
+            #\n\
+            # Accessibility settings (used with --accessible):\n\
+            # announce_interval: Seconds between time remaining announcements\n\
+            # speech: Speak state changes using the system text-to-speech tool\n\
+            #\n\
+            # Signals (control commands run on `pkill -USR1 clockit`, empty to ignore):\n\
+            # usr1: Command for SIGUSR1 (toggle pauses and resumes)\n\
+            # usr2: Command for SIGUSR2 (add 60 adds a minute)\n\
+            #\n\
+            # Sounds (bell, none, a built-in tone: beep, chime, ding, alarm or click, or the\n\
+            # path of a sound file, played with afplay, paplay, pw-play, ffplay or aplay):\n\
+            # volume: Volume of tones and sound files, from 0 to 100\n\
+            # work_end, break_end, complete, chime, goal, alert, beat: What each event plays\n\
+            #\n\
+            # Instructions (the line above each timer; {{quit_key}} is the key that exits,\n\
+            # and an empty line hides it): countdown, stopwatch, pomodoro_work,\n\
+            # pomodoro_break, pomodoro_next, flowtime_work, flowtime_break, flowtime_next,\n\
+            # standup, kitchen, multi_stopwatch, metronome, clock, cube, attach, alarm,\n\
+            # plan\n\
+            #\n\
+            # Time format (how countdown, stopwatch, pomodoro, flowtime and standup write\n\
+            # out the time; empty keeps the usual format): %H/%h hours, %M/%m minutes,\n\
+            # %S/%s seconds (capitals with a leading zero), %c hundredths, %D days;\n\
+            # [...] is only shown when a field inside isn't zero, e.g. \"[%h:]%M:%S\"\n\
+            #\n\
+            # Hooks (shell commands run on timer events, with CLOCKIT_EVENT, CLOCKIT_MODE,\n\
+            # CLOCKIT_LABEL, CLOCKIT_DURATION and CLOCKIT_CYCLE in the environment):\n\
+            # on_work_start, on_break_start, on_session_end, on_complete, on_pause, on_resume,\n\
+            # on_alert\n\
+            #\n\
+            # Idle settings (pause the stopwatch or work session when you walk away):\n\
+            # detection: off, system (X11 idle time via xprintidle, or macOS), or\n\
+            #            heartbeat (no key pressed in clockit)\n\
+            # timeout: Seconds of idleness before pausing\n\
+            #\n\
+            # Alerts (points in a countdown to alert at), for example:\n\
+            # alerts:\n\
+            #   - at: 50%          # share of the countdown elapsed, or time remaining (5:00)\n\
+            #     sound: true      # ring the bell\n\
+            #     color: yellow    # switch the digits to this color\n\
+            #     message: Halfway # show this above the timer\n\
+            #     checkpoint: false # show the message as what comes next until then\n\
+            #\n\
+            # Alarms (rung by `clockit alarms run`), for example:\n\
+            # alarms:\n\
+            #   - at: weekdays at 17:30  # a time, after daily, weekdays, weekends or days\n\
+            #     label: Leave work      # like mon,thu\n\
+            #     sound: alarm           # bell, none, beep, chime, ding, alarm or a file\n\
+            #\n\
+            # Clock check (asks an NTP server for the time when alarms start):\n\
+            # mode: off, warn (say how far off the system clock is) or adjust (ring\n\
+            #       alarms by the server's time)\n\
+            # server: NTP server to ask\n\
+            # max_skew: Seconds the system clock may be off before it counts\n\
+            #\n\
+            # Theme schedule (switches themes by the time of day while a timer runs):\n\
+            # day, night: Theme by day and by night (empty keeps theme)\n\
+            # switch: When day and night start, like 07:00/19:00; empty follows the sun\n\
+            # latitude, longitude: Where to follow the sun from, in degrees north and east\n\
             \n{}", yaml);
         
         fs::write(&config_path, commented_yaml)?;
-        println!("Created default configuration at: {:?}", config_path);
-        
-        Ok(default_config)
+        Ok(config_path)
     }
     
     /// Get the crossterm Color enum from a string color name
     pub fn parse_color(&self, color_name: &str) -> Color {
-        match color_name.to_lowercase().as_str() {
-            "black" => Color::Black,
-            "blue" => Color::Blue,
-            "cyan" => Color::Cyan,
-            "dark_blue" => Color::DarkBlue,
-            "dark_cyan" => Color::DarkCyan,
-            "dark_green" => Color::DarkGreen,
-            "dark_grey" | "dark_gray" => Color::DarkGrey,
-            "dark_magenta" => Color::DarkMagenta,
-            "dark_red" => Color::DarkRed,
-            "dark_yellow" => Color::DarkYellow,
-            "green" => Color::Green,
-            "grey" | "gray" => Color::Grey,
-            "magenta" => Color::Magenta,
-            "red" => Color::Red,
-            "white" => Color::White,
-            "yellow" => Color::Yellow,
-            _ => {
-                eprintln!("Unknown color: {}. Using default.", color_name);
-                Color::Reset
+        color_from_name(color_name).unwrap_or_else(|| {
+            warn!(color = color_name, "unknown color");
+            eprintln!("Unknown color: {}. Using default.", color_name);
+            Color::Reset
+        })
+    }
+}
+
+/// Look up a color by name, returning `None` for names Clockit doesn't know
+pub fn color_from_name(color_name: &str) -> Option<Color> {
+    let color = match color_name.to_lowercase().as_str() {
+        "black" => Color::Black,
+        "blue" => Color::Blue,
+        "cyan" => Color::Cyan,
+        "dark_blue" => Color::DarkBlue,
+        "dark_cyan" => Color::DarkCyan,
+        "dark_green" => Color::DarkGreen,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "dark_magenta" => Color::DarkMagenta,
+        "dark_red" => Color::DarkRed,
+        "dark_yellow" => Color::DarkYellow,
+        "green" => Color::Green,
+        "grey" | "gray" => Color::Grey,
+        "magenta" => Color::Magenta,
+        "red" => Color::Red,
+        "white" => Color::White,
+        "yellow" => Color::Yellow,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Where a layer of settings came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    /// The configuration file
+    File,
+    /// A project-local `.clockit.yaml`
+    Project,
+    /// `CLOCKIT_*` environment variables
+    Environment,
+}
+
+/// Settings read from one source
+pub struct ConfigLayer {
+    pub kind: LayerKind,
+    pub values: serde_yaml::Value,
+}
+
+impl ConfigLayer {
+    /// Read a configuration file, warning and returning `None` if it can't be used
+    fn read(kind: LayerKind, path: PathBuf) -> Option<Self> {
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!(?path, error = %e, "could not read config file");
+                eprintln!("Error reading config file {}: {}. Ignoring it.", path.display(), e);
+                return None;
             }
+        };
+        
+        // Parse into the typed config first so errors point at a line in this file
+        if let Err(e) = serde_yaml::from_str::<Config>(&contents) {
+            warn!(?path, error = %e, "could not parse config file");
+            eprintln!("Error parsing config file {}: {}. Ignoring it.", path.display(), e);
+            return None;
+        }
+        
+        info!(?path, "loaded configuration");
+        match serde_yaml::from_str(&contents) {
+            Ok(serde_yaml::Value::Null) | Err(_) => None, // An empty file sets nothing
+            Ok(values) => Some(ConfigLayer { kind, values }),
         }
     }
     
-    /// Get countdown color
-    pub fn countdown_color(&self) -> Color {
-        self.parse_color(&self.colors.countdown)
-    }
-    
-    /// Get stopwatch color
-    pub fn stopwatch_color(&self) -> Color {
-        self.parse_color(&self.colors.stopwatch)
+    /// Collect settings from environment variables named after their key path,
+    /// e.g. `CLOCKIT_COLORS_COUNTDOWN` for `colors.countdown`
+    fn from_env() -> Option<Self> {
+        let serde_yaml::Value::Mapping(known) = serde_yaml::to_value(Config::default()).ok()? else {
+            return None;
+        };
+        
+        let mut values = serde_yaml::Mapping::new();
+        env_values(&known, &mut Vec::new(), &mut values);
+        if values.is_empty() {
+            return None;
+        }
+        
+        info!(count = values.len(), "loaded configuration from environment");
+        Some(ConfigLayer { kind: LayerKind::Environment, values: serde_yaml::Value::Mapping(values) })
     }
-    
-    /// Get times up color
-    pub fn times_up_color(&self) -> Color {
-        self.parse_color(&self.colors.times_up)
+}
+
+/// Prefix for environment variables that override settings
+const ENV_PREFIX: &str = "CLOCKIT";
+
+/// Name of the environment variable for a key path
+fn env_var_name(path: &[&str]) -> String {
+    std::iter::once(ENV_PREFIX)
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_uppercase()
+}
+
+/// Fill `values` with the settings under `known` that have an environment variable set
+fn env_values<'a>(known: &'a serde_yaml::Mapping, path: &mut Vec<&'a str>, values: &mut serde_yaml::Mapping) {
+    for (key, default) in known {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        path.push(key);
+        
+        if let serde_yaml::Value::Mapping(nested_known) = default {
+            let mut nested = serde_yaml::Mapping::new();
+            env_values(nested_known, path, &mut nested);
+            if !nested.is_empty() {
+                values.insert(key.into(), nested.into());
+            }
+        } else {
+            let name = env_var_name(path);
+            if let Ok(raw) = env::var(&name) {
+                // Booleans and numbers are parsed, anything else is taken as text
+                let value = match default {
+                    serde_yaml::Value::Bool(_) | serde_yaml::Value::Number(_) => {
+                        serde_yaml::from_str(&raw).unwrap_or(serde_yaml::Value::String(raw))
+                    }
+                    _ => serde_yaml::Value::String(raw),
+                };
+                
+                // Check the value on its own so one bad variable doesn't discard the rest
+                let mut single = value.clone();
+                for key in path.iter().rev() {
+                    let mut mapping = serde_yaml::Mapping::new();
+                    mapping.insert((*key).into(), single);
+                    single = mapping.into();
+                }
+                match serde_yaml::from_value::<Config>(single) {
+                    Ok(_) => {
+                        values.insert(key.into(), value);
+                    }
+                    Err(e) => {
+                        warn!(variable = name, error = %e, "invalid config environment variable");
+                        eprintln!("Ignoring {}: {}", name, e);
+                    }
+                }
+            }
+        }
+        
+        path.pop();
     }
-    
-    /// Get UI text color
-    pub fn ui_text_color(&self) -> Color {
-        self.parse_color(&self.colors.ui_text)
+}
+
+/// Overlay `overlay` onto `base`, merging nested mappings key by key
+pub fn merge_values(base: &mut serde_yaml::Value, overlay: &serde_yaml::Value) {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(base), serde_yaml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
     }
-    
-    /// Get Pomodoro work session color
-    pub fn pomodoro_work_color(&self) -> Color {
-        self.parse_color(&self.colors.pomodoro_work)
+}
+
+/// Name of the project-local configuration file
+pub const PROJECT_CONFIG_FILE: &str = ".clockit.yaml";
+
+/// The configuration file chosen with `--config` (`config_flag`) or `CLOCKIT_CONFIG`, if any
+pub fn explicit_config_path(config_flag: Option<&Path>) -> Option<PathBuf> {
+    config_flag.map(Path::to_path_buf).or_else(|| {
+        env::var_os("CLOCKIT_CONFIG")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Find the nearest `.clockit.yaml` in the current directory or one of its parents.
+/// Project files are skipped when a configuration file was chosen explicitly.
+pub fn get_project_config_path(config_flag: Option<&Path>) -> Option<PathBuf> {
+    if explicit_config_path(config_flag).is_some() {
+        return None;
     }
     
-    /// Get Pomodoro break session color
-    pub fn pomodoro_break_color(&self) -> Color {
-        self.parse_color(&self.colors.pomodoro_break)
-    }
+    let current_dir = env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
 }
 
 /// Get the path to the configuration file
-fn get_config_path() -> io::Result<PathBuf> {
+/// `--config` wins over `CLOCKIT_CONFIG`, which wins over the default location
+pub fn get_config_path(config_flag: Option<&Path>) -> io::Result<PathBuf> {
+    if let Some(path) = explicit_config_path(config_flag) {
+        return Ok(path);
+    }
+    
     let config_dir = match dirs::config_dir() {
         Some(dir) => dir.join("clockit"),
         None => {
@@ -307,4 +1674,32 @@ fn get_config_path() -> io::Result<PathBuf> {
     };
     
     Ok(config_dir.join("config.yaml"))
-}
\ No newline at end of file
+}
+/// Set a top-level `key` to `value` in the configuration file, keeping the
+/// rest of the file and its comments as they are. Returns the file's path.
+pub fn save_setting(config_flag: Option<&Path>, key: &str, value: &str) -> io::Result<PathBuf> {
+    let config_path = get_config_path(config_flag)?;
+    let contents = fs::read_to_string(&config_path).unwrap_or_default();
+    let setting = format!("{}: {}", key, value);
+    
+    let mut found = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| match line.strip_prefix(key).map(str::trim_start) {
+            Some(rest) if rest.starts_with(':') && !found => {
+                found = true;
+                setting.clone()
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !found {
+        lines.push(setting);
+    }
+    
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&config_path, lines.join("\n") + "\n")?;
+    Ok(config_path)
+}
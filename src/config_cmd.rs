@@ -0,0 +1,838 @@
+// src/config_cmd.rs
+//! Module for the `clockit config` subcommands
+
+use crate::{
+    alarms::Schedule,
+    alerts::Threshold,
+    animation,
+    app::ExitSummary,
+    control,
+    config::{color_from_name, get_config_path, get_project_config_path, Config, LayerKind, BUILT_IN_PHASES},
+    digit::{BannerFont, SeparatorStyle, Transition},
+    kiosk::Chord,
+    modes::{is_valid_date_format, Precision, TotalTime},
+    ntp::ClockCheck,
+    power::PowerSave,
+    progress::ProgressStyle,
+    sound::{SoundSource, Tone},
+    store::ResumeMode,
+    theme::{is_known_theme, theme_names, ColorMode, DayStart},
+    time_format::TimeFormat,
+    parse_time_string, ui, MAX_TIME_SECS,
+};
+use serde_yaml::{Mapping, Value};
+use std::{
+    env, fmt, fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+    process::Command,
+};
+use tracing::info;
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Built-in default
+    Default,
+    /// Set in the configuration file
+    File,
+    /// Set in a project-local `.clockit.yaml`
+    Project,
+    /// Set with a `CLOCKIT_*` environment variable
+    Environment,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "file"),
+            Source::Project => write!(f, "project"),
+            Source::Environment => write!(f, "env"),
+        }
+    }
+}
+
+/// Print the effective configuration, noting where each value came from
+pub fn show(config_flag: Option<&Path>, quiet: bool) -> io::Result<()> {
+    let (config, layers) = Config::load_with_layers(config_flag, quiet)?;
+    let effective = serde_yaml::to_value(&config).map_err(io::Error::other)?;
+
+    println!("# Configuration file: {}", get_config_path(config_flag)?.display());
+    if let Some(project_path) = get_project_config_path(config_flag) {
+        println!("# Project file: {}", project_path.display());
+    }
+
+    // Later layers override earlier ones, so look for each value from the top down
+    let sources: Vec<(Source, Option<&Value>)> = layers
+        .iter()
+        .rev()
+        .map(|layer| {
+            let source = match layer.kind {
+                LayerKind::File => Source::File,
+                LayerKind::Project => Source::Project,
+                LayerKind::Environment => Source::Environment,
+            };
+            (source, Some(&layer.values))
+        })
+        .collect();
+    let mut lines = Vec::new();
+    if let Value::Mapping(mapping) = &effective {
+        collect_lines(mapping, &sources, 0, &mut lines);
+    }
+
+    // Line the provenance comments up in one column
+    let width = lines.iter().map(|(line, _)| line.chars().count()).max().unwrap_or(0);
+    for (line, source) in lines {
+        match source {
+            Some(source) => println!("{:width$}  # {}", line, source, width = width),
+            None => println!("{}", line),
+        }
+    }
+
+    Ok(())
+}
+
+/// Flatten a mapping into YAML lines, each leaf tagged with its source
+fn collect_lines(
+    mapping: &Mapping,
+    sources: &[(Source, Option<&Value>)],
+    depth: usize,
+    lines: &mut Vec<(String, Option<Source>)>,
+) {
+    let indent = "  ".repeat(depth);
+    for (key, value) in mapping {
+        let key_name = key.as_str().unwrap_or_default();
+        let nested_sources: Vec<(Source, Option<&Value>)> = sources
+            .iter()
+            .map(|(source, values)| (*source, values.and_then(|values| values.get(key_name))))
+            .collect();
+
+        let source = nested_sources
+            .iter()
+            .find(|(_, value)| value.is_some())
+            .map_or(Source::Default, |(source, _)| *source);
+
+        match value {
+            Value::Mapping(nested) => {
+                lines.push((format!("{}{}:", indent, key_name), None));
+                collect_lines(nested, &nested_sources, depth + 1, lines);
+            }
+            // Lists come from a single source as a whole
+            Value::Sequence(items) if !items.is_empty() => {
+                lines.push((format!("{}{}:", indent, key_name), Some(source)));
+                for item in items {
+                    for (i, line) in format_scalar(item).lines().enumerate() {
+                        let marker = if i == 0 { "- " } else { "  " };
+                        lines.push((format!("{}  {}{}", indent, marker, line), None));
+                    }
+                }
+            }
+            _ => {
+                lines.push((format!("{}{}: {}", indent, key_name, format_scalar(value)), Some(source)));
+            }
+        }
+    }
+}
+
+fn format_scalar(value: &Value) -> String {
+    serde_yaml::to_string(value)
+        .map(|text| text.trim_end().to_string())
+        .unwrap_or_default()
+}
+
+/// Print the path of the configuration file
+pub fn path(config_flag: Option<&Path>) -> io::Result<()> {
+    println!("{}", get_config_path(config_flag)?.display());
+    Ok(())
+}
+
+/// Open the configuration file in `$VISUAL` or `$EDITOR`, then validate it
+pub fn edit(config_flag: Option<&Path>, quiet: bool) -> io::Result<()> {
+    let config_path = get_config_path(config_flag)?;
+    if !config_path.exists() {
+        Config::load(config_flag, quiet)?; // Writes the default file
+    }
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+    // Allow editors configured with arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = Command::new(program)
+        .args(words)
+        .arg(&config_path)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("could not run editor {:?}: {}", editor, e)))?;
+
+    if !status.success() {
+        return Err(io::Error::other(format!("editor {:?} exited with {}", editor, status)));
+    }
+
+    validate(config_flag).map(|_| ())
+}
+
+/// Write a new configuration file for `--init-config`, asking for the settings
+/// most often changed unless `yes` takes the defaults. An existing file is only
+/// replaced with `force`.
+pub fn init(config_flag: Option<&Path>, force: bool, yes: bool) -> io::Result<()> {
+    let config_path = get_config_path(config_flag)?;
+    if config_path.exists() && !force {
+        println!(
+            "A configuration file already exists at {}. Use --force to replace it.",
+            config_path.display()
+        );
+        return Ok(());
+    }
+
+    let mut config = Config::default();
+    // Without a terminal to answer on, the defaults are all there is
+    if !yes && io::stdin().is_terminal() {
+        println!("Press Enter to keep the setting in brackets.");
+        ask_settings(&mut config)?;
+    }
+    let path = config.write_file(config_flag)?;
+    info!(path = %path.display(), "initialized configuration");
+    println!("Configuration written to {}", path.display());
+    Ok(())
+}
+
+/// Ask for the digit colors, blinking, Pomodoro lengths and sound
+fn ask_settings(config: &mut Config) -> io::Result<()> {
+    let color = |answer: &str| match color_from_name(answer) {
+        Some(_) => Ok(answer.to_lowercase()),
+        None => Err(format!("`{}` is not a color like green, cyan or yellow", answer)),
+    };
+    let minutes = |answer: &str| match answer.parse::<u64>() {
+        Ok(minutes) if minutes > 0 && minutes <= MAX_TIME_SECS / 60 => Ok(minutes),
+        _ => Err(format!("`{}` is not a number of minutes", answer)),
+    };
+
+    config.colors.countdown = ask("Countdown color", &config.colors.countdown, color)?;
+    config.colors.stopwatch = ask("Stopwatch color", &config.colors.stopwatch, color)?;
+    let blink = if config.blink_separator { "yes" } else { "no" };
+    config.blink_separator = ask("Blink the colons", blink, |answer| match answer.to_lowercase().as_str() {
+        "y" | "yes" => Ok(true),
+        "n" | "no" => Ok(false),
+        _ => Err("Answer yes or no".to_string()),
+    })?;
+    config.pomodoro.work_duration =
+        ask("Pomodoro work minutes", &config.pomodoro.work_duration.to_string(), minutes)?;
+    config.pomodoro.break_duration =
+        ask("Pomodoro break minutes", &config.pomodoro.break_duration.to_string(), minutes)?;
+
+    let question = format!("Sound when a timer finishes (bell, none, {} or a file)", Tone::NAMES.join(", "));
+    let sound = ask(&question, &config.sounds.complete, |answer| {
+        SoundSource::from_setting(answer).map(|_| answer.to_string())
+    })?;
+    let sounds = &mut config.sounds;
+    for setting in [&mut sounds.work_end, &mut sounds.break_end, &mut sounds.complete, &mut sounds.goal, &mut sounds.alert] {
+        setting.clone_from(&sound);
+    }
+    Ok(())
+}
+
+/// Ask `question` until the answer parses, taking `default` for an empty answer
+fn ask<T>(question: &str, default: &str, parse: impl Fn(&str) -> Result<T, String>) -> io::Result<T> {
+    loop {
+        print!("{} [{}]: ", question, default);
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "input ended before the configuration was finished",
+            ));
+        }
+        let answer = answer.trim();
+        match parse(if answer.is_empty() { default } else { answer }) {
+            Ok(value) => return Ok(value),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// A problem found in the configuration file
+struct Issue {
+    line: Option<usize>,
+    message: String,
+}
+
+/// Check the configuration file, and any project file, strictly and report every
+/// problem found. Returns whether the files are valid.
+pub fn validate(config_flag: Option<&Path>) -> io::Result<bool> {
+    let mut valid = validate_file(&get_config_path(config_flag)?)?;
+    if let Some(project_path) = get_project_config_path(config_flag) {
+        valid &= validate_file(&project_path)?;
+    }
+    Ok(valid)
+}
+
+fn validate_file(config_path: &Path) -> io::Result<bool> {
+    if !config_path.exists() {
+        println!("No configuration file at {}; using defaults.", config_path.display());
+        return Ok(true);
+    }
+
+    let contents = fs::read_to_string(config_path)?;
+    let issues = check(&contents);
+    if issues.is_empty() {
+        println!("{}: OK", config_path.display());
+        return Ok(true);
+    }
+
+    for issue in &issues {
+        match issue.line {
+            Some(line) => eprintln!("{}:{}: {}", config_path.display(), line, issue.message),
+            None => eprintln!("{}: {}", config_path.display(), issue.message),
+        }
+    }
+    eprintln!(
+        "{} problem{} found",
+        issues.len(),
+        if issues.len() == 1 { "" } else { "s" }
+    );
+
+    Ok(false)
+}
+
+/// The unknown keys in the configuration files, as `path:line: message`, for `strict_config`
+pub fn unknown_keys(config_flag: Option<&Path>) -> io::Result<Vec<String>> {
+    let mut problems = Vec::new();
+    for path in [Some(get_config_path(config_flag)?), get_project_config_path(config_flag)].into_iter().flatten() {
+        if !path.exists() {
+            continue;
+        }
+        let contents = fs::read_to_string(&path)?;
+        // Files that don't parse are reported and skipped when loading
+        let Ok(file_values) = serde_yaml::from_str::<Value>(&contents) else {
+            continue;
+        };
+
+        let mut issues = Vec::new();
+        let known = serde_yaml::to_value(Config::default()).unwrap_or_default();
+        check_keys(&contents, &file_values, &known, &mut Vec::new(), &mut issues);
+        problems.extend(issues.iter().map(|issue| match issue.line {
+            Some(line) => format!("{}:{}: {}", path.display(), line, issue.message),
+            None => format!("{}: {}", path.display(), issue.message),
+        }));
+    }
+    Ok(problems)
+}
+
+/// Find the problems in the contents of a configuration file
+fn check(contents: &str) -> Vec<Issue> {
+    let file_values: Value = match serde_yaml::from_str(contents) {
+        Ok(Value::Null) => return Vec::new(), // An empty file means all defaults
+        Ok(values) => values,
+        Err(e) => return vec![yaml_issue(e)],
+    };
+
+    let mut issues = Vec::new();
+    let known = serde_yaml::to_value(Config::default()).unwrap_or_default();
+    check_keys(contents, &file_values, &known, &mut Vec::new(), &mut issues);
+
+    match serde_yaml::from_str::<Config>(contents) {
+        Ok(config) => check_values(contents, &config, &mut issues),
+        Err(e) => issues.push(yaml_issue(e)),
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+fn yaml_issue(error: serde_yaml::Error) -> Issue {
+    Issue {
+        line: error.location().map(|location| location.line()),
+        message: error.to_string(),
+    }
+}
+
+/// Report keys in the file that don't correspond to any setting
+fn check_keys<'a>(
+    contents: &str,
+    file_values: &'a Value,
+    known: &Value,
+    path: &mut Vec<&'a str>,
+    issues: &mut Vec<Issue>,
+) {
+    let (Value::Mapping(file_mapping), Value::Mapping(known_mapping)) = (file_values, known) else {
+        return; // Type mismatches are reported by the typed parse
+    };
+    if known_mapping.is_empty() {
+        return; // Maps whose keys are chosen by the user, like pomodoro.phases
+    }
+
+    for (key, value) in file_mapping {
+        let Some(key) = key.as_str() else {
+            issues.push(Issue {
+                line: find_line(contents, path),
+                message: format!("non-string key {}", format_scalar(key)),
+            });
+            continue;
+        };
+
+        path.push(key);
+        match known_mapping.get(key) {
+            Some(known_value) => check_keys(contents, value, known_value, path, issues),
+            None => {
+                let mut message = format!("unknown key `{}`", path.join("."));
+                let known_keys = known_mapping.keys().filter_map(Value::as_str);
+                if let Some(suggestion) = closest(key, known_keys) {
+                    let parents = &path[..path.len() - 1];
+                    let suggestion = parents.iter().copied().chain([suggestion]).collect::<Vec<_>>().join(".");
+                    message.push_str(&format!(" (did you mean `{}`?)", suggestion));
+                }
+                issues.push(Issue { line: find_line(contents, path), message });
+            }
+        }
+        path.pop();
+    }
+}
+
+/// The known key most like `key`, if one is close enough to be a typo
+fn closest<'a>(key: &str, known: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    // Allow about one slip for every three characters
+    let limit = (key.chars().count() / 3).max(1);
+    known
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Number of single-character insertions, deletions or substitutions that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Report settings whose values Clockit can't use
+fn check_values(contents: &str, config: &Config, issues: &mut Vec<Issue>) {
+    if let Ok(Value::Mapping(colors)) = serde_yaml::to_value(&config.colors) {
+        for (key, value) in &colors {
+            let (Some(key), Some(color)) = (key.as_str(), value.as_str()) else {
+                continue;
+            };
+            // Left empty, the separator and fraction colors follow the digits, and
+            // the badges the time's up color
+            if color_from_name(color).is_none() && !color.trim().is_empty() {
+                issues.push(Issue {
+                    line: find_line(contents, &["colors", key]),
+                    message: format!("unknown color `{}` for colors.{}", color, key),
+                });
+            }
+        }
+    }
+
+    let themes = theme_names().collect::<Vec<_>>().join(", ");
+    if !is_known_theme(&config.theme) {
+        issues.push(Issue {
+            line: find_line(contents, &["theme"]),
+            message: format!("unknown theme `{}` (expected {})", config.theme, themes),
+        });
+    }
+
+    let schedule = &config.theme_schedule;
+    for (key, theme) in [("day", &schedule.day), ("night", &schedule.night)] {
+        if !theme.trim().is_empty() && !is_known_theme(theme.trim()) {
+            issues.push(Issue {
+                line: find_line(contents, &["theme_schedule", key]),
+                message: format!("unknown theme `{}` for theme_schedule.{} (expected {})", theme, key, themes),
+            });
+        }
+    }
+    if !schedule.day.trim().is_empty() || !schedule.night.trim().is_empty() {
+        if let Err(e) = DayStart::from_settings(schedule) {
+            issues.push(Issue {
+                line: find_line(contents, &["theme_schedule", "switch"]),
+                message: format!("theme_schedule: {}", e),
+            });
+        }
+    }
+
+    if SeparatorStyle::from_name(&config.separator_style).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["separator_style"]),
+            message: format!(
+                "unknown separator style `{}` (expected {})",
+                config.separator_style,
+                SeparatorStyle::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if Transition::from_name(&config.digit_transition).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["digit_transition"]),
+            message: format!(
+                "unknown digit transition `{}` (expected {})",
+                config.digit_transition,
+                Transition::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if BannerFont::from_name(&config.banner_font).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["banner_font"]),
+            message: format!(
+                "unknown banner font `{}` (expected {})",
+                config.banner_font,
+                BannerFont::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if ColorMode::from_name(&config.color_mode).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["color_mode"]),
+            message: format!(
+                "unknown color mode `{}` (expected {})",
+                config.color_mode,
+                ColorMode::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if Precision::from_name(&config.stopwatch_precision).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["stopwatch_precision"]),
+            message: format!(
+                "unknown stopwatch precision `{}` (expected {})",
+                config.stopwatch_precision,
+                Precision::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if TotalTime::from_name(&config.pomodoro.total_time).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["pomodoro", "total_time"]),
+            message: format!(
+                "unknown pomodoro total time `{}` (expected {})",
+                config.pomodoro.total_time,
+                TotalTime::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if ResumeMode::from_name(&config.countdown_resume).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["countdown_resume"]),
+            message: format!(
+                "unknown countdown resume mode `{}` (expected {})",
+                config.countdown_resume,
+                ResumeMode::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if !animation::NAMES.contains(&config.completion_animation.to_lowercase().as_str()) {
+        issues.push(Issue {
+            line: find_line(contents, &["completion_animation"]),
+            message: format!(
+                "unknown completion animation `{}` (expected {})",
+                config.completion_animation,
+                animation::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if !animation::SCREEN_FLASH_NAMES.contains(&config.flash_screen.to_lowercase().as_str()) {
+        issues.push(Issue {
+            line: find_line(contents, &["flash_screen"]),
+            message: format!(
+                "unknown screen flash `{}` (expected {})",
+                config.flash_screen,
+                animation::SCREEN_FLASH_NAMES.join(", ")
+            ),
+        });
+    }
+
+    if PowerSave::from_name(&config.power_save).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["power_save"]),
+            message: format!(
+                "unknown power save setting `{}` (expected {})",
+                config.power_save,
+                PowerSave::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if ProgressStyle::from_name(&config.progress_style).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["progress_style"]),
+            message: format!(
+                "unknown progress style `{}` (expected {})",
+                config.progress_style,
+                ProgressStyle::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if ExitSummary::from_name(&config.exit_summary).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["exit_summary"]),
+            message: format!(
+                "unknown exit summary `{}` (expected {})",
+                config.exit_summary,
+                ExitSummary::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if let Err(e) = Chord::parse(&config.kiosk_quit) {
+        issues.push(Issue {
+            line: find_line(contents, &["kiosk_quit"]),
+            message: format!("kiosk_quit `{}` is not a key chord like ctrl+alt+q: {}", config.kiosk_quit, e),
+        });
+    }
+
+    if !ui::FADE_CHROME_NAMES.contains(&config.fade_chrome.to_lowercase().as_str()) {
+        issues.push(Issue {
+            line: find_line(contents, &["fade_chrome"]),
+            message: format!(
+                "unknown fade_chrome `{}` (expected {})",
+                config.fade_chrome,
+                ui::FADE_CHROME_NAMES.join(", ")
+            ),
+        });
+    }
+
+    if let Ok(Value::Mapping(formats)) = serde_yaml::to_value(&config.time_format) {
+        for (key, value) in &formats {
+            // Left empty, the mode keeps its usual format
+            let (Some(key), Some(pattern)) = (key.as_str(), value.as_str()) else {
+                continue;
+            };
+            if pattern.trim().is_empty() {
+                continue;
+            }
+            if let Err(e) = TimeFormat::parse(pattern) {
+                issues.push(Issue {
+                    line: find_line(contents, &["time_format", key]),
+                    message: format!("time_format.{}: {}", key, e),
+                });
+            }
+        }
+    }
+
+    for (key, command) in [("usr1", &config.signals.usr1), ("usr2", &config.signals.usr2)] {
+        if command.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = command.parse::<control::Command>() {
+            issues.push(Issue {
+                line: find_line(contents, &["signals", key]),
+                message: format!("signals.{}: {}", key, e),
+            });
+        }
+    }
+
+    let sounds = &config.sounds;
+    for (key, setting) in [
+        ("work_end", &sounds.work_end),
+        ("break_end", &sounds.break_end),
+        ("complete", &sounds.complete),
+        ("chime", &sounds.chime),
+        ("goal", &sounds.goal),
+        ("alert", &sounds.alert),
+        ("beat", &sounds.beat),
+    ] {
+        if let Err(e) = SoundSource::from_setting(setting) {
+            issues.push(Issue {
+                line: find_line(contents, &["sounds", key]),
+                message: format!("sounds.{}: {}", key, e),
+            });
+        }
+    }
+
+    for (i, alert) in config.alerts.iter().enumerate() {
+        if let Err(e) = alert.at.parse::<Threshold>() {
+            issues.push(Issue {
+                line: find_line(contents, &["alerts"]),
+                message: format!("alerts[{}].at: {}", i, e),
+            });
+        }
+        if let Some(color) = alert.color.as_deref().filter(|color| color_from_name(color).is_none()) {
+            issues.push(Issue {
+                line: find_line(contents, &["alerts"]),
+                message: format!("unknown color `{}` for alerts[{}].color", color, i),
+            });
+        }
+    }
+
+    for (i, preset) in config.countdown_presets.iter().enumerate() {
+        let problem = match parse_time_string(preset) {
+            Ok(0) => Some(format!("`{}` needs to be longer than zero", preset)),
+            Ok(_) if i >= 9 => Some(format!("`{}` has no number key; only the first 9 presets are used", preset)),
+            Ok(_) => None,
+            Err(e) => Some(format!("`{}`: {}", preset, e)),
+        };
+        if let Some(problem) = problem {
+            issues.push(Issue {
+                line: find_line(contents, &["countdown_presets"]),
+                message: format!("countdown_presets[{}]: {}", i, problem),
+            });
+        }
+    }
+
+    for (i, alarm) in config.alarms.iter().enumerate() {
+        if let Err(e) = alarm.at.parse::<Schedule>() {
+            issues.push(Issue {
+                line: find_line(contents, &["alarms"]),
+                message: format!("alarms[{}].at: {}", i, e),
+            });
+        }
+        if let Err(e) = SoundSource::from_setting(&alarm.sound) {
+            issues.push(Issue {
+                line: find_line(contents, &["alarms"]),
+                message: format!("alarms[{}].sound: {}", i, e),
+            });
+        }
+    }
+
+    for name in &config.pomodoro.sequence {
+        if !config.pomodoro.has_phase(name) {
+            issues.push(Issue {
+                line: find_line(contents, &["pomodoro", "sequence"]),
+                message: format!(
+                    "unknown phase `{}` in pomodoro.sequence (define it under pomodoro.phases, or use {})",
+                    name,
+                    BUILT_IN_PHASES.join(" or ")
+                ),
+            });
+        }
+    }
+
+    for (name, phase) in &config.pomodoro.phases {
+        if phase.duration == 0 {
+            issues.push(Issue {
+                line: find_line(contents, &["pomodoro", "phases", name]),
+                message: format!("pomodoro.phases.{}.duration must be at least 1 minute", name),
+            });
+        }
+        if let Some(color) = phase.color.as_deref().filter(|color| color_from_name(color).is_none()) {
+            issues.push(Issue {
+                line: find_line(contents, &["pomodoro", "phases", name]),
+                message: format!("unknown color `{}` for pomodoro.phases.{}.color", color, name),
+            });
+        }
+    }
+
+    if !["off", "system", "heartbeat"].contains(&config.idle.detection.to_lowercase().as_str()) {
+        issues.push(Issue {
+            line: find_line(contents, &["idle", "detection"]),
+            message: format!(
+                "unknown idle detection `{}` (expected off, system or heartbeat)",
+                config.idle.detection
+            ),
+        });
+    }
+
+    if ClockCheck::from_name(&config.clock_check.mode).is_none() {
+        issues.push(Issue {
+            line: find_line(contents, &["clock_check", "mode"]),
+            message: format!(
+                "unknown clock check mode `{}` (expected {})",
+                config.clock_check.mode,
+                ClockCheck::NAMES.join(", ")
+            ),
+        });
+    }
+
+    if !is_valid_date_format(&config.clock.date_format) {
+        issues.push(Issue {
+            line: find_line(contents, &["clock", "date_format"]),
+            message: format!("clock.date_format `{}` is not a valid strftime format", config.clock.date_format),
+        });
+    }
+
+    let fraction = config.flowtime.break_fraction;
+    if !(fraction > 0.0 && fraction <= 1.0) {
+        issues.push(Issue {
+            line: find_line(contents, &["flowtime", "break_fraction"]),
+            message: format!("flowtime.break_fraction = {} is out of range (above 0, up to 1)", fraction),
+        });
+    }
+
+    #[cfg(feature = "export")]
+    if crate::export::Service::from_name(&config.export.service).is_err() {
+        issues.push(Issue {
+            line: find_line(contents, &["export", "service"]),
+            message: format!("unknown export service `{}` (expected toggl or clockify)", config.export.service),
+        });
+    }
+
+    // Setting path, value, and the allowed range
+    let ranges: [(&[&str], u64, u64, u64); 16] = [
+        (&["digit_spacing"], config.digit_spacing as u64, 0, 10),
+        (&["scale"], config.scale as u64, 1, 10),
+        (&["countdown_refresh_rate"], config.countdown_refresh_rate, 1, 60_000),
+        (&["stopwatch_refresh_rate"], config.stopwatch_refresh_rate, 1, 60_000),
+        (&["pomodoro", "refresh_rate"], config.pomodoro.refresh_rate, 1, 60_000),
+        (&["pomodoro", "work_duration"], config.pomodoro.work_duration, 1, 1440),
+        (&["pomodoro", "break_duration"], config.pomodoro.break_duration, 1, 1440),
+        (&["pomodoro", "cycles"], config.pomodoro.cycles, 0, 1000),
+        (&["pomodoro", "extend_by"], config.pomodoro.extend_by, 1, 1440),
+        (&["pomodoro", "break_warning"], config.pomodoro.break_warning, 0, 86_400),
+        (&["pomodoro", "auto_continue"], config.pomodoro.auto_continue, 0, 86_400),
+        (&["accessibility", "announce_interval"], config.accessibility.announce_interval, 1, 86_400),
+        (&["idle", "timeout"], config.idle.timeout, 1, 86_400),
+        (&["metronome", "beats_per_bar"], config.metronome.beats_per_bar as u64, 1, 32),
+        (&["fade_chrome_after"], config.fade_chrome_after, 1, 86_400),
+        (&["sounds", "volume"], config.sounds.volume as u64, 0, 100),
+    ];
+    for (path, value, min, max) in ranges {
+        if !(min..=max).contains(&value) {
+            issues.push(Issue {
+                line: find_line(contents, path),
+                message: format!("{} = {} is out of range ({}..={})", path.join("."), value, min, max),
+            });
+        }
+    }
+}
+
+/// Find the 1-based line a nested key is set on by following its indentation
+fn find_line(contents: &str, path: &[&str]) -> Option<usize> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut start = 0;
+    let mut parent_indent = None;
+
+    for key in path {
+        let prefix = format!("{}:", key);
+        let (index, indent) = lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .map(|(index, line)| (index, line, line.len() - line.trim_start().len()))
+            .find(|(_, line, indent)| {
+                let nested = match parent_indent {
+                    Some(parent) => *indent > parent,
+                    None => *indent == 0,
+                };
+                nested && line.trim_start().starts_with(&prefix)
+            })
+            .map(|(index, _, indent)| (index, indent))?;
+
+        start = index + 1;
+        parent_indent = Some(indent);
+    }
+
+    parent_indent.map(|_| start)
+}
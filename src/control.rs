@@ -0,0 +1,442 @@
+// src/control.rs
+//! Module for the remote-control socket
+//!
+//! Each connection sends one command per line (e.g. `pause`, `add 60`) and
+//! receives a single line reply. A connection that sends `watch` instead is
+//! sent the timer's state as a line of JSON every time it is redrawn, which
+//! is how `clockit attach` mirrors a timer.
+//!
+//! The same commands can also be written to a named pipe (`--control-fifo`),
+//! one per line, for scripts that would rather `echo pause > PATH`. Nothing
+//! is sent back through the pipe.
+
+use crate::{config::SignalSettings, modes::Status, MAX_TIME_SECS};
+use std::{fmt, io, path::PathBuf, str::FromStr};
+use tokio::sync::{mpsc, oneshot, watch};
+use tracing::{debug, info, warn};
+
+/// A command that can be sent to a running timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Pause the running timer
+    Pause,
+    /// Resume a paused timer
+    Resume,
+    /// Pause if running, resume if paused
+    Toggle,
+    /// Add the given number of seconds to the current countdown
+    Add(u64),
+    /// Finish the current session immediately
+    Skip,
+    /// Report the current timer state
+    Status,
+    /// Exit clockit
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some(word) => word.to_lowercase(),
+            None => return Err("empty command".to_string()),
+        };
+
+        let command = match command.as_str() {
+            "pause" => Command::Pause,
+            "resume" => Command::Resume,
+            "toggle" => Command::Toggle,
+            "skip" => Command::Skip,
+            "status" => Command::Status,
+            "quit" => Command::Quit,
+            "add" => match words.next().map(str::parse::<u64>) {
+                Some(Ok(secs)) if secs <= MAX_TIME_SECS => Command::Add(secs),
+                Some(Ok(_)) => return Err(format!("at most {} seconds can be added", MAX_TIME_SECS)),
+                _ => return Err("usage: add SECONDS".to_string()),
+            },
+            other => return Err(format!("unknown command: {}", other)),
+        };
+
+        if words.next().is_some() {
+            return Err("unexpected arguments".to_string());
+        }
+
+        Ok(command)
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Pause => write!(f, "pause"),
+            Command::Resume => write!(f, "resume"),
+            Command::Toggle => write!(f, "toggle"),
+            Command::Add(secs) => write!(f, "add {}", secs),
+            Command::Skip => write!(f, "skip"),
+            Command::Status => write!(f, "status"),
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+/// The outcome of a command, sent back to the client
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Ok,
+    Status(Status),
+    Error(String),
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Ok => write!(f, "ok"),
+            Reply::Status(status) => write!(f, "{}", status),
+            Reply::Error(e) => write!(f, "error: {}", e),
+        }
+    }
+}
+
+/// A command received from a client, along with the channel for its reply
+pub struct ControlRequest {
+    pub command: Command,
+    pub reply: oneshot::Sender<Reply>,
+}
+
+/// Default location of the control socket
+pub fn default_socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("clockit.sock")
+}
+
+/// Listens on a Unix socket and forwards parsed commands to the timer.
+/// The socket file is removed when the listener is dropped.
+#[cfg(unix)]
+pub struct ControlServer {
+    path: PathBuf,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl ControlServer {
+    /// Bind the socket at `path` and start accepting connections. `updates`
+    /// carries the state sent to watching connections.
+    pub fn bind(
+        path: PathBuf,
+        requests: mpsc::Sender<ControlRequest>,
+        updates: watch::Receiver<Status>,
+    ) -> io::Result<Self> {
+        use tokio::net::UnixListener;
+
+        if path.exists() {
+            // A leftover socket from a crashed run can be replaced, a live one cannot
+            if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("another clockit is already listening on {:?}", path),
+                ));
+            }
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        info!(path = ?path, "control socket listening");
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, requests.clone(), updates.clone()));
+            }
+        });
+
+        Ok(ControlServer { path, task })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+async fn handle_connection(
+    stream: tokio::net::UnixStream,
+    requests: mpsc::Sender<ControlRequest>,
+    updates: watch::Receiver<Status>,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.trim().eq_ignore_ascii_case("watch") {
+            watch_status(writer, updates).await;
+            break;
+        }
+
+        let reply = match line.parse::<Command>() {
+            Ok(command) => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                let request = ControlRequest { command, reply: reply_tx };
+                if requests.send(request).await.is_err() {
+                    break; // Timer has exited
+                }
+                match reply_rx.await {
+                    Ok(reply) => reply.to_string(),
+                    Err(_) => "error: timer exited".to_string(),
+                }
+            }
+            Err(e) => {
+                warn!(line = %line, error = %e, "invalid control command");
+                format!("error: {}", e)
+            }
+        };
+
+        if writer.write_all(format!("{}\n", reply).as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Send the state as a line of JSON now and after every change, until the
+/// timer exits or the client goes away
+#[cfg(unix)]
+async fn watch_status(mut writer: tokio::net::unix::OwnedWriteHalf, mut updates: watch::Receiver<Status>) {
+    use tokio::io::AsyncWriteExt;
+
+    info!("control client watching");
+    loop {
+        let line = format!("{}\n", updates.borrow_and_update().to_json());
+        if writer.write_all(line.as_bytes()).await.is_err() || updates.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Reads commands written to a named pipe and forwards them to the timer.
+/// A pipe created here is removed when the reader is dropped.
+#[cfg(unix)]
+pub struct ControlFifo {
+    path: PathBuf,
+    created: bool,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl ControlFifo {
+    /// Open the named pipe at `path`, creating it if needed, and start reading commands
+    pub fn open(path: PathBuf, requests: mpsc::Sender<ControlRequest>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+        use tokio::net::unix::pipe;
+
+        let created = match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_fifo() => false,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{:?} exists and isn't a named pipe", path),
+                ));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                make_fifo(&path)?;
+                true
+            }
+            Err(e) => return Err(e),
+        };
+
+        let receiver = pipe::OpenOptions::new().open_receiver(&path)?;
+        // Hold the pipe open for writing too, so the reader doesn't see the end
+        // of the pipe each time a writer like `echo` closes it
+        let keep_open = pipe::OpenOptions::new().open_sender(&path)?;
+        info!(path = ?path, "control fifo listening");
+        let task = tokio::spawn(async move {
+            let _keep_open = keep_open;
+            read_fifo(receiver, requests).await;
+        });
+
+        Ok(ControlFifo { path, created, task })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ControlFifo {
+    fn drop(&mut self) {
+        self.task.abort();
+        if self.created {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Create a named pipe only the current user can write to
+#[cfg(unix)]
+fn make_fifo(path: &std::path::Path) -> io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL byte"))?;
+    // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(unix)]
+async fn read_fifo(receiver: tokio::net::unix::pipe::Receiver, requests: mpsc::Sender<ControlRequest>) {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let mut lines = BufReader::new(receiver).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match line.parse::<Command>() {
+            Ok(command) => {
+                if !forward(command, &requests, "fifo").await {
+                    break; // Timer has exited
+                }
+            }
+            Err(e) => warn!(line = %line, error = %e, "invalid fifo command"),
+        }
+    }
+}
+
+/// Runs the configured commands when the process receives SIGUSR1 or SIGUSR2
+#[cfg(unix)]
+pub struct SignalControl {
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl SignalControl {
+    /// Start listening for the signals that have a command set
+    pub fn listen(settings: &SignalSettings, requests: mpsc::Sender<ControlRequest>) -> io::Result<Self> {
+        use tokio::signal::unix::{signal, Signal, SignalKind};
+
+        let handler = |key, command: &str, kind| -> io::Result<Option<(Signal, Command)>> {
+            parse_signal_command(key, command).map(|command| Ok((signal(kind)?, command))).transpose()
+        };
+        let mut usr1 = handler("usr1", &settings.usr1, SignalKind::user_defined1())?;
+        let mut usr2 = handler("usr2", &settings.usr2, SignalKind::user_defined2())?;
+
+        let task = tokio::spawn(async move {
+            loop {
+                let command = tokio::select! {
+                    Some(command) = next_signal(&mut usr1) => command,
+                    Some(command) = next_signal(&mut usr2) => command,
+                    else => break,
+                };
+                if !forward(command, &requests, "signal").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(SignalControl { task })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SignalControl {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The command for a signal, or None if it's left empty or can't be used
+#[cfg(unix)]
+fn parse_signal_command(key: &str, command: &str) -> Option<Command> {
+    if command.trim().is_empty() {
+        return None;
+    }
+    match command.parse() {
+        Ok(command) => Some(command),
+        Err(e) => {
+            warn!(key, command, error = %e, "invalid signal command");
+            eprintln!("Invalid signals.{} `{}`: {}. Ignoring the signal.", key, command, e);
+            None
+        }
+    }
+}
+
+/// Wait for the signal and return its command, or None if it isn't handled
+#[cfg(unix)]
+async fn next_signal(handler: &mut Option<(tokio::signal::unix::Signal, Command)>) -> Option<Command> {
+    let (signal, command) = handler.as_mut()?;
+    signal.recv().await.map(|_| *command)
+}
+
+/// Send a command that has no one to answer to the timer, noting any failure.
+/// Returns false once the timer has exited.
+#[cfg(unix)]
+async fn forward(command: Command, requests: &mpsc::Sender<ControlRequest>, source: &str) -> bool {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if requests.send(ControlRequest { command, reply: reply_tx }).await.is_err() {
+        return false;
+    }
+    match reply_rx.await {
+        Ok(Reply::Error(e)) => {
+            warn!(source, command = %command, error = %e, "control command failed");
+            true
+        }
+        Ok(reply) => {
+            debug!(source, command = %command, reply = %reply, "control command");
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Control sockets rely on Unix domain sockets and are unavailable elsewhere
+#[cfg(not(unix))]
+pub struct ControlServer;
+
+#[cfg(not(unix))]
+impl ControlServer {
+    pub fn bind(
+        _path: PathBuf,
+        _requests: mpsc::Sender<ControlRequest>,
+        _updates: watch::Receiver<Status>,
+    ) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "control sockets are only supported on Unix",
+        ))
+    }
+}
+
+/// There are no SIGUSR1 or SIGUSR2 to listen for outside Unix
+#[cfg(not(unix))]
+pub struct SignalControl;
+
+#[cfg(not(unix))]
+impl SignalControl {
+    pub fn listen(_settings: &SignalSettings, _requests: mpsc::Sender<ControlRequest>) -> io::Result<Self> {
+        Ok(SignalControl)
+    }
+}
+
+/// Named pipes are a Unix feature too
+#[cfg(not(unix))]
+pub struct ControlFifo;
+
+#[cfg(not(unix))]
+impl ControlFifo {
+    pub fn open(_path: PathBuf, _requests: mpsc::Sender<ControlRequest>) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "control fifos are only supported on Unix",
+        ))
+    }
+}
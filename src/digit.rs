@@ -1,81 +1,86 @@
 // src/digit.rs
 //! Module for rendering ASCII digits
 
+use crate::{caps, config::Config, theme::ColorMode};
+use ratatui::style::{Color, Style};
+use std::time::Duration;
+use tracing::warn;
+
 /// Returns ASCII art representation of a digit (0-9)
 /// Using simple ASCII characters to ensure consistent width rendering
-pub fn get_digit(digit: u8) -> Vec<&'static str> {
+pub fn get_digit(digit: u8) -> Glyph {
   match digit {
-      0 => vec![
+      0 => [
           ".---.",
           "|   |",
           "|   |",
           "|   |",
           "'---'",
       ],
-      1 => vec![
+      1 => [
           "  .  ",
           "  |  ",
           "  |  ",
           "  |  ",
           "  |  ",
       ],
-      2 => vec![
+      2 => [
           ".---.",
           "    |",
           ".---.",
           "|    ",
           "'---'",
       ],
-      3 => vec![
+      3 => [
           ".---.",
           "    |",
           ".---.",
           "    |",
           "'---'",
       ],
-      4 => vec![
+      4 => [
           "|   |",
           "|   |",
           "'---|",
           "    |",
           "    |",
       ],
-      5 => vec![
+      5 => [
           ".---.",
           "|    ",
           "'---.",
           "    |",
           "'---'",
       ],
-      6 => vec![
+      6 => [
           ".---.",
           "|    ",
           "|---.",
           "|   |",
           "'---'",
       ],
-      7 => vec![
+      7 => [
           ".---.",
           "    |",
           "    |",
           "    |",
           "    |",
       ],
-      8 => vec![
+      8 => [
           ".---.",
           "|   |",
           "|---.",
           "|   |",
           "'---'",
       ],
-      9 => vec![
+      9 => [
           ".---.",
           "|   |",
           "'---|",
           "    |",
           "'---'",
       ],
-      _ => vec![
+      _ => [
           "     ",
           "     ",
           "     ",
@@ -85,70 +90,738 @@ pub fn get_digit(digit: u8) -> Vec<&'static str> {
   }
 }
 
-/// Returns ASCII art representation of a colon
-pub fn get_colon() -> Vec<&'static str> {
-  vec![
-      "     ",
-      "  o  ",
-      "     ",
-      "  o  ",
-      "     ",
+/// Returns ASCII art representation of the "d" after a number of days
+pub fn get_day_suffix() -> Glyph {
+  [
+      "    .",
+      "    |",
+      ".---|",
+      "|   |",
+      "'---'",
   ]
 }
 
-/// Returns ASCII art representation of a dot
-pub fn get_dot() -> Vec<&'static str> {
-  vec![
-      "     ",
-      "     ",
-      "     ",
-      "     ",
-      "  o  ",
-  ]
+/// Returns ASCII art for a capital letter or a punctuation mark in titles,
+/// drawn in the same style as the digits
+pub fn get_letter(letter: char) -> Option<Glyph> {
+  Some(match letter {
+      'A' => [
+          ".---.",
+          "|   |",
+          "|---|",
+          "|   |",
+          "|   |",
+      ],
+      'B' => [
+          "|---.",
+          "|   |",
+          "|---<",
+          "|   |",
+          "|---'",
+      ],
+      'C' => [
+          ".---.",
+          "|    ",
+          "|    ",
+          "|    ",
+          "'---'",
+      ],
+      'D' => [
+          "|---.",
+          "|   |",
+          "|   |",
+          "|   |",
+          "|---'",
+      ],
+      'E' => [
+          ".---.",
+          "|    ",
+          "|--- ",
+          "|    ",
+          "'---'",
+      ],
+      'F' => [
+          ".---.",
+          "|    ",
+          "|--- ",
+          "|    ",
+          "|    ",
+      ],
+      'G' => [
+          ".---.",
+          "|    ",
+          "| --.",
+          "|   |",
+          "'---'",
+      ],
+      'H' => [
+          "|   |",
+          "|   |",
+          "|---|",
+          "|   |",
+          "|   |",
+      ],
+      'I' => [
+          "-----",
+          "  |  ",
+          "  |  ",
+          "  |  ",
+          "-----",
+      ],
+      'J' => [
+          "    |",
+          "    |",
+          "    |",
+          "|   |",
+          "'---'",
+      ],
+      'K' => [
+          "|   /",
+          "|  / ",
+          "|-<  ",
+          "|  \\ ",
+          "|   \\",
+      ],
+      'L' => [
+          "|    ",
+          "|    ",
+          "|    ",
+          "|    ",
+          "'---'",
+      ],
+      'M' => [
+          "|\\ /|",
+          "| V |",
+          "|   |",
+          "|   |",
+          "|   |",
+      ],
+      'N' => [
+          "|.  |",
+          "|\\  |",
+          "| \\ |",
+          "|  \\|",
+          "|  '|",
+      ],
+      'O' => [
+          ".---.",
+          "|   |",
+          "|   |",
+          "|   |",
+          "'---'",
+      ],
+      'P' => [
+          ".---.",
+          "|   |",
+          "|---'",
+          "|    ",
+          "|    ",
+      ],
+      'Q' => [
+          ".---.",
+          "|   |",
+          "|   |",
+          "|  \\|",
+          "'---\\",
+      ],
+      'R' => [
+          ".---.",
+          "|   |",
+          "|---'",
+          "|  \\ ",
+          "|   \\",
+      ],
+      'S' => [
+          ".---.",
+          "|    ",
+          "'---.",
+          "    |",
+          "'---'",
+      ],
+      'T' => [
+          "-----",
+          "  |  ",
+          "  |  ",
+          "  |  ",
+          "  |  ",
+      ],
+      'U' => [
+          "|   |",
+          "|   |",
+          "|   |",
+          "|   |",
+          "'---'",
+      ],
+      'V' => [
+          "|   |",
+          "|   |",
+          "|   |",
+          " \\ / ",
+          "  V  ",
+      ],
+      'W' => [
+          "|   |",
+          "|   |",
+          "|   |",
+          "| A |",
+          "|/ \\|",
+      ],
+      'X' => [
+          "\\   /",
+          " \\ / ",
+          "  X  ",
+          " / \\ ",
+          "/   \\",
+      ],
+      'Y' => [
+          "\\   /",
+          " \\ / ",
+          "  |  ",
+          "  |  ",
+          "  |  ",
+      ],
+      'Z' => [
+          "-----",
+          "   / ",
+          "  /  ",
+          " /   ",
+          "-----",
+      ],
+      '!' => [
+          "|",
+          "|",
+          "|",
+          " ",
+          "o",
+      ],
+      '\'' => [
+          "|",
+          "'",
+          " ",
+          " ",
+          " ",
+      ],
+      '?' => [
+          ".--.",
+          "   |",
+          " .-'",
+          "    ",
+          " o  ",
+      ],
+      '-' => [
+          "   ",
+          "   ",
+          "---",
+          "   ",
+          "   ",
+      ],
+      ' ' => [
+          "   ",
+          "   ",
+          "   ",
+          "   ",
+          "   ",
+      ],
+      _ => return None,
+  })
+}
+
+/// Height of every glyph in rows
+const GLYPH_HEIGHT: usize = 5;
+
+/// The rows of a digit or letter
+pub type Glyph = [&'static str; GLYPH_HEIGHT];
+
+/// Largest size multiplier tried when fitting the digits to the terminal
+pub const MAX_SCALE: usize = 10;
+
+/// How the colon and dot separators between digits are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeparatorStyle {
+  /// Small "o" marks
+  #[default]
+  Colon,
+  /// Periods
+  Dots,
+  /// Solid blocks ("#" when drawing with plain ASCII)
+  Blocks,
+  /// "o" marks in a narrower glyph
+  Thin,
+}
+
+impl SeparatorStyle {
+  /// Names accepted for `separator_style` in the configuration
+  pub const NAMES: [&'static str; 4] = ["colon", "dots", "blocks", "thin"];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+      match name.to_lowercase().as_str() {
+          "colon" => Some(SeparatorStyle::Colon),
+          "dots" => Some(SeparatorStyle::Dots),
+          "blocks" => Some(SeparatorStyle::Blocks),
+          "thin" => Some(SeparatorStyle::Thin),
+          _ => None,
+      }
+  }
+
+  fn width(self) -> usize {
+      match self {
+          SeparatorStyle::Thin => 3,
+          _ => 5,
+      }
+  }
+
+  fn mark(self, ascii_only: bool) -> char {
+      match self {
+          SeparatorStyle::Colon | SeparatorStyle::Thin => 'o',
+          SeparatorStyle::Dots => '.',
+          SeparatorStyle::Blocks if ascii_only => '#',
+          SeparatorStyle::Blocks => '█',
+      }
+  }
+}
+
+/// How a digit is animated as it changes to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+  /// Swap straight to the new digit
+  #[default]
+  None,
+  /// Scroll the old digit up and out as the new one comes in from below
+  Roll,
+  /// Fold the old digit away towards its middle, then unfold the new one
+  Flip,
+}
+
+impl Transition {
+  /// Names accepted for `digit_transition` in the configuration
+  pub const NAMES: [&'static str; 3] = ["none", "roll", "flip"];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+      match name.to_lowercase().as_str() {
+          "none" => Some(Transition::None),
+          "roll" => Some(Transition::Roll),
+          "flip" => Some(Transition::Flip),
+          _ => None,
+      }
+  }
+
+  pub fn from_config(config: &Config) -> Self {
+      Transition::from_name(&config.digit_transition).unwrap_or_else(|| {
+          // Digits are set up for every mode, some with the screen already up
+          warn!(transition = config.digit_transition, "unknown digit transition, using none");
+          Transition::default()
+      })
+  }
+
+  /// The glyph `progress` of the way (0 to 1) through changing from `old` to `new`
+  pub fn glyph(self, old: &[String], new: &[String], progress: f64) -> Vec<String> {
+      let height = new.len();
+      let blank = " ".repeat(new.first().map_or(0, |row| row.chars().count()));
+      match self {
+          Transition::None => new.to_vec(),
+          Transition::Roll => {
+              // The old digit and the new one stacked with a blank row between,
+              // seen through a window sliding down from one to the other
+              let offset = (progress.clamp(0.0, 1.0) * (height + 1) as f64).round() as usize;
+              let strip: Vec<&String> = old.iter().chain(std::iter::once(&blank)).chain(new).collect();
+              strip[offset..offset + height].iter().map(|row| row.to_string()).collect()
+          }
+          Transition::Flip => {
+              // The old digit squashes down to nothing by halfway, then the new one grows back
+              let progress = progress.clamp(0.0, 1.0);
+              let (glyph, shown) = if progress < 0.5 {
+                  (old, 1.0 - progress * 2.0)
+              } else {
+                  (new, progress * 2.0 - 1.0)
+              };
+              let rows = (shown * height as f64).round() as usize;
+              let top = (height - rows) / 2;
+              (0..height)
+                  .map(|row| match row.checked_sub(top).filter(|row| *row < rows) {
+                      Some(row) => glyph[row * height / rows].clone(),
+                      None => blank.clone(),
+                  })
+                  .collect()
+          }
+      }
+  }
+}
+
+/// How titles like the TIME'S UP banner and speaker names are lettered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BannerFont {
+  /// Three-row lettering for TIME'S UP, and names framed in a box
+  #[default]
+  Small,
+  /// Big letters in the same style as the digits, where they fit
+  Large,
+}
+
+impl BannerFont {
+  /// Names accepted for `banner_font` in the configuration
+  pub const NAMES: [&'static str; 2] = ["small", "large"];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+      match name.to_lowercase().as_str() {
+          "small" => Some(BannerFont::Small),
+          "large" => Some(BannerFont::Large),
+          _ => None,
+      }
+  }
+
+  pub fn from_config(config: &Config) -> Self {
+      BannerFont::from_name(&config.banner_font).unwrap_or_else(|| {
+          warn!(font = config.banner_font, "unknown banner font, using small");
+          BannerFont::default()
+      })
+  }
+}
+
+/// Options for rendering times as big digits
+#[derive(Debug, Clone, Copy)]
+pub struct DigitOptions {
+  /// Blank columns between glyphs
+  pub spacing: usize,
+  pub separator: SeparatorStyle,
+  /// Draw with plain ASCII characters only
+  pub ascii_only: bool,
+  /// Largest size multiplier to draw the glyphs at
+  pub scale: usize,
+  /// Use the largest size that fits instead of `scale`
+  pub auto_fit: bool,
+  /// Color for the separators, if not the same as the digits
+  pub separator_color: Option<Color>,
+  /// Color for the hundredths after the dot, if not the same as the digits
+  pub fraction_color: Option<Color>,
+  /// How the digit color changes from frame to frame
+  pub color_mode: ColorMode,
+  /// Animate the color smoothly with 24-bit color
+  pub true_color: bool,
+  /// How digits change to the next
+  pub transition: Transition,
+  /// How titles are lettered
+  pub banner_font: BannerFont,
+  /// Keep the colons in place but hide them, for the off half of a blink
+  pub colons_hidden: bool,
+}
+
+impl DigitOptions {
+  pub fn from_config(config: &Config) -> Self {
+      let separator = SeparatorStyle::from_name(&config.separator_style).unwrap_or_else(|| {
+          warn!(style = config.separator_style, "unknown separator style, using colon");
+          SeparatorStyle::default()
+      });
+
+      DigitOptions {
+          spacing: config.digit_spacing as usize,
+          separator,
+          ascii_only: config.ascii_only || !caps::supports_unicode(),
+          scale: config.scale.max(1) as usize,
+          auto_fit: config.auto_fit,
+          separator_color: optional_color(config, &config.colors.separator),
+          fraction_color: optional_color(config, &config.colors.fraction),
+          color_mode: ColorMode::from_config(config),
+          true_color: caps::supports_true_color(),
+          transition: Transition::from_config(config),
+          banner_font: BannerFont::from_config(config),
+          colons_hidden: false,
+      }
+  }
+
+  /// Style for one part of the time in a frame drawn with the colors
+  /// `animated` that long, given the style of the digits
+  pub fn style(&self, part: Part, digits: Style, animated: Duration) -> Style {
+      let digits = self.color_mode.style(digits, self.true_color, animated);
+      let color = match part {
+          Part::Digits => None,
+          Part::Separator => self.separator_color,
+          Part::Fraction => self.fraction_color,
+      };
+      color.map_or(digits, |color| digits.fg(color))
+  }
 }
 
-/// Combines multiple digit ASCII arts horizontally into one string
-pub fn combine_digits(digits: Vec<Vec<&str>>) -> Vec<String> {
-  let height = if !digits.is_empty() { digits[0].len() } else { 0 };
-  let mut result = vec![String::new(); height];
-  
-  for digit in digits {
-      for (i, line) in digit.iter().enumerate() {
-          result[i].push_str(line);
+/// A configured color, or None when it's left empty
+fn optional_color(config: &Config, name: &str) -> Option<Color> {
+  (!name.trim().is_empty()).then(|| config.parse_color(name.trim()).into())
+}
+
+/// Which part of a time some rendered art belongs to, so each can be colored on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+  /// Hours, minutes and seconds, and the gaps between glyphs
+  Digits,
+  /// Colons and dots
+  Separator,
+  /// Hundredths of a second after the dot
+  Fraction,
+}
+
+/// One character of a rendered time, with what it belongs to so it can be
+/// styled on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StyledChar {
+  pub symbol: char,
+  pub part: Part,
+  /// Position in the time string of the character this is drawn for, or
+  /// None for the gaps between glyphs
+  pub glyph: Option<usize>,
+  /// Drawn invisibly, keeping its place, like a colon blinked off
+  pub hidden: bool,
+}
+
+impl StyledChar {
+  /// A blank cell in a gap between glyphs
+  const GAP: StyledChar = StyledChar { symbol: ' ', part: Part::Digits, glyph: None, hidden: false };
+}
+
+/// Pairs each character of a time string (like "1:23.45") with the part it belongs to
+pub fn time_parts(time_string: &str) -> impl Iterator<Item = (char, Part)> + '_ {
+  let mut after_dot = false;
+  time_string.chars().map(move |c| {
+      let part = match c {
+          ':' | '.' | ' ' => Part::Separator,
+          _ if after_dot => Part::Fraction,
+          _ => Part::Digits,
+      };
+      after_dot |= c == '.';
+      (c, part)
+  })
+}
+
+/// Rows of the colon's marks
+const COLON_ROWS: [usize; 2] = [1, 3];
+
+/// Row of the dot's mark
+const DOT_ROWS: [usize; 1] = [4];
+
+/// Returns the "TIME'S UP!" banner drawn with box-drawing characters,
+/// or with plain ASCII when `ascii_only` is set
+pub fn get_time_up_banner(ascii_only: bool) -> Vec<&'static str> {
+  if ascii_only {
+      vec![
+          "___ _ _  _ ____ ' ____   _  _ ___  |",
+          " |  | |\\/| |___   [__    |  | |__] |",
+          " |  | |  | |___   ___]   |__| |    o",
+      ]
+  } else {
+      vec![
+          "┌┬┐┬┌┬┐┌─┐ ┬┌─┐  ┬ ┬┌─┐┬",
+          " │ ││││├┤  │└─┐  │ │├─┘│",
+          " ┴ ┴┴ ┴└─┘ ┴└─┘  └─┘┴  o",
+      ]
+  }
+}
+
+/// A time rendered in big digits, cell by cell. Rendering again reuses the
+/// rows and glyph list from the time before, so redrawing many times a
+/// second doesn't allocate afresh for every frame.
+#[derive(Debug, Clone, Default)]
+pub struct RenderedTime {
+  /// Rows of cells, of which the first `height` are in use; the rest are
+  /// kept for their memory
+  cells: Vec<Vec<StyledChar>>,
+  height: usize,
+  /// Columns taken up by the widest row
+  width: usize,
+  /// The art for each character, kept for its memory
+  arts: Vec<(Part, Art)>,
+}
+
+impl RenderedTime {
+  pub const fn new() -> Self {
+      RenderedTime { cells: Vec::new(), height: 0, width: 0, arts: Vec::new() }
+  }
+
+  /// The rows of cells, top to bottom
+  pub fn cells(&self) -> &[Vec<StyledChar>] {
+      &self.cells[..self.height]
+  }
+
+  pub fn width(&self) -> usize {
+      self.width
+  }
+
+  pub fn height(&self) -> usize {
+      self.height
+  }
+
+  /// Make room for `height` rows, reusing the ones already there
+  fn set_height(&mut self, height: usize) {
+      if self.cells.len() < height {
+          self.cells.resize_with(height, Vec::new);
       }
+      self.height = height;
+  }
+
+  /// Render a time string (like "12:34" or "1:23.45"), with the digits listed
+  /// in `changing` part way through changing from another one: for each
+  /// character, the digit it was and how far through the transition it is
+  pub fn render(&mut self, time_string: &str, options: &DigitOptions, changing: &[Option<(char, f64)>]) {
+      let mut arts = std::mem::take(&mut self.arts);
+      arts.clear();
+      arts.extend(time_parts(time_string).enumerate().map(|(i, (c, part))| {
+          let art = match changing.get(i).copied().flatten() {
+              Some((old, progress)) => {
+                  Art::Rows(options.transition.glyph(&glyph(old, options), &glyph(c, options), progress))
+              }
+              None => Art::of(c, options),
+          };
+          (part, art)
+      }));
+      self.combine(&arts, options.spacing);
+      self.arts = arts;
+  }
+
+  /// Render a time as a single row of plain characters, for when big digits don't fit
+  pub fn render_plain(&mut self, time_string: &str) {
+      self.set_height(1);
+      let row = &mut self.cells[0];
+      row.clear();
+      row.extend(
+          time_parts(time_string)
+              .enumerate()
+              .map(|(i, (symbol, part))| StyledChar { symbol, part, glyph: Some(i), hidden: false }),
+      );
+      self.width = row.len();
+  }
+
+  /// Enlarge the art so each character becomes a `scale`×`scale` block
+  pub fn scale(&mut self, scale: usize) {
+      if scale <= 1 {
+          return;
+      }
+      let height = self.height;
+      self.set_height(height * scale);
+      // Widen each row from its right end, so no cell is overwritten before it is copied
+      for row in &mut self.cells[..height] {
+          let width = row.len();
+          row.resize(width * scale, StyledChar::GAP);
+          for x in (0..width).rev() {
+              let cell = row[x];
+              row[x * scale..(x + 1) * scale].fill(cell);
+          }
+      }
+      // ...then repeat the rows from the bottom up, for the same reason
+      for y in (0..height).rev() {
+          for copy in (0..scale).rev() {
+              let to = y * scale + copy;
+              if to != y {
+                  let (above, below) = self.cells.split_at_mut(to);
+                  below[0].clone_from(&above[y]);
+              }
+          }
+      }
+      self.width *= scale;
+  }
+
+  /// Lay the glyphs side by side with `spacing` blank columns between them
+  fn combine(&mut self, arts: &[(Part, Art)], spacing: usize) {
+      self.set_height(if arts.is_empty() { 0 } else { GLYPH_HEIGHT });
+      for (y, row) in self.cells[..self.height].iter_mut().enumerate() {
+          row.clear();
+          for (i, (part, art)) in arts.iter().enumerate() {
+              if i > 0 {
+                  row.extend(std::iter::repeat_n(StyledChar::GAP, spacing));
+              }
+              let start = row.len();
+              art.write_row(y, row);
+              for cell in &mut row[start..] {
+                  cell.part = *part;
+                  cell.glyph = Some(i);
+              }
+          }
+      }
+      self.width = self.cells().iter().map(Vec::len).max().unwrap_or(0);
+  }
+
+  /// The rows as plain text, without what each character belongs to
+  fn into_text(mut self) -> Vec<String> {
+      self.cells.truncate(self.height);
+      self.cells.into_iter().map(|row| row.into_iter().map(|cell| cell.symbol).collect()).collect()
   }
-  
-  result
 }
 
-/// Renders a time string (like "12:34" or "1:23.45") as ASCII art
-pub fn render_time(time_string: &str) -> Vec<String> {
-  let mut digit_arts = Vec::new();
-  
-  for c in time_string.chars() {
+/// The art for one character of a time, looked up without allocating where it can be
+#[derive(Debug, Clone)]
+enum Art {
+  Glyph(Glyph),
+  /// A separator `width` columns wide with `mark` on the given rows, drawn
+  /// invisibly when `hidden`
+  Separator { mark_rows: &'static [usize], mark: char, width: usize, hidden: bool },
+  /// Rows worked out for this frame, like a digit part way through changing
+  Rows(Vec<String>),
+}
+
+impl Art {
+  fn of(c: char, options: &DigitOptions) -> Self {
+      let separator = |mark_rows, hidden| Art::Separator {
+          mark_rows,
+          mark: options.separator.mark(options.ascii_only),
+          width: options.separator.width(),
+          hidden,
+      };
       match c {
-          '0'..='9' => {
-              let digit = c.to_digit(10).unwrap() as u8;
-              digit_arts.push(get_digit(digit));
-          },
-          ':' => {
-              digit_arts.push(get_colon());
-          },
-          '.' => {
-              digit_arts.push(get_dot());
-          },
-          _ => {
-              // For any other character (space, etc.) just add empty space
-              digit_arts.push(vec![
-                  "     ",
-                  "     ",
-                  "     ",
-                  "     ",
-                  "     ",
-              ]);
+          '0'..='9' => Art::Glyph(get_digit(c as u8 - b'0')),
+          'd' => Art::Glyph(get_day_suffix()),
+          ':' => separator(&COLON_ROWS, options.colons_hidden),
+          '.' => separator(&DOT_ROWS, false),
+          // A space between fields is as wide as a separator without its marks
+          ' ' => separator(&[], false),
+          // For any other character just add empty space
+          _ => Art::Glyph(["     "; GLYPH_HEIGHT]),
+      }
+  }
+
+  /// Add row `y` of the art to `row`, as cells still to be tagged with what they belong to
+  fn write_row(&self, y: usize, row: &mut Vec<StyledChar>) {
+      let cell = |symbol| StyledChar { symbol, ..StyledChar::GAP };
+      match self {
+          Art::Glyph(glyph) => row.extend(glyph[y].chars().map(cell)),
+          Art::Separator { mark_rows, mark, width, hidden } => {
+              let left = (width - 1) / 2;
+              let marked = mark_rows.contains(&y);
+              row.extend((0..*width).map(|x| StyledChar {
+                  hidden: *hidden,
+                  ..cell(if marked && x == left { *mark } else { ' ' })
+              }));
           }
+          Art::Rows(rows) => row.extend(rows.get(y).map_or("", String::as_str).chars().map(cell)),
       }
   }
-  
-  combine_digits(digit_arts)
-}
\ No newline at end of file
+}
+
+/// Renders a time string (like "12:34" or "1:23.45") as ASCII art, with each
+/// glyph tagged by the part of the time it shows
+pub fn render_time(time_string: &str, options: &DigitOptions) -> RenderedTime {
+  let mut rendered = RenderedTime::new();
+  rendered.render(time_string, options, &[]);
+  rendered
+}
+
+/// The ASCII art for one character of a time, as rows to change between
+fn glyph(c: char, options: &DigitOptions) -> Vec<String> {
+  let mut rendered = RenderedTime::new();
+  rendered.combine(&[(Part::Digits, Art::of(c, options))], 0);
+  rendered.into_text()
+}
+
+/// Renders text in big letters like the digits, or None if it is empty or
+/// has a character there is no glyph for
+pub fn render_text(text: &str, spacing: usize) -> Option<Vec<String>> {
+  let arts = text
+      .to_uppercase()
+      .chars()
+      .map(|c| match c.to_digit(10) {
+          Some(digit) => Some(get_digit(digit as u8)),
+          None => get_letter(c),
+      })
+      .map(|glyph| glyph.map(|glyph| (Part::Digits, Art::Glyph(glyph))))
+      .collect::<Option<Vec<_>>>()?;
+  if arts.is_empty() {
+      return None;
+  }
+  let mut rendered = RenderedTime::new();
+  rendered.combine(&arts, spacing);
+  Some(rendered.into_text())
+}
@@ -0,0 +1,324 @@
+// src/export.rs
+//! Module for sending tracked time to Toggl or Clockify (enabled with the
+//! `export` feature)
+//!
+//! Requests are made with `curl`, so Clockit itself needs no network or TLS
+//! code. Entries that can't be sent for now are queued in the state directory
+//! and go out with the next batch. Entries the service refuses are set aside
+//! in a file of their own there, since sending them again would fail the same way.
+
+use crate::{config::ExportSettings, store, track::TrackedTime};
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+use tracing::{info, warn};
+
+/// Tries per entry before it waits in the queue for next time
+const ATTEMPTS: u32 = 3;
+
+/// Longest a single request may take
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Toggl,
+    Clockify,
+}
+
+impl Service {
+    /// The service named by `export.service`, or None while it's empty
+    pub fn from_name(name: &str) -> Result<Option<Self>, String> {
+        match name.trim().to_lowercase().as_str() {
+            "" => Ok(None),
+            "toggl" => Ok(Some(Service::Toggl)),
+            "clockify" => Ok(Some(Service::Clockify)),
+            other => Err(format!("unknown export service `{}` (expected toggl or clockify)", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Service::Toggl => "Toggl",
+            Service::Clockify => "Clockify",
+        }
+    }
+
+    fn default_api_url(self) -> &'static str {
+        match self {
+            Service::Toggl => "https://api.track.toggl.com/api/v9",
+            Service::Clockify => "https://api.clockify.me/api/v1",
+        }
+    }
+}
+
+/// Why an entry wasn't sent
+enum SendError {
+    /// Worth trying again: no connection, rate limiting or a server error
+    Transient(String),
+    /// Trying again won't help until the settings change, e.g. a wrong token
+    Rejected(String),
+}
+
+/// Sends tracked time to the configured service
+pub struct Exporter {
+    service: Service,
+    settings: ExportSettings,
+}
+
+impl Exporter {
+    /// The configured exporter, or None while `export.service` is empty
+    pub fn from_settings(settings: &ExportSettings) -> Result<Option<Self>, String> {
+        let service = Service::from_name(&settings.service)?;
+        Ok(service.map(|service| Exporter { service, settings: settings.clone() }))
+    }
+
+    /// Send any queued entries, then `entries`, queueing whatever can't be sent
+    /// for now and setting aside whatever the service refuses
+    pub fn send(&self, entries: &[TrackedTime]) -> io::Result<()> {
+        let mut pending = load_queue()?;
+        pending.extend_from_slice(entries);
+        if pending.is_empty() {
+            println!("Nothing waiting to be sent to {}.", self.service.name());
+            return Ok(());
+        }
+
+        let mut sent = 0;
+        let mut unsent = Vec::new();
+        let mut rejected = Vec::new();
+        let mut offline_error = None;
+        let mut rejected_error = None;
+        for entry in pending {
+            // Once the service can't be reached, keep the rest for next time
+            if offline_error.is_some() {
+                unsent.push(entry);
+                continue;
+            }
+            match self.send_with_retries(&entry) {
+                Ok(()) => sent += 1,
+                Err(SendError::Transient(e)) => {
+                    unsent.push(entry);
+                    offline_error = Some(e);
+                }
+                Err(SendError::Rejected(e)) => {
+                    warn!(
+                        project = entry.project,
+                        started_at = %entry.started_at,
+                        error = %e,
+                        "export rejected, setting it aside"
+                    );
+                    rejected.push(entry);
+                    rejected_error = Some(e);
+                }
+            }
+        }
+        save_queue(&unsent)?;
+        let rejected_path = set_aside(&rejected)?;
+
+        if sent > 0 {
+            println!("Sent {} to {}.", entries_text(sent), self.service.name());
+        }
+        if let Some(e) = offline_error {
+            println!(
+                "Couldn't send to {}: {}. {} queued; run `clockit track sync` to try again.",
+                self.service.name(),
+                e,
+                entries_text(unsent.len())
+            );
+        }
+        if let Some(e) = rejected_error {
+            println!(
+                "{} refused {}: {}. Set aside in {} rather than sent again.",
+                self.service.name(),
+                entries_text(rejected.len()),
+                e,
+                rejected_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    fn send_with_retries(&self, entry: &TrackedTime) -> Result<(), SendError> {
+        let mut attempt = 1;
+        loop {
+            match self.send_once(entry) {
+                Err(SendError::Transient(e)) if attempt < ATTEMPTS => {
+                    warn!(attempt, error = %e, "export failed, retrying");
+                    thread::sleep(Duration::from_secs(1 << (attempt - 1)));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn send_once(&self, entry: &TrackedTime) -> Result<(), SendError> {
+        let settings = &self.settings;
+        let api_url = match settings.api_url.trim() {
+            "" => self.service.default_api_url(),
+            url => url.trim_end_matches('/'),
+        };
+        let project_id = settings.projects.get(&entry.project);
+        let start = entry.started_at.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let stop = entry.ended_at.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let mut curl_config = Vec::new();
+        match self.service {
+            Service::Toggl => {
+                let workspace_id = numeric_id(&settings.workspace_id, "export.workspace_id")?;
+                let mut body = json!({
+                    "created_with": "clockit",
+                    "workspace_id": workspace_id,
+                    "description": entry.project,
+                    "start": start,
+                    "stop": stop,
+                    "duration": entry.duration_secs,
+                    "tags": ["clockit"],
+                });
+                if let Some(id) = project_id {
+                    body["project_id"] = Value::from(numeric_id(id, &format!("export.projects.{}", entry.project))?);
+                }
+                curl_config.push(("url", format!("{}/workspaces/{}/time_entries", api_url, workspace_id)));
+                curl_config.push(("user", format!("{}:api_token", settings.api_token)));
+                curl_config.push(("data-binary", body.to_string()));
+            }
+            Service::Clockify => {
+                let mut body = json!({ "start": start, "end": stop, "description": entry.project });
+                if let Some(id) = project_id {
+                    body["projectId"] = Value::from(id.as_str());
+                }
+                curl_config.push(("url", format!("{}/workspaces/{}/time-entries", api_url, settings.workspace_id)));
+                curl_config.push(("header", format!("X-Api-Key: {}", settings.api_token)));
+                curl_config.push(("data-binary", body.to_string()));
+            }
+        }
+        curl_config.push(("header", "Content-Type: application/json".to_string()));
+
+        let status = curl(&curl_config)?;
+        match status {
+            200..=299 => {
+                info!(service = self.service.name(), project = %entry.project, "exported tracked time");
+                Ok(())
+            }
+            401 | 403 => Err(SendError::Rejected(format!("HTTP {} (check export.api_token)", status))),
+            429 | 500..=599 => Err(SendError::Transient(format!("HTTP {}", status))),
+            _ => Err(SendError::Rejected(format!("HTTP {}", status))),
+        }
+    }
+}
+
+/// Run curl with `options` passed as a config file on stdin, so the token
+/// doesn't show up in the process list. Returns the HTTP status.
+fn curl(options: &[(&str, String)]) -> Result<u16, SendError> {
+    let mut config = String::new();
+    for (name, value) in options {
+        config.push_str(&format!("{} = \"{}\"\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    let null = if cfg!(windows) { "NUL" } else { "/dev/null" };
+    config.push_str(&format!("output = \"{}\"\nwrite-out = \"%{{http_code}}\"\nmax-time = {}\n", null, REQUEST_TIMEOUT_SECS));
+
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SendError::Rejected(format!("could not run curl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes()).map_err(|e| SendError::Transient(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| SendError::Transient(e.to_string()))?;
+
+    // curl reports 000 when no response came back at all
+    match String::from_utf8_lossy(&output.stdout).trim().parse::<u16>() {
+        Ok(status) if status != 0 => Ok(status),
+        _ => {
+            let error = String::from_utf8_lossy(&output.stderr).trim().trim_start_matches("curl: ").to_string();
+            Err(SendError::Transient(if error.is_empty() { "no response".to_string() } else { error }))
+        }
+    }
+}
+
+/// Parse an ID that Toggl expects as a number
+fn numeric_id(id: &str, setting: &str) -> Result<u64, SendError> {
+    id.trim().parse().map_err(|_| SendError::Rejected(format!("{} should be a number, not `{}`", setting, id)))
+}
+
+fn entries_text(count: usize) -> String {
+    format!("{} {}", count, if count == 1 { "entry" } else { "entries" })
+}
+
+fn get_queue_path() -> io::Result<PathBuf> {
+    Ok(store::get_state_dir()?.join("export-queue.jsonl"))
+}
+
+fn get_rejected_path() -> io::Result<PathBuf> {
+    Ok(store::get_state_dir()?.join("export-rejected.jsonl"))
+}
+
+fn load_queue() -> io::Result<Vec<TrackedTime>> {
+    let path = get_queue_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!(error = %e, "dropping unreadable queued export");
+                None
+            }
+        })
+        .collect())
+}
+
+fn save_queue(entries: &[TrackedTime]) -> io::Result<()> {
+    let path = get_queue_path()?;
+    if entries.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, json_lines(entries)?)
+}
+
+/// Add entries the service refused to the ones set aside before, returning
+/// the file they are kept in
+fn set_aside(entries: &[TrackedTime]) -> io::Result<PathBuf> {
+    let path = get_rejected_path()?;
+    if entries.is_empty() {
+        return Ok(path);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(json_lines(entries)?.as_bytes())?;
+    Ok(path)
+}
+
+/// One JSON object per line, as the queue files are kept
+fn json_lines(entries: &[TrackedTime]) -> io::Result<String> {
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).map_err(io::Error::other)?);
+        lines.push('\n');
+    }
+    Ok(lines)
+}
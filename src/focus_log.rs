@@ -0,0 +1,38 @@
+// src/focus_log.rs
+//! Module for the `--log-to` focus journal: one human-readable line per
+//! completed work session, appended to a file the user chooses, followed by
+//! a line for each interruption noted during it
+
+use crate::history::SessionRecord;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+};
+
+/// Append a completed session to the journal at `path`.
+/// Files ending in `.md` get a Markdown list item, anything else plain text.
+pub fn append(path: &Path, session: &SessionRecord) -> io::Result<()> {
+    let markdown = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("md") || extension.eq_ignore_ascii_case("markdown"));
+
+    let timestamp = session.started_at.format("%Y-%m-%d %H:%M");
+    let minutes = session.duration_secs.div_ceil(60);
+    let mut line = match (&session.task, markdown) {
+        (Some(task), true) => format!("- {} ({} min) **{}**: {}\n", timestamp, minutes, session.label, task),
+        (None, true) => format!("- {} ({} min) **{}**\n", timestamp, minutes, session.label),
+        (Some(task), false) => format!("{}\t{} min\t{}\t{}\n", timestamp, minutes, session.label, task),
+        (None, false) => format!("{}\t{} min\t{}\n", timestamp, minutes, session.label),
+    };
+    for note in &session.notes {
+        let at = note.at.format("%H:%M");
+        if markdown {
+            line.push_str(&format!("  - {} interrupted: {}\n", at, note.text));
+        } else {
+            line.push_str(&format!("\t{}\tinterrupted\t{}\n", at, note.text));
+        }
+    }
+
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(line.as_bytes())
+}
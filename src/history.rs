@@ -0,0 +1,112 @@
+// src/history.rs
+//! Module for the history of completed sessions
+//!
+//! Each completed session is appended as one JSON object per line, so a crash
+//! mid-write can at worst lose the line being written.
+
+use crate::store;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+use tracing::{info, warn};
+
+/// What kind of session a record describes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    Work,
+    Break,
+}
+
+/// A completed session
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionRecord {
+    pub kind: SessionKind,
+    /// Name shown while the session ran, e.g. "Work Session #2"
+    pub label: String,
+    /// What the session was spent on, from `--task`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    /// Time spent in the session, not counting pauses
+    pub duration_secs: u64,
+    /// Time the session was paused because the user was away
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub idle_secs: u64,
+    /// Time added to the session while it ran, e.g. by extending it
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub extended_secs: u64,
+    /// Times the session was paused, by hand or while idle
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub interruptions: u32,
+    /// Time the session spent paused, idle time included
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub paused_secs: u64,
+    /// Interruptions noted with `i` while the session ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+}
+
+/// A note typed during a session, like what interrupted it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Note {
+    pub at: DateTime<Local>,
+    pub text: String,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Get the path to the history file
+pub fn get_history_path() -> io::Result<PathBuf> {
+    Ok(store::get_state_dir()?.join("history.jsonl"))
+}
+
+/// Append a completed session to the history file
+pub fn record(session: &SessionRecord) -> io::Result<()> {
+    let path = get_history_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut line = serde_json::to_string(session).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new().create(true).append(true).open(&path)?.write_all(line.as_bytes())?;
+    info!(label = %session.label, duration_secs = session.duration_secs, "recorded session");
+    Ok(())
+}
+
+/// Load every recorded session that started on or after `since`
+pub fn load(since: Option<NaiveDate>) -> io::Result<Vec<SessionRecord>> {
+    let path = get_history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let mut sessions = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<SessionRecord>(line) {
+            Ok(session) => sessions.push(session),
+            Err(e) => {
+                // Skip damaged lines rather than losing the whole history
+                warn!(line = index + 1, error = %e, "skipping unreadable history entry");
+                eprintln!("Skipping unreadable history entry on line {}: {}", index + 1, e);
+            }
+        }
+    }
+
+    if let Some(since) = since {
+        sessions.retain(|session| session.started_at.date_naive() >= since);
+    }
+    Ok(sessions)
+}
@@ -0,0 +1,143 @@
+// src/history_cmd.rs
+//! Module for the `clockit history` subcommands
+
+use crate::history::{self, SessionKind, SessionRecord};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use clap::ValueEnum;
+use std::{
+    fs::File,
+    io::{self, stdout, Write},
+    path::Path,
+};
+
+/// File formats sessions can be exported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    /// Calendar events for the work sessions, for importing into a calendar app
+    Ics,
+}
+
+/// Write completed sessions to `output`, or stdout when no path is given
+pub fn export(format: ExportFormat, since: Option<NaiveDate>, output: Option<&Path>) -> io::Result<()> {
+    let mut sessions = history::load(since)?;
+    if format == ExportFormat::Ics {
+        sessions.retain(|session| session.kind == SessionKind::Work);
+    }
+
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(stdout().lock()),
+    };
+
+    match format {
+        ExportFormat::Csv => write_csv(&mut writer, &sessions)?,
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &sessions).map_err(io::Error::other)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Ics => write_ics(&mut writer, &sessions)?,
+    }
+    writer.flush()?;
+
+    if let Some(path) = output {
+        println!("Exported {} sessions to {}", sessions.len(), path.display());
+    }
+    Ok(())
+}
+
+fn write_csv(writer: &mut dyn Write, sessions: &[SessionRecord]) -> io::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "kind",
+        "label",
+        "task",
+        "started_at",
+        "ended_at",
+        "duration_secs",
+        "idle_secs",
+        "extended_secs",
+        "interruptions",
+        "paused_secs",
+    ])?;
+    for session in sessions {
+        let kind = serde_json::to_value(session.kind).map_err(io::Error::other)?;
+        csv.write_record([
+            kind.as_str().unwrap_or_default(),
+            &session.label,
+            session.task.as_deref().unwrap_or_default(),
+            &session.started_at.to_rfc3339(),
+            &session.ended_at.to_rfc3339(),
+            &session.duration_secs.to_string(),
+            &session.idle_secs.to_string(),
+            &session.extended_secs.to_string(),
+            &session.interruptions.to_string(),
+            &session.paused_secs.to_string(),
+        ])?;
+    }
+    csv.flush()
+}
+
+/// Write the sessions as an iCalendar file, one event per session
+fn write_ics(writer: &mut dyn Write, sessions: &[SessionRecord]) -> io::Result<()> {
+    let stamp = ics_time(&Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Clockit//Focus sessions//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for session in sessions {
+        let focused = format!("{} min focused", session.duration_secs.div_ceil(60));
+        let description = match &session.task {
+            Some(task) => format!("{}: {}, {}", session.label, task, focused),
+            None => format!("{}, {}", session.label, focused),
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            // Made from the start time, so importing the history again
+            // updates the events instead of adding them twice
+            format!("UID:{}@clockit", ics_time(&session.started_at)),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", ics_time(&session.started_at)),
+            format!("DTEND:{}", ics_time(&session.ended_at)),
+            format!("SUMMARY:{}", ics_text(session.task.as_deref().unwrap_or(&session.label))),
+            format!("DESCRIPTION:{}", ics_text(&description)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    for line in lines {
+        writer.write_all(fold_ics_line(&line).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// A time in UTC, as iCalendar writes it
+fn ics_time<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    time.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape the characters iCalendar gives a meaning in text values
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+/// End a content line with CRLF, folding it so no line is longer than 75
+/// bytes; continuation lines start with a space
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
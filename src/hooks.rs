@@ -0,0 +1,116 @@
+// src/hooks.rs
+//! Module for running user-configured shell commands when timer events happen
+
+use crate::config::HooksSettings;
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+/// Timer events that can trigger a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A Pomodoro work session started
+    WorkStart,
+    /// A Pomodoro break started
+    BreakStart,
+    /// A Pomodoro work or break session ended
+    SessionEnd,
+    /// A countdown finished, a stopwatch reached its goal, or all Pomodoro cycles are done
+    Complete,
+    /// The timer was paused
+    Pause,
+    /// The timer was resumed
+    Resume,
+    /// A countdown crossed one of its alerts
+    Alert,
+}
+
+impl HookEvent {
+    /// Name passed to hooks in `CLOCKIT_EVENT`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::WorkStart => "work_start",
+            HookEvent::BreakStart => "break_start",
+            HookEvent::SessionEnd => "session_end",
+            HookEvent::Complete => "complete",
+            HookEvent::Pause => "pause",
+            HookEvent::Resume => "resume",
+            HookEvent::Alert => "alert",
+        }
+    }
+}
+
+/// Details about an event, passed to hooks as environment variables
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    /// Running mode (`CLOCKIT_MODE`)
+    pub mode: &'static str,
+    /// Current session name, if any (`CLOCKIT_LABEL`)
+    pub label: Option<String>,
+    /// Length of the session or timer in seconds (`CLOCKIT_DURATION`)
+    pub duration_secs: Option<u64>,
+    /// Pomodoro cycle number (`CLOCKIT_CYCLE`)
+    pub cycle: Option<u64>,
+}
+
+/// The configured hooks
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    settings: HooksSettings,
+}
+
+impl Hooks {
+    pub fn new(settings: &HooksSettings) -> Self {
+        Hooks { settings: settings.clone() }
+    }
+
+    fn command_for(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::WorkStart => &self.settings.on_work_start,
+            HookEvent::BreakStart => &self.settings.on_break_start,
+            HookEvent::SessionEnd => &self.settings.on_session_end,
+            HookEvent::Complete => &self.settings.on_complete,
+            HookEvent::Pause => &self.settings.on_pause,
+            HookEvent::Resume => &self.settings.on_resume,
+            HookEvent::Alert => &self.settings.on_alert,
+        };
+        command.as_deref().filter(|command| !command.trim().is_empty())
+    }
+
+    /// Run the hook for `event` in the background, if one is configured
+    pub fn fire(&self, event: HookEvent, context: &HookContext) {
+        let Some(command) = self.command_for(event) else {
+            return;
+        };
+
+        let mut shell = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+
+        shell
+            .arg(command)
+            .env("CLOCKIT_EVENT", event.name())
+            .env("CLOCKIT_MODE", context.mode)
+            .env("CLOCKIT_LABEL", context.label.as_deref().unwrap_or_default())
+            .env("CLOCKIT_DURATION", context.duration_secs.map(|secs| secs.to_string()).unwrap_or_default())
+            .env("CLOCKIT_CYCLE", context.cycle.map(|cycle| cycle.to_string()).unwrap_or_default())
+            // Hook output would draw over the timer
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        match shell.spawn() {
+            Ok(mut child) => {
+                info!(event = event.name(), command, "running hook");
+                // Reap the hook in the background once it finishes
+                std::thread::spawn(move || child.wait());
+            }
+            Err(e) => warn!(event = event.name(), command, error = %e, "could not run hook"),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// src/http.rs
+//! Module for the HTTP status server (enabled with the `http` feature)
+//!
+//! A deliberately small HTTP/1.1 server: each connection sends one request
+//! and gets one JSON response, which is all browser widgets and shortcut
+//! apps need.
+//!
+//! - `GET /status` returns the current timer state
+//! - `POST` to `/pause`, `/resume`, `/toggle`, `/skip` and `/add?secs=N`
+//!   controls the timer
+//!
+//! Commands must be POSTed and are refused when a browser says they come
+//! from a web page, so a page open in the browser can't work the timer.
+
+use crate::{
+    control::{Command, ControlRequest, Reply},
+    MAX_TIME_SECS,
+};
+use serde_json::{json, Value};
+use std::{io, net::SocketAddr};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+};
+use tracing::{info, warn};
+
+/// Largest request head we are willing to read
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Serves timer status and commands over HTTP until dropped
+pub struct HttpServer {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl HttpServer {
+    /// Bind `addr` and start accepting connections
+    pub async fn bind(addr: SocketAddr, requests: mpsc::Sender<ControlRequest>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        info!(%addr, "http server listening");
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, requests.clone()));
+            }
+        });
+
+        Ok(HttpServer { task })
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, requests: mpsc::Sender<ControlRequest>) {
+    let (status_code, body) = match read_request(&mut stream).await {
+        Ok(request) => route(&request, &requests).await,
+        Err(e) => {
+            warn!(error = %e, "bad http request");
+            (400, json!({ "error": e.to_string() }))
+        }
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {}",
+        status_code,
+        reason(status_code),
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// What we act on from a request head
+struct Request {
+    method: String,
+    /// Path and query
+    target: String,
+    /// Whether a browser sent it on behalf of a web page (it has an `Origin`)
+    from_page: bool,
+}
+
+/// Read the request head
+async fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..read]);
+        if head.len() > MAX_REQUEST_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "request too large"));
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let from_page = lines
+        .filter_map(|line| line.split_once(':'))
+        .any(|(name, _)| name.trim().eq_ignore_ascii_case("origin"));
+    match (request_line.next(), request_line.next()) {
+        (Some(method @ ("GET" | "POST")), Some(target)) => {
+            Ok(Request { method: method.to_string(), target: target.to_string(), from_page })
+        }
+        (Some(_), Some(_)) => Err(io::Error::new(io::ErrorKind::InvalidInput, "only GET and POST are supported")),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "malformed request line")),
+    }
+}
+
+/// Turn a request into a command and run it
+async fn route(request: &Request, requests: &mpsc::Sender<ControlRequest>) -> (u16, Value) {
+    let (path, query) = request.target.split_once('?').unwrap_or((&request.target, ""));
+    let command = match path {
+        "/status" => Command::Status,
+        "/pause" => Command::Pause,
+        "/resume" => Command::Resume,
+        "/toggle" => Command::Toggle,
+        "/skip" => Command::Skip,
+        "/add" => {
+            let secs = query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == "secs")
+                .and_then(|(_, value)| value.parse::<u64>().ok());
+            match secs {
+                Some(secs) if secs <= MAX_TIME_SECS => Command::Add(secs),
+                Some(_) => return (400, json!({ "error": format!("at most {} seconds can be added", MAX_TIME_SECS) })),
+                None => return (400, json!({ "error": "usage: /add?secs=SECONDS" })),
+            }
+        }
+        _ => return (404, json!({ "error": format!("no such endpoint: {}", path) })),
+    };
+    if command != Command::Status {
+        if request.method != "POST" {
+            return (405, json!({ "error": format!("{} only accepts POST", path) }));
+        }
+        if request.from_page {
+            return (403, json!({ "error": "commands from web pages are refused" }));
+        }
+    }
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if requests.send(ControlRequest { command, reply: reply_tx }).await.is_err() {
+        return (503, json!({ "error": "timer exited" }));
+    }
+
+    match reply_rx.await {
+        Ok(Reply::Ok) => (200, json!({ "ok": true })),
+        Ok(Reply::Status(status)) => (200, status.to_json()),
+        Ok(Reply::Error(e)) => (409, json!({ "error": e })),
+        Err(_) => (503, json!({ "error": "timer exited" })),
+    }
+}
+
+fn reason(status_code: u16) -> &'static str {
+    match status_code {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
@@ -0,0 +1,134 @@
+// src/idle.rs
+//! Module for noticing when the user has walked away, so time spent idle
+//! isn't counted as focus time
+//!
+//! Idleness comes either from the system (X11 via `xprintidle`, macOS via
+//! `ioreg`) or from a heartbeat: no key pressed in clockit for the timeout.
+
+use crate::{clock, config::IdleSettings};
+use std::{
+    process::{Command, Stdio},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// How often the system idle time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+enum Source {
+    Off,
+    /// Only key presses and commands in clockit count as activity
+    Heartbeat,
+    /// Time of the last input the system saw, kept up to date by a polling thread
+    System(Poller),
+}
+
+/// The polling thread's view of the last system input. The thread stops once
+/// this is dropped along with its mode.
+struct Poller {
+    last_input: Arc<Mutex<Option<Instant>>>,
+    _stop: mpsc::Sender<()>,
+}
+
+/// Tracks when the user was last active
+pub struct IdleMonitor {
+    source: Source,
+    timeout: Duration,
+    /// Last key press or command in clockit
+    last_interaction: Instant,
+}
+
+impl IdleMonitor {
+    pub fn new(settings: &IdleSettings) -> Self {
+        let source = match settings.detection.to_lowercase().as_str() {
+            "off" => Source::Off,
+            "heartbeat" => Source::Heartbeat,
+            "system" => Source::System(spawn_poller()),
+            other => {
+                warn!(detection = other, "unknown idle detection, turning it off");
+                Source::Off
+            }
+        };
+
+        IdleMonitor {
+            source,
+            timeout: Duration::from_secs(settings.timeout.max(1)),
+            last_interaction: clock::now(),
+        }
+    }
+
+    /// Record that the user pressed a key or sent a command
+    pub fn activity(&mut self, now: Instant) {
+        self.last_interaction = now;
+    }
+
+    /// When the user was last active, if that is known
+    fn last_activity(&self) -> Option<Instant> {
+        match &self.source {
+            Source::Off => None,
+            Source::Heartbeat => Some(self.last_interaction),
+            Source::System(poller) => poller
+                .last_input
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .map(|last_input| last_input.max(self.last_interaction)),
+        }
+    }
+
+    /// When the user went idle, once they have been idle for longer than the timeout
+    pub fn idle_since(&self, now: Instant) -> Option<Instant> {
+        self.last_activity().filter(|last| now.saturating_duration_since(*last) >= self.timeout)
+    }
+
+    /// Whether the user has done anything since `since`
+    pub fn active_since(&self, since: Instant) -> bool {
+        self.last_activity().is_some_and(|last| last > since)
+    }
+}
+
+/// Start a thread that keeps the time of the last system input up to date
+fn spawn_poller() -> Poller {
+    let last_input = Arc::new(Mutex::new(None));
+    let shared = Arc::clone(&last_input);
+    let (stop, stopped) = mpsc::channel();
+    std::thread::spawn(move || loop {
+        let Some(idle_ms) = system_idle_ms() else {
+            info!("system idle time unavailable, idle detection disabled");
+            *shared.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            return;
+        };
+
+        let now = Instant::now();
+        let last = now.checked_sub(Duration::from_millis(idle_ms)).unwrap_or(now);
+        *shared.lock().unwrap_or_else(|e| e.into_inner()) = Some(last);
+        // Nothing is ever sent, so this only ends early once the monitor is gone
+        if let Err(mpsc::RecvTimeoutError::Disconnected) = stopped.recv_timeout(POLL_INTERVAL) {
+            return;
+        }
+    });
+    Poller { last_input, _stop: stop }
+}
+
+/// Ask the system how long it has been since the last input
+fn system_idle_ms() -> Option<u64> {
+    if cfg!(target_os = "macos") {
+        // HIDIdleTime is reported in nanoseconds
+        let output = run("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+        let line = output.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+        let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+        Some(nanos / 1_000_000)
+    } else {
+        run("xprintidle", &[])?.trim().parse().ok()
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
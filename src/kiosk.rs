@@ -0,0 +1,133 @@
+// src/kiosk.rs
+//! Module for `--kiosk`: a timer left running on a wall display
+//!
+//! Every key is ignored but the quit chord (`kiosk_quit`), the instructions
+//! are hidden and the digits fill the terminal. The timer runs in a child
+//! process that is started again whenever it exits any other way, whether
+//! it finished, was quit over the control socket or crashed.
+
+use crate::config::Config;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_yaml::Value;
+use std::{
+    env, io,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Set in the environment of the timer the kiosk runs
+const CHILD_VAR: &str = "CLOCKIT_KIOSK_CHILD";
+
+/// Exit code of a kiosk timer quit with the chord, the only exit that isn't
+/// followed by a restart
+pub const RELEASED: i32 = 64;
+
+/// A timer exiting sooner than this after starting is taken to be failing
+/// to start at all, so it isn't restarted
+const SHORTEST_RUN: Duration = Duration::from_secs(2);
+
+/// Pause before starting the timer again
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// A key with the modifiers held down with it, like `ctrl+alt+q`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Chord {
+    /// Parse a chord: modifiers (`ctrl`, `alt`, `shift`) and a key, joined
+    /// by `+`. The key is a character, `esc`, `enter`, `space`, `tab`,
+    /// `backspace` or `f1` to `f12`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let parts: Vec<String> = text.split('+').map(|part| part.trim().to_lowercase()).collect();
+        let (key, held) = parts.split_last().filter(|(key, _)| !key.is_empty()).ok_or("no key given")?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in held {
+            modifiers |= match modifier.as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}` (expected ctrl, alt or shift)", modifier)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.as_str() {
+                "esc" => KeyCode::Esc,
+                "enter" => KeyCode::Enter,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", key)),
+                },
+            },
+        };
+        Ok(Chord { code, modifiers })
+    }
+
+    /// Whether `key` is this chord. Letters match in either case, since
+    /// terminals differ in how they report them with shift held.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let code = match key.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        code == self.code && key.modifiers.contains(self.modifiers)
+    }
+}
+
+/// Whether this process is the timer a kiosk runs, rather than the kiosk
+pub fn is_timer() -> bool {
+    env::var_os(CHILD_VAR).is_some()
+}
+
+/// Turn off what a wall display has no use for: the instructions, the
+/// question before quitting and the summary, and draw the digits as large
+/// as the terminal allows
+pub fn apply(config: &mut Config) {
+    if let Ok(Value::Mapping(mut instructions)) = serde_yaml::to_value(&config.instructions) {
+        for (_, text) in instructions.iter_mut() {
+            *text = Value::String(String::new());
+        }
+        match serde_yaml::from_value(Value::Mapping(instructions)) {
+            Ok(hidden) => config.instructions = hidden,
+            Err(e) => warn!(error = %e, "could not hide the instructions"),
+        }
+    }
+    config.auto_fit = true;
+    config.confirm_quit = false;
+    config.fade_chrome = "off".to_string();
+    config.exit_summary = "off".to_string();
+}
+
+/// Run this same command line as the kiosk's timer, again and again, until
+/// it is quit with the chord
+pub fn supervise() -> io::Result<()> {
+    let exe = env::current_exe()?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    loop {
+        let started = Instant::now();
+        info!("starting kiosk timer");
+        let status = Command::new(&exe).args(&args).env(CHILD_VAR, "1").status()?;
+        if status.code() == Some(RELEASED) {
+            info!("kiosk quit");
+            return Ok(());
+        }
+        if started.elapsed() < SHORTEST_RUN {
+            warn!(%status, "kiosk timer exited right after starting");
+            println!("The timer exited right after starting ({}), so the kiosk won't restart it.", status);
+            return Ok(());
+        }
+        warn!(%status, "kiosk timer exited, restarting");
+        thread::sleep(RESTART_DELAY);
+    }
+}
@@ -0,0 +1,207 @@
+// src/lib.rs
+//! Clockit's timer modes, rendering and configuration, shared by the
+//! `clockit` binary, the benchmarks and the integration tests
+
+pub mod accessible;
+pub mod alarms;
+pub mod alerts;
+pub mod animation;
+pub mod app;
+pub mod attach;
+pub mod caps;
+pub mod clock;
+pub mod config;
+pub mod config_cmd;
+pub mod control;
+pub mod digit;
+#[cfg(feature = "export")]
+pub mod export;
+pub mod focus_log;
+pub mod history;
+pub mod history_cmd;
+pub mod hooks;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod idle;
+pub mod kiosk;
+pub mod locale;
+pub mod logging;
+pub mod media;
+pub mod modes;
+pub mod ntp;
+pub mod popup;
+pub mod power;
+pub mod profile;
+pub mod progress;
+pub mod prompt;
+pub mod sound;
+pub mod stats_cmd;
+pub mod store;
+pub mod sun;
+pub mod theme;
+pub mod time_format;
+pub mod track;
+pub mod track_cmd;
+pub mod tui;
+pub mod ui;
+
+/// Longest time [`parse_time_string`] accepts, about a century, so timers can
+/// always add it to the current time
+pub const MAX_TIME_SECS: u64 = 100 * 365 * 24 * 60 * 60;
+
+/// Parse a time string in format "HH:MM:SS" or "MM:SS" or "SS", or with units
+/// like "1h30m", "25m" or "90s"
+/// Handles overflow in any position (e.g., 75 seconds becomes 1 minute 15 seconds)
+pub fn parse_time_string(time_str: &str) -> Result<u64, &'static str> {
+    if time_str.trim().ends_with(|c: char| c.is_ascii_alphabetic()) {
+        return parse_time_units(time_str.trim());
+    }
+
+    let parts: Vec<&str> = time_str.split(':').collect();
+    
+    // Every field needs a number, so "::5" or "5:" is a typo rather than 5 seconds
+    if parts.iter().any(|part| part.trim().is_empty()) {
+        return Err("Empty field in time");
+    }
+    
+    // Initialize counters for hours, minutes, seconds
+    let mut hours = 0;
+    let mut minutes = 0;
+    let seconds;
+    
+    match parts.len() {
+        // Just seconds
+        1 => {
+            seconds = match parts[0].trim().parse::<u64>() {
+                Ok(s) => s,
+                Err(_) => return Err("Invalid seconds format"),
+            };
+        },
+        // Minutes:Seconds
+        2 => {
+            minutes = match parts[0].trim().parse::<u64>() {
+                Ok(m) => m,
+                Err(_) => return Err("Invalid minutes format"),
+            };
+            
+            seconds = match parts[1].trim().parse::<u64>() {
+                Ok(s) => s,
+                Err(_) => return Err("Invalid seconds format"),
+            };
+        },
+        // Hours:Minutes:Seconds
+        3 => {
+            hours = match parts[0].trim().parse::<u64>() {
+                Ok(h) => h,
+                Err(_) => return Err("Invalid hours format"),
+            };
+            
+            minutes = match parts[1].trim().parse::<u64>() {
+                Ok(m) => m,
+                Err(_) => return Err("Invalid minutes format"),
+            };
+            
+            seconds = match parts[2].trim().parse::<u64>() {
+                Ok(s) => s,
+                Err(_) => return Err("Invalid seconds format"),
+            };
+        },
+        _ => return Err("Invalid time format. Use HH:MM:SS, MM:SS, SS, or 1h30m"),
+    }
+    
+    // Convert to total seconds; fields past 59 carry over into the next one
+    hours
+        .checked_mul(3600)
+        .and_then(|total| total.checked_add(minutes.checked_mul(60)?))
+        .and_then(|total| total.checked_add(seconds))
+        .filter(|total| *total <= MAX_TIME_SECS)
+        .ok_or("Time is too long")
+}
+
+/// Parse a time given in units, like "2h", "1h30m" or "45s"
+fn parse_time_units(text: &str) -> Result<u64, &'static str> {
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err("Invalid time unit"),
+        };
+        if number.is_empty() {
+            return Err("Time unit without a number");
+        }
+        let secs = number.parse::<u64>().ok().and_then(|n| n.checked_mul(unit)).ok_or("Time is too long")?;
+        total = total.checked_add(secs).ok_or("Time is too long")?;
+        number.clear();
+    }
+    Some(total).filter(|total| *total <= MAX_TIME_SECS).ok_or("Time is too long")
+}
+
+/// Add up several times, as given to `-c 1h -c 30m`
+pub fn sum_time_strings<S: AsRef<str>>(times: &[S]) -> Result<u64, &'static str> {
+    times.iter().try_fold(0u64, |total, time| {
+        let secs = parse_time_string(time.as_ref())?;
+        total.checked_add(secs).filter(|total| *total <= MAX_TIME_SECS).ok_or("Time is too long")
+    })
+}
+
+/// Parse Pomodoro configuration string in format "WORK/BREAK/CYCLES"
+/// Returns (work_minutes, break_minutes, cycles)
+/// Parts left out or empty, as in "50" or "30//8", take the default values (25, 5, 0)
+/// A cycle count of 0 means infinite cycles
+pub fn parse_pomodoro_config(config_str: &str) -> Result<(u64, u64, u64), String> {
+    // Default values
+    let default_work = 25;
+    let default_break = 5;
+    let default_cycles = 0; // 0 means infinite
+    
+    let parts: Vec<&str> = config_str.split('/').map(str::trim).collect();
+    if parts.len() > 3 {
+        return Err(format!("`{}` has more than three parts", config_str));
+    }
+    
+    // A session needs whole minutes, no more than the longest time a timer runs for
+    let minutes = |part: Option<&&str>, name: &str, default: u64| match part {
+        None | Some(&"") => Ok(default),
+        Some(part) => match part.parse::<u64>() {
+            Ok(0) => Err(format!("the {} length must be more than zero", name)),
+            Ok(m) if m <= MAX_TIME_SECS / 60 => Ok(m),
+            Ok(_) => Err(format!("the {} length `{}` is too long", name, part)),
+            Err(_) => Err(format!("the {} length `{}` is not a number of minutes", name, part)),
+        },
+    };
+    
+    let work_minutes = minutes(parts.first(), "work", default_work)?;
+    let break_minutes = minutes(parts.get(1), "break", default_break)?;
+    let cycles = match parts.get(2) {
+        None | Some(&"") => default_cycles,
+        Some(part) => part.parse::<u64>().map_err(|_| format!("the cycle count `{}` is not a number", part))?,
+    };
+    
+    Ok((work_minutes, break_minutes, cycles))
+}
+
+/// Format duration in seconds to a human-readable string
+pub fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{} seconds", seconds)
+    } else if seconds < 3600 {
+        let minutes = seconds / 60;
+        let secs = seconds % 60;
+        if secs == 0 {
+            format!("{} minutes", minutes)
+        } else {
+            format!("{} minutes {} seconds", minutes, secs)
+        }
+    } else {
+        let hours = seconds / 3600;
+        let minutes = (seconds % 3600) / 60;
+        format!("{} hours {} minutes", hours, minutes)
+    }
+}
@@ -0,0 +1,322 @@
+// src/locale.rs
+//! Module for the phrases Clockit shows on screen, in the language picked
+//! with `language` or the `LANG` environment variable
+//!
+//! English is built in. Other languages come from a catalog of YAML
+//! messages: those bundled with Clockit, or `locales/<language>.yaml` next to
+//! the configuration file, which can also override single bundled phrases.
+//! Any phrase a catalog leaves out stays in English.
+
+use crate::config::{self, merge_values, Config, InstructionSettings};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::{env, fs, path::{Path, PathBuf}, sync::OnceLock};
+use tracing::{info, warn};
+
+/// Catalogs bundled with Clockit, by language code
+const BUNDLED: [(&str, &str); 3] = [
+    ("de", include_str!("../locales/de.yaml")),
+    ("es", include_str!("../locales/es.yaml")),
+    ("fr", include_str!("../locales/fr.yaml")),
+];
+
+/// The phrases shown on screen. Placeholders in braces, like `{session}`,
+/// are filled in with [`fill`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Messages {
+    pub work_session: String,
+    pub break_session: String,
+    pub back_to_work: String,
+    pub break_time: String,
+    /// Flashed when a break is about to run out (`break_warning`)
+    pub break_ending: String,
+    /// `{session}` is the session's name
+    pub session_complete: String,
+    /// `{count}` pauses adding up to `{time}`, under a finished work session
+    pub session_interruptions: String,
+    pub no_interruptions: String,
+    /// `{cycles}` is the number of cycles run
+    pub all_cycles_complete: String,
+    pub cycle: String,
+    pub cycle_of: String,
+    pub current: String,
+    /// `{session}`, `{duration}` and `{time}`, the clock time it starts
+    pub next: String,
+    pub last_session: String,
+    pub focused_today: String,
+    /// `{time}` since the Pomodoro run began, breaks included
+    pub run_total: String,
+    /// `{time}` spent in work sessions since the Pomodoro run began
+    pub run_worked: String,
+    pub paused: String,
+    pub idle_paused: String,
+    /// Shown above the note being typed after pressing `i` in a work session
+    pub note_prompt: String,
+    /// Badge for a stopwatch running past its goal
+    pub overtime: String,
+    /// The stopwatch goal `{time}` and the `{left}` still to go
+    pub goal: String,
+    /// `{time}` is the stopwatch goal
+    pub goal_reached: String,
+    /// Above a stopwatch that stopped itself at its goal
+    pub stopped_at_goal: String,
+    /// Above the count before a stopwatch with `--warmup` starts
+    pub get_ready: String,
+    /// The latest lap: its number `{lap}` and `{time}`
+    pub lap: String,
+    /// The fastest lap: `{time}` and its number `{lap}`
+    pub best_lap: String,
+    /// The slowest lap: `{time}` and its number `{lap}`
+    pub worst_lap: String,
+    /// The average lap `{time}`
+    pub average_lap: String,
+    /// Under the stopwatches of `multi-stopwatch`, `{time}` on all of them
+    pub total: String,
+    pub times_up: String,
+    /// The prompt of a finished countdown, shown by `clockit attach`
+    pub times_up_prompt: String,
+    /// `{time}` elapsed and `{percent}` of the countdown done
+    pub elapsed: String,
+    /// `{checkpoint}` is the label of the next checkpoint, `{time}` the time until it
+    pub next_checkpoint: String,
+    /// Beneath TIME'S UP; `{presets}` lists the countdown presets by number key
+    pub presets: String,
+    /// While a countdown waits to start; `{time}` is when it starts
+    pub starts_at: String,
+    /// Label of a countdown waiting for its start time, shown by `clockit attach`
+    pub waiting_to_start: String,
+    /// Beneath the time when the terminal is too small for the big digits
+    pub too_small: String,
+    /// In the corner of the screens between Pomodoro sessions, `{time}`
+    /// before the next session starts by itself
+    pub continuing_in: String,
+    pub flow: String,
+    pub break_over: String,
+    pub standup_complete: String,
+    pub speaker: String,
+    /// The metronome's tempo, `{bpm}` beats a minute
+    pub bpm: String,
+    /// Where the metronome is: `{beat}` of the `{beats}` in bar `{bar}`
+    pub bar_beat: String,
+    /// The same, as short as it goes in the status label
+    pub bar_beat_short: String,
+    /// Above the wait before the next block of a plan starts at `{time}`
+    pub free_until: String,
+    /// Once every block of a plan is over
+    pub plan_complete: String,
+    pub any_key_to_continue: String,
+    pub any_key_to_exit: String,
+    /// Asked before `q` quits a running timer with `confirm_quit` on
+    pub confirm_quit: String,
+    /// Label of an alarm set without one
+    pub alarm: String,
+    /// Labels for the figures in the summary shown once a timer exits
+    pub summary_duration: String,
+    pub summary_focused: String,
+    pub summary_laps: String,
+    pub summary_cycles: String,
+    pub summary_interruptions: String,
+    /// Instruction lines, used wherever `instructions` in the config is left as it was
+    pub instructions: Mapping,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        let text = |text: &str| text.to_string();
+        Messages {
+            work_session: text("Work Session"),
+            break_session: text("Break"),
+            back_to_work: text("Back to Work!"),
+            break_time: text("Break Time!"),
+            break_ending: text("Break ends soon - time to head back"),
+            session_complete: text("{session} Complete!"),
+            session_interruptions: text("Interruptions: {count} ({time} paused)"),
+            no_interruptions: text("No interruptions"),
+            all_cycles_complete: text("All {cycles} Pomodoro cycles completed!"),
+            cycle: text("Cycle {cycle}"),
+            cycle_of: text("Cycle {cycle}/{cycles}"),
+            current: text("Current: {session}"),
+            next: text("Next: {session} ({duration}) at {time}"),
+            last_session: text("Last session, ends at {time}"),
+            focused_today: text("Focused today: {time}"),
+            run_total: text("Total: {time}"),
+            run_worked: text("Worked: {time}"),
+            paused: text("Paused"),
+            idle_paused: text("Paused while idle - press any key to resume"),
+            note_prompt: text("Type what interrupted you, then Enter (Esc to cancel)"),
+            overtime: text("Overtime"),
+            goal: text("Goal: {time} ({left} to go)"),
+            goal_reached: text("Goal of {time} reached!"),
+            stopped_at_goal: text("Stopped at goal"),
+            get_ready: text("Get ready..."),
+            lap: text("Lap {lap}: {time}"),
+            best_lap: text("Best {time} (lap {lap})"),
+            worst_lap: text("Worst {time} (lap {lap})"),
+            average_lap: text("Avg {time}"),
+            total: text("Total {time}"),
+            times_up: text("TIME'S UP!"),
+            times_up_prompt: text("Time's up! Press {quit_key} to exit."),
+            elapsed: text("{time} elapsed ({percent}%)"),
+            next_checkpoint: text("Next: {checkpoint} in {time}"),
+            presets: text("Start another: {presets}"),
+            starts_at: text("Starts at {time}"),
+            waiting_to_start: text("Waiting to start"),
+            too_small: text("Terminal too small for big digits"),
+            continuing_in: text("Continuing in {time}"),
+            flow: text("Flow"),
+            break_over: text("Break over!"),
+            standup_complete: text("Standup complete!"),
+            speaker: text("Speaker {speaker} of {speakers}"),
+            bpm: text("{bpm} bpm"),
+            bar_beat: text("Bar {bar}, beat {beat} of {beats}"),
+            bar_beat_short: text("Bar {bar}, beat {beat}/{beats}"),
+            free_until: text("Free until {time}"),
+            plan_complete: text("Plan complete"),
+            any_key_to_continue: text("Press any key to continue"),
+            any_key_to_exit: text("Press any key to exit"),
+            confirm_quit: text("Really quit? y/n"),
+            alarm: text("Alarm"),
+            summary_duration: text("Duration"),
+            summary_focused: text("Focused"),
+            summary_laps: text("Laps"),
+            summary_cycles: text("Cycles"),
+            summary_interruptions: text("Interruptions"),
+            instructions: Mapping::new(),
+        }
+    }
+}
+
+impl Messages {
+    /// Whether the time's up banner should be drawn from the big built-in
+    /// glyphs, which only spell it in English
+    pub fn english_times_up(&self) -> bool {
+        self.times_up == Messages::default().times_up
+    }
+}
+
+static MESSAGES: OnceLock<Messages> = OnceLock::new();
+
+/// The phrases in the selected language (English until [`init`] is called)
+pub fn messages() -> &'static Messages {
+    MESSAGES.get_or_init(Messages::default)
+}
+
+/// Fill in the `{name}` placeholders of a phrase
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in values {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Load the catalog for the configured language, and use its instruction
+/// lines wherever the config doesn't reword them. User catalogs are looked for
+/// next to the configuration file, the one given with `--config` (`config_flag`) if any.
+pub fn init(config: &mut Config, config_flag: Option<&Path>) {
+    let language = language(config);
+    let messages = load(&language, config_flag).unwrap_or_default();
+    localize_instructions(&mut config.instructions, &messages.instructions);
+    let _ = MESSAGES.set(messages);
+}
+
+/// The language code to use, e.g. "de" for `de_DE.UTF-8`
+fn language(config: &Config) -> String {
+    let setting = if config.language.trim().is_empty() {
+        // The first locale variable that is set decides, as for other programs
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+    } else {
+        config.language.clone()
+    };
+
+    let code: String = setting.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    match code.to_lowercase().as_str() {
+        // The C locale means no language in particular
+        "" | "c" | "posix" => "en".to_string(),
+        code => code.to_string(),
+    }
+}
+
+/// Path of a user catalog, next to the configuration file
+fn user_catalog_path(language: &str, config_flag: Option<&Path>) -> Option<PathBuf> {
+    let config_path = config::get_config_path(config_flag).ok()?;
+    Some(config_path.parent()?.join("locales").join(format!("{}.yaml", language)))
+}
+
+/// The catalog for `language`, or None to stay in English
+fn load(language: &str, config_flag: Option<&Path>) -> Option<Messages> {
+    let mut catalog = Value::Mapping(Mapping::new());
+    let mut found = false;
+
+    if let Some((_, bundled)) = BUNDLED.iter().find(|(code, _)| *code == language) {
+        match serde_yaml::from_str::<Value>(bundled) {
+            Ok(values) => {
+                merge_values(&mut catalog, &values);
+                found = true;
+            }
+            Err(e) => warn!(language, error = %e, "unreadable bundled catalog"),
+        }
+    }
+
+    if let Some(path) = user_catalog_path(language, config_flag).filter(|path| path.exists()) {
+        match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|contents| {
+            serde_yaml::from_str::<Value>(&contents).map_err(|e| e.to_string())
+        }) {
+            Ok(values) => {
+                merge_values(&mut catalog, &values);
+                found = true;
+            }
+            Err(e) => {
+                warn!(path = ?path, error = %e, "unreadable message catalog");
+                eprintln!("Could not read {}: {}. Using English.", path.display(), e);
+                return None;
+            }
+        }
+    }
+
+    if !found {
+        if language != "en" {
+            info!(language, "no message catalog, using English");
+        }
+        return None;
+    }
+    match serde_yaml::from_value(catalog) {
+        Ok(messages) => {
+            info!(language, "loaded message catalog");
+            Some(messages)
+        }
+        Err(e) => {
+            warn!(language, error = %e, "invalid message catalog");
+            eprintln!("Invalid messages for {}: {}. Using English.", language, e);
+            None
+        }
+    }
+}
+
+/// Replace each instruction line still worded as the English default with
+/// the catalog's, leaving the ones set in the config alone
+fn localize_instructions(instructions: &mut InstructionSettings, localized: &Mapping) {
+    if localized.is_empty() {
+        return;
+    }
+    let (Ok(Value::Mapping(defaults)), Ok(Value::Mapping(mut current))) =
+        (serde_yaml::to_value(InstructionSettings::default()), serde_yaml::to_value(&*instructions))
+    else {
+        return;
+    };
+
+    for (key, text) in localized {
+        if current.get(key).is_some_and(|value| defaults.get(key) == Some(value)) {
+            current.insert(key.clone(), text.clone());
+        }
+    }
+    match serde_yaml::from_value(Value::Mapping(current)) {
+        Ok(localized) => *instructions = localized,
+        Err(e) => warn!(error = %e, "invalid localized instructions"),
+    }
+}
@@ -0,0 +1,41 @@
+// src/logging.rs
+//! Module for writing diagnostic logs to a file
+//!
+//! Anything printed to the terminal while the timer is running either corrupts
+//! the display or disappears with the alternate screen, so diagnostics go to
+//! a log file instead.
+
+use crate::store;
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::PathBuf,
+    sync::Mutex,
+};
+use tracing::level_filters::LevelFilter;
+
+/// Get the path to the log file
+pub fn get_log_path() -> io::Result<PathBuf> {
+    Ok(store::get_state_dir()?.join("clockit.log"))
+}
+
+/// Start appending log records at or above `level` to the log file
+pub fn init(level: LevelFilter) -> io::Result<()> {
+    if level == LevelFilter::OFF {
+        return Ok(());
+    }
+
+    let log_path = get_log_path()?;
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&log_path)?;
+
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(Mutex::new(file))
+        .init();
+
+    Ok(())
+}
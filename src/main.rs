@@ -1,30 +1,49 @@
 // src/main.rs
-mod config;
-mod digit;
-
-use clap::Parser;
-use config::Config;
-use crossterm::{
-    cursor,
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    style::{self, Color, Stylize},
-    terminal::{self, Clear, ClearType},
-    ExecutableCommand,
+use clockit::{
+    alarms, alerts, app, attach, caps, clock, config, config_cmd, control, format_duration, history_cmd, kiosk, locale,
+    logging, modes, parse_pomodoro_config, parse_time_string, popup, power, prompt, sound, stats_cmd, store,
+    sum_time_strings, theme, track, track_cmd, tui, ui,
 };
+#[cfg(feature = "export")]
+use clockit::export;
+
+use alerts::Alerts;
+use app::{ExitSummary, RunOptions};
+use chrono::{Local, NaiveDate, SubsecRound};
+use clap::{ArgGroup, Parser, Subcommand};
+use config::{AlertSettings, Config};
+use history_cmd::ExportFormat;
+use modes::{
+    load_plan, parse_deadline, parse_tempo, parse_timer, Build, Chain, Countdown, Cube, Flowtime, Kitchen, Metronome,
+    Mode, MultiStopwatch, Plan, Pomodoro, Sequence, Standup, Step, Stopwatch, WallClock, Watched, MAX_STOPWATCHES,
+    TECHNIQUES,
+};
+use ratatui::style::Color;
+use sound::SoundPlayer;
+use store::{ResumeMode, SavedCountdown};
+use theme::{ThemeSchedule, ThemeSwitch};
+use track::TrackedTime;
 use std::{
-    io::{self, stdout, Write},
-    thread,
-    time::{Duration, Instant},
+    io,
+    path::PathBuf,
+    time::Duration,
 };
+use tracing::{info, level_filters::LevelFilter};
 
 /// A beautiful ASCII art timer for the terminal
 #[derive(Parser)]
 #[command(name = "clockit")]
 #[command(about = "A beautiful ASCII art timer for the terminal", long_about = None)]
+#[command(group(ArgGroup::new("pomodoro_mode").args(["pomodoro", "technique"]).multiple(true)))]
 struct Cli {
-    /// Start a countdown timer in HH:MM:SS format
-    #[arg(short = 'c', long = "countdown")]
-    countdown: Option<String>,
+    /// Start a countdown timer in HH:MM:SS format or with units like 1h30m. Give it more
+    /// than once to add the times up, or leave the time out to be asked for it
+    #[arg(short = 'c', long = "countdown", value_name = "TIME", num_args = 0..=1, default_missing_value = "")]
+    countdown: Vec<String>,
+
+    /// With more than one --countdown, run them one after another instead of adding them up
+    #[arg(long = "list", requires = "countdown", conflicts_with = "name")]
+    list: bool,
 
     /// Start a stopwatch
     #[arg(short = 's', long = "stopwatch", default_value_t = false)]
@@ -35,784 +54,1033 @@ struct Cli {
     #[arg(short = 'p', long = "pomodoro", num_args = 0..=1, default_missing_value = "")]
     pomodoro: Option<String>,
     
-    /// Generate a default config file
-    #[arg(long = "init-config", default_value_t = false)]
-    init_config: bool,
-}
+    /// Start a Pomodoro-style timer using a built-in technique: classic (four 25-minute
+    /// sessions, then a long break), 52-17, or ultradian (90 minutes on, 20 off)
+    #[arg(long = "technique", value_name = "NAME", conflicts_with = "pomodoro")]
+    technique: Option<String>,
+    
+    /// Start a Flowtime session: work as long as you like, then press b for a break
+    /// sized to match (a fifth of the work time by default)
+    #[arg(long = "flowtime", default_value_t = false, conflicts_with_all = ["pomodoro", "technique", "countdown", "stopwatch"])]
+    flowtime: bool,
+    
+    /// Run a standup: a countdown for each of these comma-separated speakers in turn
+    #[arg(long = "standup", value_name = "NAMES", conflicts_with_all = ["pomodoro", "technique", "flowtime", "countdown", "stopwatch"])]
+    standup: Option<String>,
+    
+    /// Keep several named timers going at once, adding more with a while it runs;
+    /// optionally start with comma-separated timers like "pasta 9:00,rice 15:00"
+    #[arg(long = "kitchen", value_name = "TIMERS", num_args = 0..=1, default_missing_value = "", conflicts_with_all = ["pomodoro", "technique", "flowtime", "standup", "countdown", "stopwatch"])]
+    kitchen: Option<String>,
+    
+    /// Time Rubik's cube solves: a 15-second inspection, then the solve, keeping
+    /// best and average times for the session
+    #[arg(long = "cube", default_value_t = false, conflicts_with_all = ["pomodoro", "technique", "flowtime", "standup", "kitchen", "countdown", "stopwatch"])]
+    cube: bool,
+    
+    /// Count down to the time or deadline in FILE, like 25:00 or "at 17:30", starting
+    /// over whenever the file changes
+    #[arg(long = "watch-file", value_name = "FILE", conflicts_with_all = ["pomodoro", "technique", "flowtime", "standup", "kitchen", "cube", "countdown", "stopwatch", "presentation"])]
+    watch_file: Option<PathBuf>,
+    
+    /// Flash the screen and click on every beat at a tempo like 60bpm, counting the
+    /// beats of each bar; space taps a new tempo
+    #[arg(long = "metronome", value_name = "TEMPO", conflicts_with_all = ["pomodoro", "technique", "flowtime", "standup", "kitchen", "cube", "watch_file", "countdown", "stopwatch", "presentation"])]
+    metronome: Option<String>,
+    
+    /// Show the time of day in big digits, with the date and ISO week beneath
+    #[arg(long = "clock", default_value_t = false, conflicts_with_all = ["pomodoro", "technique", "flowtime", "standup", "kitchen", "cube", "watch_file", "metronome", "countdown", "stopwatch", "presentation"])]
+    clock: bool,
+    
+    /// With --metronome, the beats in a bar (metronome.beats_per_bar in the config, 4 by default)
+    #[arg(long = "beats", value_name = "N", requires = "metronome")]
+    beats: Option<u32>,
+    
+    /// With --standup, how long each speaker gets
+    #[arg(long = "per-speaker", value_name = "TIME", default_value = "2:00", requires = "standup")]
+    per_speaker: String,
+    
+    /// Start a countdown for a talk that turns the whole screen green, then amber,
+    /// then red as time runs out
+    #[arg(long = "presentation", value_name = "TIME", conflicts_with_all = ["countdown", "stopwatch", "pomodoro", "technique", "flowtime", "standup"])]
+    presentation: Option<String>,
+    
+    /// With --presentation, when to turn amber: time remaining (5:00) or a share elapsed (75%)
+    #[arg(long = "amber", value_name = "AT", default_value = "5:00", requires = "presentation")]
+    amber: String,
+    
+    /// With --presentation, when to turn red: time remaining (1:00) or a share elapsed (90%)
+    #[arg(long = "red", value_name = "AT", default_value = "1:00", requires = "presentation")]
+    red: String,
+    
+    /// With --stopwatch, a target time in HH:MM:SS format to alert at once reached
+    #[arg(long = "goal", value_name = "TIME", requires = "stopwatch")]
+    goal: Option<String>,
 
-/// Parse a time string in format "HH:MM:SS" or "MM:SS" or "SS"
-/// Handles overflow in any position (e.g., 75 seconds becomes 1 minute 15 seconds)
-fn parse_time_string(time_str: &str) -> Result<u64, &'static str> {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    
-    // Initialize counters for hours, minutes, seconds
-    let mut hours = 0;
-    let mut minutes = 0;
-    let mut seconds;
-    
-    match parts.len() {
-        // Just seconds
-        1 => {
-            seconds = match parts[0].trim().parse::<u64>() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid seconds format"),
-            };
-        },
-        // Minutes:Seconds
-        2 => {
-            minutes = match parts[0].trim().parse::<u64>() {
-                Ok(m) => m,
-                Err(_) => return Err("Invalid minutes format"),
-            };
-            
-            seconds = match parts[1].trim().parse::<u64>() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid seconds format"),
-            };
-        },
-        // Hours:Minutes:Seconds
-        3 => {
-            hours = match parts[0].trim().parse::<u64>() {
-                Ok(h) => h,
-                Err(_) => return Err("Invalid hours format"),
-            };
-            
-            minutes = match parts[1].trim().parse::<u64>() {
-                Ok(m) => m,
-                Err(_) => return Err("Invalid minutes format"),
-            };
-            
-            seconds = match parts[2].trim().parse::<u64>() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid seconds format"),
-            };
-        },
-        _ => return Err("Invalid time format. Use HH:MM:SS, MM:SS, or SS"),
-    }
+    /// With --stopwatch, count down SECS in big digits, beeping each second, before it starts
+    #[arg(long = "warmup", value_name = "SECS", requires = "stopwatch")]
+    warmup: Option<u64>,
+
+    /// Stop the stopwatch when it reaches its --goal
+    #[arg(long = "stop-at-goal", default_value_t = false, requires = "goal")]
+    stop_at_goal: bool,
     
-    // Handle overflow
-    if seconds >= 60 {
-        minutes += seconds / 60;
-        seconds %= 60;
-    }
+    /// With --countdown, ring the bell at this point: a share elapsed (50%) or time
+    /// remaining (5:00). Can be given more than once; adds to the alerts in the config.
+    #[arg(long = "alert", value_name = "AT", requires = "countdown")]
+    alert: Vec<String>,
     
-    if minutes >= 60 {
-        hours += minutes / 60;
-        minutes %= 60;
-    }
+    /// With --countdown, a labeled checkpoint like 45:00=Section A: the label and the time
+    /// until it show above the timer, and the bell rings as it is crossed. Can be given
+    /// more than once.
+    #[arg(long = "checkpoint", value_name = "AT=LABEL", requires = "countdown")]
+    checkpoint: Vec<String>,
     
-    // Convert to total seconds
-    let total_seconds = hours * 3600 + minutes * 60 + seconds;
-    Ok(total_seconds)
-}
+    /// With --countdown, wait this long before it starts, e.g. 30s or 5:00
+    #[arg(long = "start-in", value_name = "TIME", requires = "countdown", conflicts_with = "start_at")]
+    start_in: Option<String>,
+
+    /// With --countdown, wait until this time of day before it starts, e.g. 14:00
+    #[arg(long = "start-at", value_name = "TIME", requires = "countdown")]
+    start_at: Option<String>,
 
-/// Parse Pomodoro configuration string in format "WORK/BREAK/CYCLES"
-/// Returns (work_minutes, break_minutes, cycles)
-/// If no configuration is provided or parsing fails, returns default values (25, 5, 0)
-/// A cycle count of 0 means infinite cycles
-fn parse_pomodoro_config(config_str: &str) -> (u64, u64, u64) {
-    // Default values
-    let default_work = 25;
-    let default_break = 5;
-    let default_cycles = 0; // 0 means infinite
+    /// With --countdown or --presentation, show the time elapsed and the percentage
+    /// done beneath the digits
+    #[arg(long = "show-elapsed", conflicts_with_all = ["stopwatch", "pomodoro", "technique", "flowtime", "standup"])]
+    show_elapsed: bool,
     
-    let parts: Vec<&str> = config_str.split('/').collect();
+    /// Once the timer finishes, go on to MODE in the same session: stopwatch, pomodoro,
+    /// or a countdown TIME. Can be given more than once.
+    #[arg(long = "then", value_name = "MODE", conflicts_with_all = ["persist", "name"])]
+    then: Vec<String>,
     
-    // If empty string or not enough parts, return defaults
-    if config_str.is_empty() || parts.len() < 1 {
-        return (default_work, default_break, default_cycles);
-    }
+    /// Exit as soon as the timer finishes instead of waiting for a key
+    #[arg(long = "exit-when-finished", default_value_t = false)]
+    exit_when_finished: bool,
     
-    // Parse work minutes
-    let work_minutes = match parts[0].parse::<u64>() {
-        Ok(w) if w > 0 => w,
-        _ => default_work,
-    };
+    /// Ring the bell and pulse the digits every TIME during a countdown or stopwatch
+    #[arg(long = "chime-every", value_name = "TIME", conflicts_with_all = ["pomodoro", "technique"])]
+    chime_every: Option<String>,
     
-    // Parse break minutes if provided
-    let break_minutes = if parts.len() > 1 {
-        match parts[1].parse::<u64>() {
-            Ok(b) if b > 0 => b,
-            _ => default_break,
-        }
-    } else {
-        default_break
-    };
+    /// With --stopwatch, save the elapsed time under NAME on exit and resume from it next time
+    #[arg(long = "persist", value_name = "NAME", requires = "stopwatch")]
+    persist: Option<String>,
     
-    // Parse cycles if provided
-    let cycles = if parts.len() > 2 {
-        match parts[2].parse::<u64>() {
-            Ok(c) => c,
-            _ => default_cycles,
-        }
-    } else {
-        default_cycles
-    };
+    /// With --countdown, save the time left under NAME on exit so it can be carried
+    /// on later with `clockit resume NAME`
+    #[arg(long = "name", value_name = "NAME", requires = "countdown")]
+    name: Option<String>,
+    
+    /// With --pomodoro, append a line for each completed work session to this file
+    /// (Markdown if it ends in .md, plain text otherwise)
+    #[arg(long = "log-to", value_name = "FILE", requires = "pomodoro_mode")]
+    log_to: Option<PathBuf>,
+
+    /// With --pomodoro, what the work sessions are for; saved in the history and focus log
+    #[arg(long = "task", value_name = "TEXT", requires = "pomodoro_mode")]
+    task: Option<String>,
     
-    (work_minutes, break_minutes, cycles)
+    /// Read settings from this file instead of the default location
+    /// (also set with CLOCKIT_CONFIG; skips any project .clockit.yaml)
+    #[arg(long = "config", value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+
+    /// Write a config file, asking for the digit colors, blinking, Pomodoro
+    /// lengths and sound
+    #[arg(long = "init-config", default_value_t = false)]
+    init_config: bool,
+
+    /// With --init-config, write the defaults without asking
+    #[arg(long = "yes", requires = "init_config")]
+    yes: bool,
+
+    /// With --init-config, replace the config file if there already is one
+    #[arg(long = "force", requires = "init_config")]
+    force: bool,
+
+    /// Print what the terminal supports (colors, Unicode, alternate screen) and
+    /// how Clockit adapts to it
+    #[arg(long = "caps", default_value_t = false)]
+    caps: bool,
+
+    /// Accept remote commands (pause, resume, add SECS, skip, status, quit) on a Unix socket
+    /// Uses $XDG_RUNTIME_DIR/clockit.sock when no path is given
+    #[arg(long = "control-socket", value_name = "PATH", num_args = 0..=1, default_missing_value = "")]
+    control_socket: Option<PathBuf>,
+
+    /// Accept the same commands written as lines to a named pipe at PATH, created if
+    /// it doesn't exist (e.g. echo pause > PATH)
+    #[arg(long = "control-fifo", value_name = "PATH")]
+    control_fifo: Option<PathBuf>,
+
+    /// Serve timer status and controls over HTTP on this address (e.g. 127.0.0.1:7878)
+    #[cfg(feature = "http")]
+    #[arg(long = "serve", value_name = "ADDR")]
+    serve: Option<std::net::SocketAddr>,
+
+    /// Print plain-text updates instead of ASCII art, for use with screen readers
+    #[arg(long = "accessible", default_value_t = false)]
+    accessible: bool,
+
+    /// Time every frame drawn and print the average and worst render times and the
+    /// bytes written per frame on exit
+    #[arg(long = "profile", default_value_t = false)]
+    profile: bool,
+
+    /// Print nothing but errors and play no sounds or bell, for scripts and
+    /// recorded terminal sessions
+    #[arg(long = "quiet", default_value_t = false)]
+    quiet: bool,
+
+    /// On exit, print one JSON object describing the run (mode, requested and actual
+    /// time, whether it finished, laps and cycles) instead of the closing message
+    #[arg(long = "json-summary", default_value_t = false)]
+    json_summary: bool,
+
+    /// Save the theme picked with t to the configuration file on exit without asking
+    #[arg(long = "save-theme", default_value_t = false)]
+    save_theme: bool,
+
+    /// Run as a wall display: ignore every key but the kiosk_quit chord (ctrl+alt+q by
+    /// default), hide the instructions, fit the digits to the terminal and start the
+    /// timer again whenever it exits
+    #[arg(long = "kiosk", default_value_t = false, conflicts_with = "accessible")]
+    kiosk: bool,
+
+    /// Write diagnostic logs at this level (error, warn, info, debug, trace) to the log file
+    #[arg(long = "log-level", value_name = "LEVEL", default_value_t = LevelFilter::OFF)]
+    log_level: LevelFilter,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
 }
 
-fn main() -> io::Result<()> {
+#[derive(Subcommand)]
+enum Commands {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Work with the history of completed sessions
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Summarize the history of completed sessions
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+    /// Track time on a project with a stopwatch, logged when it exits
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Track {
+        /// Project to log the time under
+        #[arg(long = "project", value_name = "NAME", required = true)]
+        project: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<TrackAction>,
+    },
+    /// Mirror a timer running with --control-socket in another terminal, read-only
+    Attach {
+        /// Control socket of the timer (default: $XDG_RUNTIME_DIR/clockit.sock)
+        #[arg(long = "socket", value_name = "PATH")]
+        socket: Option<PathBuf>,
+    },
+    /// Run a timer in a tmux popup sized to fit it, closing once it finishes
+    Popup {
+        /// The timer's options, e.g. `-c 5:00`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true, value_name = "TIMER")]
+        args: Vec<String>,
+    },
+    /// Ring the alarms set in the config at their times of day
+    Alarms {
+        #[command(subcommand)]
+        action: AlarmsAction,
+    },
+    /// Follow a day of time blocks from a plan file, like "09:00 deep work 90m"
+    Plan {
+        /// YAML file listing the blocks
+        file: PathBuf,
+    },
+    /// Keep a stopwatch for each task, started and stopped with the number keys
+    MultiStopwatch {
+        /// Comma-separated tasks, one stopwatch each (at most 9), like "task a,task b"
+        #[arg(long = "labels", value_name = "LABELS")]
+        labels: String,
+    },
+    /// Carry on with a countdown saved with --name
+    Resume {
+        /// Name the countdown was saved under
+        name: String,
+    },
+    /// Manage stopwatches saved with --persist
+    Stopwatch {
+        /// Forget the elapsed time saved under NAME
+        #[arg(long = "reset", value_name = "NAME")]
+        reset: String,
+    },
+    /// Start a countdown of these times added up, e.g. `clockit countdown 25m 5m`
+    Countdown {
+        /// Times in HH:MM:SS format or with units like 1h30m, asked for if left out
+        #[arg(value_name = "TIME")]
+        times: Vec<String>,
+
+        /// Run the times one after another instead of adding them up
+        #[arg(long = "list")]
+        list: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// Write completed sessions as CSV or JSON, or work sessions as calendar events
+    Export {
+        /// Output format
+        #[arg(long = "format", value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+
+        /// Only include sessions started on or after this date (YYYY-MM-DD)
+        #[arg(long = "since", value_name = "DATE")]
+        since: Option<NaiveDate>,
+
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrackAction {
+    /// Show the hours tracked on each project, day by day
+    Report {
+        /// Number of days to cover, counting today
+        #[arg(long = "days", value_name = "N", default_value_t = 7)]
+        days: u64,
+    },
+    /// Send tracked time still queued for Toggl or Clockify
+    #[cfg(feature = "export")]
+    Sync,
+}
+
+#[derive(Subcommand)]
+enum AlarmsAction {
+    /// Wait for each alarm and ring it, until Ctrl+C
+    Run,
+    /// Show the alarms and when each rings next
+    List,
+}
+
+#[derive(Subcommand)]
+enum StatsAction {
+    /// Show a calendar of focused minutes per day
+    Heatmap {
+        /// Number of weeks to show (default: as many as fit, up to a year)
+        #[arg(long = "weeks", value_name = "N")]
+        weeks: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective configuration and where each value came from
+    Show,
+    /// Print the path of the configuration file
+    Path,
+    /// Open the configuration file in $VISUAL or $EDITOR
+    Edit,
+    /// Check the configuration file for unknown keys and invalid values
+    Validate,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> io::Result<()> {
     let cli = Cli::parse();
+    tui::install_panic_hook();
     
-    // Load configuration
-    let config = Config::load()?;
-    println!("Loaded configuration:");
-    println!("  blink_separator = {}", config.blink_separator);
-    println!("  countdown_color = {}", config.colors.countdown);
-    println!("  stopwatch_color = {}", config.colors.stopwatch);
-    println!("  countdown_refresh_rate = {}ms", config.countdown_refresh_rate);
-    println!("  pomodoro_work_duration = {}min", config.pomodoro.work_duration);
-    println!("  pomodoro_break_duration = {}min", config.pomodoro.break_duration);
-    println!("  pomodoro_cycles = {}", if config.pomodoro.cycles == 0 { "∞".to_string() } else { config.pomodoro.cycles.to_string() });
-    
-    // Handle --init-config flag
-    if cli.init_config {
-        println!("Configuration file initialized.");
+    // Logging is optional, so a log file that can't be opened shouldn't stop the timer
+    if let Err(e) = logging::init(cli.log_level) {
+        eprintln!("Could not open log file: {}. Logging disabled.", e);
+    }
+    
+    let config_flag = cli.config.as_deref();
+    // Scripts reading the JSON summary need it to be all that is printed
+    let quiet = cli.quiet || cli.json_summary;
+    
+    if cli.caps {
+        return caps::print_report();
+    }
+    
+    // Handle subcommands
+    if let Some(Commands::Stopwatch { reset }) = &cli.command {
+        if store::reset_stopwatch(reset)? {
+            println!("Reset stopwatch '{}'.", reset);
+        } else {
+            println!("No saved stopwatch named '{}'.", reset);
+        }
         return Ok(());
     }
 
-    // Handle pomodoro mode
-    if let Some(pomodoro_config) = cli.pomodoro.as_deref() {
-        // If custom parameters are provided, use them; otherwise, use config defaults
-        let (work_minutes, break_minutes, cycles) = if pomodoro_config.is_empty() {
-            // Use config file defaults
-            (config.pomodoro.work_duration, config.pomodoro.break_duration, config.pomodoro.cycles)
-        } else {
-            // Parse command line parameters
-            parse_pomodoro_config(pomodoro_config)
+    if let Some(Commands::History { action }) = &cli.command {
+        return match action {
+            HistoryAction::Export { format, since, output } => {
+                history_cmd::export(*format, *since, output.as_deref())
+            }
         };
-        
-        println!("Starting Pomodoro timer ({}min work, {}min break, {} cycles)",
-                work_minutes, break_minutes, if cycles == 0 { "∞".to_string() } else { cycles.to_string() });
-        return run_pomodoro_with_config(&config, work_minutes, break_minutes, cycles);
     }
 
-    // Handle countdown
-    if let Some(time_str) = cli.countdown {
-        match parse_time_string(&time_str) {
-            Ok(total_seconds) => {
-                if total_seconds == 0 {
-                    println!("Please specify a valid countdown time greater than zero.");
-                    return Ok(());
+    if let Some(Commands::Track { action: Some(TrackAction::Report { days }), .. }) = &cli.command {
+        return track_cmd::report(*days);
+    }
+
+    if let Some(Commands::Config { action }) = &cli.command {
+        return match action {
+            ConfigAction::Show => config_cmd::show(config_flag, quiet),
+            ConfigAction::Path => config_cmd::path(config_flag),
+            ConfigAction::Edit => config_cmd::edit(config_flag, quiet),
+            ConfigAction::Validate => {
+                if !config_cmd::validate(config_flag)? {
+                    std::process::exit(1);
                 }
-                return run_countdown(total_seconds, &config);
-            },
-            Err(e) => {
-                println!("Error parsing time: {}. Use format HH:MM:SS, MM:SS, or SS.", e);
-                return Ok(());
+                Ok(())
             }
-        }
+        };
     }
     
-    // Handle stopwatch
-    if cli.stopwatch {
-        return run_stopwatch(&config);
+    // Before loading, which would write a default file of its own
+    if cli.init_config {
+        return config_cmd::init(config_flag, cli.force, cli.yes);
     }
     
-    // If no valid options provided, show usage
-    println!("No valid command specified. Use -c/--countdown TIME, -s/--stopwatch, or -p/--pomodoro");
-    Ok(())
-}
-
-// Helper function to reduce screen flicker by only updating what changed
-fn stable_display(
-    stdout: &mut io::Stdout, 
-    ascii_time: &[String], 
-    last_display: &mut Option<Vec<String>>,
-    x_pos: u16,
-    y_pos: u16,
-    color: Color,
-) -> io::Result<()> {
-    // If this is the first time or the display size has changed
-    if last_display.is_none() || last_display.as_ref().unwrap().len() != ascii_time.len() {
-        // Display entire ascii art
-        for (i, line) in ascii_time.iter().enumerate() {
-            stdout.execute(cursor::MoveTo(x_pos, y_pos + i as u16))?;
-            stdout.execute(style::PrintStyledContent(
-                line.to_string().with(color)
-            ))?;
-        }
-        *last_display = Some(ascii_time.to_vec());
-        return Ok(());
+    // Load configuration
+    let mut config = Config::load(config_flag, quiet)?;
+    locale::init(&mut config, config_flag);
+    power::apply(&mut config);
+    if cli.quiet {
+        // Every sound, the bell included, is played through these settings
+        config.sounds.mute();
     }
     
-    // Only update lines that have changed
-    let prev_display = last_display.as_ref().unwrap();
-    for (i, (new_line, old_line)) in ascii_time.iter().zip(prev_display.iter()).enumerate() {
-        if new_line != old_line {
-            stdout.execute(cursor::MoveTo(x_pos, y_pos + i as u16))?;
-            // Clear the old line first
-            stdout.execute(Clear(ClearType::CurrentLine))?;
-            stdout.execute(cursor::MoveTo(x_pos, y_pos + i as u16))?;
-            stdout.execute(style::PrintStyledContent(
-                new_line.to_string().with(color)
-            ))?;
-        }
+    #[cfg(feature = "export")]
+    if let Some(Commands::Track { action: Some(TrackAction::Sync), .. }) = &cli.command {
+        return match export::Exporter::from_settings(&config.export) {
+            Ok(Some(exporter)) => exporter.send(&[]),
+            Ok(None) => {
+                println!("Set export.service to toggl or clockify to send tracked time.");
+                Ok(())
+            }
+            Err(e) => {
+                println!("Can't send tracked time: {}.", e);
+                Ok(())
+            }
+        };
     }
-    
-    // Update the saved display
-    *last_display = Some(ascii_time.to_vec());
-    Ok(())
-}
 
-fn run_countdown(total_seconds: u64, config: &Config) -> io::Result<()> {
-    let mut stdout = stdout();
-    let start_time = Instant::now();
-    let end_time = start_time + Duration::from_secs(total_seconds);
-    
-    // For tracking display changes
-    let mut last_display: Option<Vec<String>> = None;
-
-    // Setup terminal
-    terminal::enable_raw_mode()?;
-    stdout.execute(terminal::EnterAlternateScreen)?;
-    stdout.execute(cursor::Hide)?;
-
-    // Clear screen once at the beginning
-    stdout.execute(Clear(ClearType::All))?;
-    
-    // Display instructions (only once)
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press q or Ctrl+C to exit".with(config.ui_text_color())
-    ))?;
-    
-    // Main timer loop
-    loop {
-        // Check for exit key (q or Ctrl+C)
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-                if code == KeyCode::Char('q') || 
-                   (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-                    break;
-                }
-            }
-        }
-        
-        let now = Instant::now();
-        if now >= end_time {
-            // Timer complete
-            show_time_up(&mut stdout, config)?;
-            break;
-        }
-        
-        let remaining = end_time - now;
-        let remaining_secs = remaining.as_secs();
-        let minutes = remaining_secs / 60;
-        let seconds = remaining_secs % 60;
-        
-        // Format time based on the original length
-        let display_time = if minutes >= 60 {
-            let hours = minutes / 60;
-            let mins = minutes % 60;
-            format!("{}:{:02}:{:02}", hours, mins, seconds)
-        } else {
-            format!("{}:{:02}", minutes, seconds)
+    if let Some(Commands::Attach { socket }) = &cli.command {
+        let path = socket.clone().unwrap_or_else(control::default_socket_path);
+        return attach::attach(&path, &config).await;
+    }
+
+    if let Some(Commands::Alarms { action }) = &cli.command {
+        return match action {
+            AlarmsAction::Run => alarms::run(&config).await,
+            AlarmsAction::List => alarms::list(&config),
         };
-        
-        // If blinking is enabled, alternate the colon visibility
-        let display_with_blink = if config.blink_separator {
-            // Toggle blink state about once per second
-            // Use the time since start for consistent blinking
-            let blink_on = (now.duration_since(start_time).as_millis() / 500) % 2 == 0;
-            
-            if blink_on {
-                display_time
-            } else {
-                // Replace colons with spaces when blinked off
-                display_time.replace(':', " ")
-            }
-        } else {
-            display_time
+    }
+
+    if let Some(Commands::Popup { args }) = &cli.command {
+        return popup::popup(args, &config, config_flag);
+    }
+
+    if let Some(Commands::Stats { action }) = &cli.command {
+        return match action {
+            StatsAction::Heatmap { weeks } => stats_cmd::heatmap(&config, *weeks),
         };
-        
-        // Get ASCII art representation
-        let ascii_time = digit::render_time(&display_with_blink);
-        
-        // Display ASCII art time centered on screen
-        let (term_width, term_height) = terminal::size()?;
-        let time_width = ascii_time[0].len() as u16;
-        let time_height = ascii_time.len() as u16;
-        
-        let x_pos = (term_width - time_width) / 2;
-        let y_pos = (term_height - time_height) / 2;
-        
-        // Use our stable display function to avoid flickering
-        stable_display(&mut stdout, &ascii_time, &mut last_display, x_pos, y_pos, config.countdown_color())?;
-        
-        stdout.flush()?;
-        thread::sleep(Duration::from_millis(config.countdown_refresh_rate));
     }
 
-    // Cleanup
-    stdout.execute(cursor::Show)?;
-    stdout.execute(terminal::LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
-    
-    println!("Timer complete!");
-    Ok(())
-}
+    let kiosk = match cli.kiosk.then(|| kiosk::Chord::parse(&config.kiosk_quit)).transpose() {
+        Ok(kiosk) => kiosk,
+        Err(e) => {
+            println!("Error parsing kiosk_quit `{}`: {}. Use keys like ctrl+alt+q.", config.kiosk_quit, e);
+            return Ok(());
+        }
+    };
+    if kiosk.is_some() {
+        if !kiosk::is_timer() {
+            return kiosk::supervise();
+        }
+        kiosk::apply(&mut config);
+    }
+    // Picks the theme for the time of day, so it comes before anything is drawn
+    let theme_schedule = ThemeSchedule::from_config(&mut config);
 
-fn show_time_up(stdout: &mut io::Stdout, config: &Config) -> io::Result<()> {
-    let time_up_text = vec![
-        "┌┬┐┬┌┬┐┌─┐ ┬┌─┐  ┬ ┬┌─┐┬",
-        " │ ││││├┤  │└─┐  │ │├─┘│",
-        " ┴ ┴┴ ┴└─┘ ┴└─┘  └─┘┴  o",
-    ];
-    
-    // Get terminal size
-    let (term_width, term_height) = terminal::size()?;
-    
-    // Calculate the width of the text (accounting for possible unicode width issues)
-    // Using a fixed width for each string to ensure proper centering
-    let text_width = 27u16; // Adjust this value if needed to match the actual width
-    let text_height = time_up_text.len() as u16;
-    
-    // Calculate the position to center the text
-    let x_pos = (term_width.saturating_sub(text_width)) / 2;
-    let y_pos = (term_height.saturating_sub(text_height)) / 2;
-    
-    // Flash "TIME'S UP!" a few times
-    for i in 0..5 {
-        stdout.execute(Clear(ClearType::All))?;
-        
-        // Always display instructions at the top
-        stdout.execute(cursor::MoveTo(0, 0))?;
-        stdout.execute(style::PrintStyledContent(
-            "Press q or Ctrl+C to exit".with(config.ui_text_color())
-        ))?;
-        
-        // Only display TIME'S UP on even iterations (creates flashing effect)
-        if i % 2 == 0 {
-            for (j, line) in time_up_text.iter().enumerate() {
-                // Center each line individually to ensure perfect alignment
-                stdout.execute(cursor::MoveTo(x_pos, y_pos + j as u16))?;
-                stdout.execute(style::PrintStyledContent(
-                    line.to_string().with(config.times_up_color()).bold()
-                ))?;
+    let options = RunOptions {
+        control_socket: cli.control_socket.map(|path| {
+            if path.as_os_str().is_empty() {
+                control::default_socket_path()
+            } else {
+                path
             }
+        }),
+        control_fifo: cli.control_fifo,
+        signals: config.signals.clone(),
+        accessible: cli.accessible.then(|| config.accessibility.clone()),
+        fade_chrome: ui::ChromeFade::from_config(&config),
+        profile: cli.profile,
+        quiet,
+        json_summary: cli.json_summary,
+        themes: Some(ThemeSwitch::new(&config, config_flag, cli.save_theme)),
+        theme_schedule,
+        confirm_quit: config.confirm_quit,
+        // Scripts waiting for the timer to finish shouldn't wait on the summary too
+        summary: match ExitSummary::from_config(&config) {
+            ExitSummary::Screen if cli.exit_when_finished && quiet => ExitSummary::Off,
+            ExitSummary::Screen if cli.exit_when_finished => ExitSummary::Print,
+            summary => summary,
+        },
+        kiosk,
+        #[cfg(feature = "http")]
+        serve: cli.serve,
+    };
+
+    let chime_every = match cli.chime_every.as_deref().map(parse_time_string) {
+        Some(Ok(secs)) if secs > 0 => Some(Duration::from_secs(secs)),
+        Some(Ok(_)) => {
+            println!("Please specify a chime interval greater than zero.");
+            return Ok(());
         }
-        
-        stdout.flush()?;
-        
-        // Check for exit key during the flashing animation
-        let start = Instant::now();
-        while start.elapsed() < Duration::from_millis(500) {
-            if event::poll(Duration::from_millis(50))? {
-                if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-                    if code == KeyCode::Char('q') || 
-                       (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-                        return Ok(());
-                    }
-                }
+        Some(Err(e)) => {
+            println!("Error parsing chime interval: {}. Use format HH:MM:SS, MM:SS, or SS.", e);
+            return Ok(());
+        }
+        None => None,
+    };
+    
+    let mut steps = Vec::new();
+    for step in &cli.then {
+        match chain_step(step, &config, chime_every) {
+            Ok(step) => steps.push(step),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
             }
         }
     }
     
-    // After flashing, keep showing the "TIME'S UP!" message until user exits
-    stdout.execute(Clear(ClearType::All))?;
-    
-    // Display instructions at the top
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press q or Ctrl+C to exit".with(config.ui_text_color())
-    ))?;
-    
-    // Display final "TIME'S UP!" message
-    for (j, line) in time_up_text.iter().enumerate() {
-        stdout.execute(cursor::MoveTo(x_pos, y_pos + j as u16))?;
-        stdout.execute(style::PrintStyledContent(
-            line.to_string().with(config.times_up_color()).bold()
-        ))?;
+    if let Some(Commands::Resume { name }) = &cli.command {
+        let Some(saved) = store::load_countdown(name)? else {
+            let names = store::countdown_names()?;
+            if names.is_empty() {
+                println!("No saved countdown named '{}'. Save one with -c TIME --name NAME.", name);
+            } else {
+                println!("No saved countdown named '{}'. Saved countdowns: {}.", name, names.join(", "));
+            }
+            return Ok(());
+        };
+        let remaining = saved.remaining(ResumeMode::from_config(&config), Local::now());
+        if remaining.is_zero() {
+            store::forget_countdown(name)?;
+            println!("Countdown '{}' ran out while it was suspended.", name);
+            return Ok(());
+        }
+
+        info!(name, remaining_ms = remaining.as_millis() as u64, "resuming countdown");
+        let mut countdown = saved.resume(remaining, &config);
+        if !config.alerts.is_empty() {
+            let alerts = Alerts::new(&config.alerts, |name| config.parse_color(name).into());
+            countdown = countdown.with_alerts(alerts, SoundPlayer::spawn(true, &config.sounds));
+        }
+        let result = app::run(&mut countdown, options).await;
+        suspend_countdown(name, &countdown, quiet)?;
+        return result;
     }
-    
-    stdout.flush()?;
-    
-    // Wait for user to exit
-    loop {
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-                if code == KeyCode::Char('q') || 
-                   (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-                    break;
+
+    if let Some(path) = &cli.watch_file {
+        let build: Build = {
+            let config = config.clone();
+            Box::new(move |total_seconds| {
+                let mut countdown = Countdown::new(total_seconds, &config);
+                if !config.alerts.is_empty() {
+                    let alerts = Alerts::new(&config.alerts, |name| config.parse_color(name).into());
+                    countdown = countdown.with_alerts(alerts, SoundPlayer::spawn(true, &config.sounds));
+                }
+                if let Some(interval) = chime_every {
+                    countdown = countdown.with_chime(interval, SoundPlayer::spawn(true, &config.sounds));
                 }
+                if cli.show_elapsed {
+                    countdown = countdown.with_elapsed_line();
+                }
+                countdown
+            })
+        };
+        let watched = match Watched::open(path.clone(), build) {
+            Ok(watched) => watched,
+            Err(e) => {
+                println!("Error reading the watched file: {}. Write a time like 25:00 or 25m, or a deadline like at 17:30.", e);
+                return Ok(());
+            }
+        };
+        return run_chain(watched, steps, cli.exit_when_finished, options).await;
+    }
+
+    if let Some(Commands::Plan { file }) = &cli.command {
+        let blocks = match load_plan(file) {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                println!("Error reading plan: {}. List blocks like \"09:00 deep work 90m\".", e);
+                return Ok(());
             }
+        };
+        info!(blocks = blocks.len(), "starting plan");
+        let plan = Plan::new(&config, blocks, SoundPlayer::spawn(true, &config.sounds));
+        return run_chain(plan, steps, cli.exit_when_finished, options).await;
+    }
+
+    if let Some(Commands::MultiStopwatch { labels }) = &cli.command {
+        let labels: Vec<String> =
+            labels.split(',').map(str::trim).filter(|label| !label.is_empty()).map(str::to_string).collect();
+        if labels.is_empty() || labels.len() > MAX_STOPWATCHES {
+            println!("Please give between 1 and {} comma-separated labels, e.g. \"task a,task b\".", MAX_STOPWATCHES);
+            return Ok(());
         }
+        info!(stopwatches = labels.len(), "starting multiple stopwatches");
+        let stopwatches = MultiStopwatch::new(&config, labels);
+        return run_chain(stopwatches, steps, cli.exit_when_finished, options).await;
     }
-    
-    Ok(())
-}
 
-/// Run the Pomodoro timer with default settings (25min work, 5min break, infinite cycles)
-/// This function is now used internally by run_pomodoro_with_config
-#[allow(dead_code)]
-fn run_pomodoro(config: &Config) -> io::Result<()> {
-    run_pomodoro_with_config(config, 25, 5, 0)
-}
+    if let Some(Commands::Track { project: Some(project), .. }) = &cli.command {
+        info!(project, "tracking time");
+        let started_at = Local::now();
+        let mut stopwatch = Stopwatch::new(&config).with_label(format!("Tracking: {}", project));
+        let result = app::run(&mut stopwatch, options).await;
+        let tracked = stopwatch.elapsed(clock::now());
+        if tracked.as_secs() == 0 {
+            println!("Nothing tracked for {}.", project);
+            return result;
+        }
 
-/// Run the Pomodoro timer with custom settings
-/// cycles = 0 means run indefinitely
-fn run_pomodoro_with_config(config: &Config, work_minutes: u64, break_minutes: u64, cycles: u64) -> io::Result<()> {
-    let mut stdout = stdout();
-    let mut cycle = 1;
-    let work_time = work_minutes * 60; // convert to seconds
-    let break_time = break_minutes * 60; // convert to seconds
-    
-    // Setup terminal
-    terminal::enable_raw_mode()?;
-    stdout.execute(terminal::EnterAlternateScreen)?;
-    stdout.execute(cursor::Hide)?;
-
-    // Clear screen once at the beginning
-    stdout.execute(Clear(ClearType::All))?;
-    
-    loop {
-        // Check if we've reached the desired number of cycles
-        if cycles > 0 && cycle > cycles {
-            // Display a message that all cycles are completed
-            stdout.execute(Clear(ClearType::All))?;
-            
-            // Get terminal size for centering
-            let (term_width, term_height) = terminal::size()?;
-            
-            let message = format!("All {} Pomodoro cycles completed!", cycles);
-            let msg_x = (term_width as usize).saturating_sub(message.len()) / 2;
-            let msg_y = term_height / 2;
-            
-            stdout.execute(cursor::MoveTo(msg_x as u16, msg_y))?;
-            stdout.execute(style::PrintStyledContent(
-                message.with(config.times_up_color()).bold()
-            ))?;
-            
-            stdout.execute(cursor::MoveTo(0, 0))?;
-            stdout.execute(style::PrintStyledContent(
-                "Press any key to exit".with(config.ui_text_color())
-            ))?;
-            
-            stdout.flush()?;
-            
-            // Wait for user input to exit
-            event::read()?;
-            break;
+        let entry = TrackedTime {
+            project: project.clone(),
+            started_at: started_at.trunc_subsecs(0),
+            ended_at: Local::now().trunc_subsecs(0),
+            duration_secs: tracked.as_secs(),
+        };
+        track::record(&entry)?;
+        if !quiet {
+            println!("Tracked {} on {}.", format_duration(tracked.as_secs()), project);
         }
-        
-        // Display cycle information
-        let cycle_info = if cycles > 0 {
-            format!("Cycle {}/{}", cycle, cycles)
+        #[cfg(feature = "export")]
+        match export::Exporter::from_settings(&config.export) {
+            Ok(Some(exporter)) => exporter.send(&[entry])?,
+            Ok(None) => {}
+            Err(e) => println!("Not sending tracked time: {}. It's saved locally.", e),
+        }
+        return result;
+    }
+
+    // Handle pomodoro mode, with the configured sequence or a built-in technique
+    let pomodoro_session = if let Some(name) = cli.technique.as_deref() {
+        let Some(sequence) = Sequence::technique(name, &config) else {
+            println!("Unknown technique: {}. Choose one of: {}.", name, TECHNIQUES.join(", "));
+            return Ok(());
+        };
+        let cycles = config.pomodoro.cycles;
+        if !quiet {
+            println!("Starting Pomodoro timer ({} technique, {} cycles)", name, cycles_text(cycles));
+        }
+        info!(technique = name, cycles, "starting pomodoro");
+        Some((sequence, cycles))
+    } else if let Some(pomodoro_config) = cli.pomodoro.as_deref() {
+        // If custom parameters are provided, use them; otherwise, use config defaults
+        let (work_minutes, break_minutes, cycles) = if pomodoro_config.is_empty() {
+            // Use config file defaults
+            (config.pomodoro.work_duration, config.pomodoro.break_duration, config.pomodoro.cycles)
         } else {
-            format!("Cycle {}", cycle)
+            // Parse command line parameters
+            match parse_pomodoro_config(pomodoro_config) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    println!("Error parsing Pomodoro settings: {}. Use format WORK/BREAK/CYCLES, e.g. 50/10/4.", e);
+                    return Ok(());
+                }
+            }
         };
         
-        // Work session
-        let session_name = format!("Work Session #{}", cycle);
-        
-        // Show work session info at top of terminal
-        stdout.execute(Clear(ClearType::All))?;
-        stdout.execute(cursor::MoveTo(0, 0))?;
-        stdout.execute(style::PrintStyledContent(
-            "Press q or Ctrl+C to exit".with(config.ui_text_color())
-        ))?;
-        
-        stdout.execute(cursor::MoveTo(0, 1))?;
-        stdout.execute(style::PrintStyledContent(
-            cycle_info.with(config.ui_text_color())
-        ))?;
-        
-        // Run work session with is_work_session = true
-        if !run_pomodoro_session(&mut stdout, &session_name, work_time, true, config)? {
-            break; // User quit
+        if quiet {
+            // Nothing to announce
+        } else if config.pomodoro.sequence.is_empty() {
+            println!("Starting Pomodoro timer ({}min work, {}min break, {} cycles)",
+                    work_minutes, break_minutes, cycles_text(cycles));
+        } else {
+            println!("Starting Pomodoro timer ({}, {} cycles)",
+                    config.pomodoro.sequence.join(" → "), cycles_text(cycles));
         }
-        
-        // Show a message that it's break time
-        if !display_phase_change(&mut stdout, "Break Time!", config)? {
-            break; // User quit
+        info!(work_minutes, break_minutes, cycles, "starting pomodoro");
+        let sequence = Sequence::from_config(
+            &config,
+            Duration::from_secs(work_minutes * 60),
+            Duration::from_secs(break_minutes * 60),
+        );
+        Some((sequence, cycles))
+    } else {
+        None
+    };
+
+    if let Some(names) = cli.standup.as_deref() {
+        let names: Vec<String> = names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        if names.is_empty() {
+            println!("Please name at least one speaker, e.g. --standup \"alice,bob,carol\".");
+            return Ok(());
         }
-        
-        // Break session
-        let session_name = format!("Break #{}", cycle);
-        // Run break session with is_work_session = false
-        if !run_pomodoro_session(&mut stdout, &session_name, break_time, false, config)? {
-            break; // User quit
+        let per_speaker = match parse_time_string(&cli.per_speaker) {
+            Ok(secs) if secs > 0 => Duration::from_secs(secs),
+            Ok(_) => {
+                println!("Please give each speaker more than zero seconds.");
+                return Ok(());
+            }
+            Err(e) => {
+                println!("Error parsing time per speaker: {}", e);
+                return Ok(());
+            }
+        };
+
+        if !quiet {
+            println!("Starting standup ({} speakers, {} each)", names.len(), format_duration(per_speaker.as_secs()));
         }
-        
-        // Show a message that it's work time again
-        if cycles == 0 || cycle < cycles {
-            if !display_phase_change(&mut stdout, "Back to Work!", config)? {
-                break; // User quit
+        info!(speakers = names.len(), per_speaker_secs = per_speaker.as_secs(), "starting standup");
+        let standup = Standup::new(&config, &names, per_speaker, SoundPlayer::spawn(true, &config.sounds));
+        return run_chain(standup, steps, cli.exit_when_finished, options).await;
+    }
+
+    if let Some(timers) = cli.kitchen.as_deref() {
+        let mut parsed = Vec::new();
+        for (i, timer) in timers.split(',').filter(|timer| !timer.trim().is_empty()).enumerate() {
+            match parse_timer(timer, &format!("Timer {}", i + 1)) {
+                Ok(timer) => parsed.push(timer),
+                Err(e) => {
+                    println!("Error parsing kitchen timer {}: {}. Use a name and a time, e.g. \"pasta 9:00\".", timer.trim(), e);
+                    return Ok(());
+                }
             }
         }
-        
-        // Increment cycle counter
-        cycle += 1;
+        info!(timers = parsed.len(), "starting kitchen timers");
+        let kitchen = Kitchen::new(&config, parsed, SoundPlayer::spawn(true, &config.sounds));
+        return run_chain(kitchen, steps, cli.exit_when_finished, options).await;
     }
-    
-    // Cleanup
-    stdout.execute(cursor::Show)?;
-    stdout.execute(terminal::LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
-    
-    println!("Pomodoro timer ended. Completed {} full cycles.", cycle - 1);
-    Ok(())
-}
 
-/// Display a phase change message between Pomodoro sessions
-/// Returns true if user wants to continue, false if they want to quit
-fn display_phase_change(stdout: &mut io::Stdout, message: &str, config: &Config) -> io::Result<bool> {
-    stdout.execute(Clear(ClearType::All))?;
-    
-    // Get terminal size
-    let (term_width, term_height) = terminal::size()?;
-    
-    // Display instructions at the top
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press q or Ctrl+C to exit, any other key to continue".with(config.ui_text_color())
-    ))?;
-    
-    // Display the phase change message centered
-    let msg_x = (term_width as usize).saturating_sub(message.len()) / 2;
-    let msg_y = term_height / 2;
-    
-    stdout.execute(cursor::MoveTo(msg_x as u16, msg_y))?;
-    stdout.execute(style::PrintStyledContent(
-        message.to_string().with(config.times_up_color()).bold()
-    ))?;
-    
-    stdout.flush()?;
-    
-    // Wait for user input to continue or quit
-    if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-        if code == KeyCode::Char('q') || 
-           (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-            return Ok(false);
+    if cli.cube {
+        // Starting on release needs a terminal that reports it; otherwise a press starts the solve
+        let hold_to_start = tui::supports_key_release();
+        info!(hold_to_start, "starting cube timer");
+        let cube = Cube::new(&config, hold_to_start);
+        return run_chain(cube, steps, cli.exit_when_finished, options).await;
+    }
+
+    if cli.clock {
+        info!("starting clock");
+        let clock = WallClock::new(&config);
+        return run_chain(clock, steps, cli.exit_when_finished, options).await;
+    }
+
+    if let Some(tempo) = &cli.metronome {
+        let bpm = match parse_tempo(tempo) {
+            Ok(bpm) => bpm,
+            Err(e) => {
+                println!("Error parsing tempo: {}. Use beats per minute, e.g. 60bpm.", e);
+                return Ok(());
+            }
+        };
+        let beats_per_bar = cli.beats.unwrap_or(config.metronome.beats_per_bar);
+        info!(bpm, beats_per_bar, "starting metronome");
+        let sound = SoundPlayer::spawn(config.metronome.sound_enabled, &config.sounds);
+        let metronome = Metronome::new(&config, bpm, beats_per_bar, sound);
+        return run_chain(metronome, steps, cli.exit_when_finished, options).await;
+    }
+
+    if cli.flowtime {
+        if !quiet {
+            println!("Starting Flowtime (breaks are {}% of the work before them)",
+                    (config.flowtime.break_fraction * 100.0).round());
         }
+        info!(break_fraction = config.flowtime.break_fraction, "starting flowtime");
+        let flowtime = Flowtime::new(&config, SoundPlayer::spawn(config.flowtime.sound_enabled, &config.sounds));
+        return run_chain(flowtime, steps, cli.exit_when_finished, options).await;
     }
-    
-    Ok(true)
-}
 
-/// Run a single session of the Pomodoro timer (either work or break)
-/// Returns true if the session completed normally, false if user quit
-fn run_pomodoro_session(
-    stdout: &mut io::Stdout, 
-    session_name: &str, 
-    duration_secs: u64, 
-    is_work_session: bool, // New parameter to identify session type
-    config: &Config
-) -> io::Result<bool> {
-    let start_time = Instant::now();
-    let end_time = start_time + Duration::from_secs(duration_secs);
-    
-    // For tracking display changes
-    let mut last_display: Option<Vec<String>> = None;
-    
-    // Select color based on session type
-    let color = if is_work_session {
-        config.pomodoro_work_color()
-    } else {
-        config.pomodoro_break_color()
+    if let Some((sequence, cycles)) = pomodoro_session {
+        // Each phase decides whether it rings the bell
+        let sound = SoundPlayer::spawn(true, &config.sounds);
+        let mut pomodoro = Pomodoro::new(&config, sequence, cycles, sound);
+        if let Some(task) = cli.task {
+            pomodoro = pomodoro.with_task(task);
+        }
+        if let Some(path) = cli.log_to {
+            pomodoro = pomodoro.with_focus_log(path);
+        }
+        return run_chain(pomodoro, steps, cli.exit_when_finished, options).await;
+    }
+
+    // Handle countdown, or a presentation timer built on one. Several times are
+    // added up, or with --list run in turn like --then steps.
+    let (mut countdown_times, list) = match &cli.command {
+        Some(Commands::Countdown { times, list }) => (times.clone(), *list),
+        _ => (cli.countdown.clone(), cli.list),
     };
+    // `-c` without a time, or `clockit countdown` without any, asks for one
+    let wants_countdown = !countdown_times.is_empty() || matches!(cli.command, Some(Commands::Countdown { .. }));
+    countdown_times.retain(|time| !time.is_empty());
+    if wants_countdown && countdown_times.is_empty() {
+        match prompt::ask_countdown_time()? {
+            Some(time) => countdown_times.push(time),
+            None => {
+                println!("No countdown time given. Use -c TIME, e.g. -c 5:00 or -c 25m.");
+                return Ok(());
+            }
+        }
+    }
+    let countdown_time = match &cli.presentation {
+        Some(time) => Some(parse_time_string(time)),
+        None if countdown_times.is_empty() => None,
+        // Every time has to parse, but the first runs on its own
+        None if list => Some(sum_time_strings(&countdown_times).and_then(|_| parse_time_string(&countdown_times[0]))),
+        None => Some(sum_time_strings(&countdown_times)),
+    };
+    if let Some(parsed) = countdown_time {
+        match parsed {
+            Ok(total_seconds) => {
+                let empty_step = list && countdown_times.iter().any(|time| parse_time_string(time) == Ok(0));
+                if total_seconds == 0 || empty_step {
+                    println!("Please specify a valid countdown time greater than zero.");
+                    return Ok(());
+                }
+                if list {
+                    let mut times = Vec::new();
+                    for time in &countdown_times[1..] {
+                        match chain_step(time, &config, chime_every) {
+                            Ok(step) => times.push(step),
+                            Err(e) => {
+                                println!("{}", e);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    steps.splice(0..0, times);
+                }
+                let delay = match start_delay(cli.start_in.as_deref(), cli.start_at.as_deref()) {
+                    Ok(delay) => delay,
+                    Err(e) => {
+                        println!("{}", e);
+                        return Ok(());
+                    }
+                };
+                info!(total_seconds, "starting countdown");
+                let mut countdown = Countdown::new(total_seconds, &config);
+                if let Some(delay) = delay {
+                    info!(delay_secs = delay.as_secs(), "waiting before the countdown starts");
+                    countdown = countdown.with_delay(delay);
+                }
+                let mut alert_settings = config.alerts.clone();
+                alert_settings.extend(cli.alert.iter().map(|at| AlertSettings::at(at)));
+                for checkpoint in &cli.checkpoint {
+                    match alerts::parse_checkpoint(checkpoint) {
+                        Ok(settings) => alert_settings.push(settings),
+                        Err(e) => {
+                            println!("Error parsing checkpoint: {}. Use a time remaining or share elapsed and a label, e.g. 45:00=Section A.", e);
+                            return Ok(());
+                        }
+                    }
+                }
+                if cli.presentation.is_some() {
+                    // Silent, so the bell doesn't interrupt the speaker
+                    for (at, color) in [(&cli.amber, "yellow"), (&cli.red, "red")] {
+                        alert_settings.push(AlertSettings { sound: false, color: Some(color.to_string()), ..AlertSettings::at(at) });
+                    }
+                    countdown = countdown.with_backdrop(Color::Green);
+                }
+                if !alert_settings.is_empty() {
+                    let alerts = Alerts::new(&alert_settings, |name| config.parse_color(name).into());
+                    countdown = countdown.with_alerts(alerts, SoundPlayer::spawn(true, &config.sounds));
+                }
+                if let Some(interval) = chime_every {
+                    countdown = countdown.with_chime(interval, SoundPlayer::spawn(true, &config.sounds));
+                }
+                if cli.show_elapsed {
+                    countdown = countdown.with_elapsed_line();
+                }
+                let Some(name) = &cli.name else {
+                    return run_chain(countdown, steps, cli.exit_when_finished, options).await;
+                };
+                let result = app::run(&mut countdown, options).await;
+                suspend_countdown(name, &countdown, quiet)?;
+                return result;
+            },
+            Err(e) => {
+                println!("Error parsing time: {}. Use format HH:MM:SS, MM:SS, SS, or 1h30m.", e);
+                return Ok(());
+            }
+        }
+    }
     
-    // Display instructions and session info
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press q or Ctrl+C to exit".with(config.ui_text_color())
-    ))?;
-    
-    stdout.execute(cursor::MoveTo(0, 2))?;
-    stdout.execute(style::PrintStyledContent(
-        format!("Current: {}", session_name).with(config.ui_text_color())
-    ))?;
-    
-    // Main timer loop
-    loop {
-        // Check for exit key (q or Ctrl+C)
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-                if code == KeyCode::Char('q') || 
-                   (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-                    return Ok(false); // User quit
+    // Handle stopwatch
+    if cli.stopwatch {
+        let mut stopwatch = Stopwatch::new(&config);
+        if let Some(name) = &cli.persist {
+            if let Some(elapsed) = store::load_stopwatch(name)? {
+                info!(name, elapsed_ms = elapsed.as_millis() as u64, "resuming stopwatch");
+                stopwatch = stopwatch.with_elapsed(elapsed);
+            }
+        }
+        if let Some(goal_str) = cli.goal {
+            match parse_time_string(&goal_str) {
+                Ok(goal_seconds) if goal_seconds > 0 => {
+                    info!(goal_seconds, "setting stopwatch goal");
+                    let goal = Duration::from_secs(goal_seconds);
+                    stopwatch = stopwatch.with_goal(goal, cli.stop_at_goal, SoundPlayer::spawn(true, &config.sounds));
+                }
+                Ok(_) => {
+                    println!("Please specify a goal greater than zero.");
+                    return Ok(());
+                }
+                Err(e) => {
+                    println!("Error parsing goal: {}. Use format HH:MM:SS, MM:SS, or SS.", e);
+                    return Ok(());
                 }
             }
         }
-        
-        let now = Instant::now();
-        if now >= end_time {
-            // Session complete
-            show_session_complete(stdout, session_name, config)?;
-            return Ok(true); // Session completed normally
+
+        if let Some(interval) = chime_every {
+            stopwatch = stopwatch.with_chime(interval, SoundPlayer::spawn(true, &config.sounds));
         }
-        
-        let remaining = end_time - now;
-        let remaining_secs = remaining.as_secs();
-        let minutes = remaining_secs / 60;
-        let seconds = remaining_secs % 60;
-        
-        // Format time
-        let display_time = format!("{}:{:02}", minutes, seconds);
-        
-        // Apply blinking effect if enabled
-        let display_with_blink = if config.blink_separator {
-            let blink_on = (now.duration_since(start_time).as_millis() / 500) % 2 == 0;
-            if blink_on { display_time } else { display_time.replace(':', " ") }
-        } else {
-            display_time
+        if let Some(secs) = cli.warmup {
+            stopwatch = stopwatch.with_warmup(secs, SoundPlayer::spawn(true, &config.sounds));
+        }
+
+        info!("starting stopwatch");
+        let Some(name) = &cli.persist else {
+            return run_chain(stopwatch, steps, cli.exit_when_finished, options).await;
         };
-        
-        // Get ASCII art representation
-        let ascii_time = digit::render_time(&display_with_blink);
-        
-        // Display ASCII art time centered on screen
-        let (term_width, term_height) = terminal::size()?;
-        let time_width = ascii_time[0].len() as u16;
-        let time_height = ascii_time.len() as u16;
-        
-        let x_pos = (term_width - time_width) / 2;
-        let y_pos = (term_height - time_height) / 2;
-        
-        // Use our stable display function to avoid flickering
-        stable_display(stdout, &ascii_time, &mut last_display, x_pos, y_pos, color)?;
-        
-        stdout.flush()?;
-        // Use the pomodoro-specific refresh rate
-        thread::sleep(Duration::from_millis(config.pomodoro.refresh_rate));
+        let result = app::run(&mut stopwatch, options).await;
+        store::save_stopwatch(name, stopwatch.elapsed(clock::now()))?;
+        if !quiet {
+            println!("Saved stopwatch '{}'. Resume it with --persist {}.", name, name);
+        }
+        return result;
     }
+    
+    // If no valid options provided, show usage
+    println!("No valid command specified. Use -c/--countdown TIME, -s/--stopwatch, --presentation TIME, -p/--pomodoro, --flowtime, --standup NAMES, --kitchen, --cube, --metronome TEMPO, or --clock");
+    Ok(())
 }
 
-/// Format duration in seconds to a human-readable string
-/// This function is currently unused after removing the timer info display
-#[allow(dead_code)]
-fn format_duration(seconds: u64) -> String {
-    if seconds < 60 {
-        format!("{} seconds", seconds)
-    } else if seconds < 3600 {
-        let minutes = seconds / 60;
-        let secs = seconds % 60;
-        if secs == 0 {
-            format!("{} minutes", minutes)
-        } else {
-            format!("{} minutes {} seconds", minutes, secs)
-        }
-    } else {
-        let hours = seconds / 3600;
-        let minutes = (seconds % 3600) / 60;
-        format!("{} hours {} minutes", hours, minutes)
+/// How long to wait before the countdown starts, given `--start-in` or `--start-at`
+fn start_delay(start_in: Option<&str>, start_at: Option<&str>) -> Result<Option<Duration>, String> {
+    if let Some(time) = start_in {
+        return parse_time_string(time)
+            .map(|secs| Some(Duration::from_secs(secs)))
+            .map_err(|e| format!("Error parsing --start-in: {}. Use format HH:MM:SS, MM:SS, SS, or 1h30m.", e));
     }
+    let Some(time) = start_at else {
+        return Ok(None);
+    };
+    let now = Local::now();
+    let deadline =
+        parse_deadline(time, now).map_err(|e| format!("Error parsing --start-at: {}. Use a time like 14:00.", e))?;
+    Ok(Some((deadline - now).to_std().unwrap_or_default()))
 }
 
-/// Show a session complete message
-fn show_session_complete(stdout: &mut io::Stdout, session_name: &str, config: &Config) -> io::Result<()> {
-    stdout.execute(Clear(ClearType::All))?;
-    
-    // Get terminal size
-    let (term_width, term_height) = terminal::size()?;
-    
-    // Display instructions at the top
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press any key to continue".with(config.ui_text_color())
-    ))?;
-    
-    // Display session complete message
-    let message = format!("{} Complete!", session_name);
-    let msg_x = (term_width as usize).saturating_sub(message.len()) / 2;
-    let msg_y = term_height / 2;
-    
-    stdout.execute(cursor::MoveTo(msg_x as u16, msg_y))?;
-    stdout.execute(style::PrintStyledContent(
-        message.with(config.times_up_color()).bold()
-    ))?;
-    
-    stdout.flush()?;
-    
-    // Wait for any key press
-    event::read()?;
-    
+/// Save the time left on a named countdown for `clockit resume`, or forget
+/// it once it has run out
+fn suspend_countdown(name: &str, countdown: &Countdown, quiet: bool) -> io::Result<()> {
+    let status = countdown.status(clock::now());
+    let remaining = status.remaining.unwrap_or_default();
+    if status.finished || remaining.is_zero() {
+        store::forget_countdown(name)?;
+        return Ok(());
+    }
+
+    let saved = SavedCountdown {
+        length_secs: countdown.length().as_secs(),
+        remaining_ms: remaining.as_millis().try_into().unwrap_or(u64::MAX),
+        paused: status.paused,
+        saved_at: Local::now(),
+    };
+    store::save_countdown(name, saved)?;
+    if quiet {
+        return Ok(());
+    }
+    println!(
+        "Saved countdown '{}' with {} left. Carry on with: clockit resume {}",
+        name,
+        format_duration(remaining.as_secs()),
+        name
+    );
     Ok(())
 }
 
-fn run_stopwatch(config: &Config) -> io::Result<()> {
-    let mut stdout = stdout();
-    let start_time = Instant::now();
-    
-    // For tracking display changes
-    let mut last_display: Option<Vec<String>> = None;
-
-    // Setup terminal
-    terminal::enable_raw_mode()?;
-    stdout.execute(terminal::EnterAlternateScreen)?;
-    stdout.execute(cursor::Hide)?;
-    
-    // Clear screen once at the beginning
-    stdout.execute(Clear(ClearType::All))?;
-    
-    // Display instructions (only once)
-    stdout.execute(cursor::MoveTo(0, 0))?;
-    stdout.execute(style::PrintStyledContent(
-        "Press q or Ctrl+C to exit".with(config.ui_text_color())
-    ))?;
-
-    // Main stopwatch loop
-    loop {
-        // Check for exit key (q or Ctrl+C)
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-                if code == KeyCode::Char('q') || 
-                   (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
-                    break;
-                }
-            }
-        }
-        
-        let now = Instant::now();
-        let elapsed = now - start_time;
-        let elapsed_secs = elapsed.as_secs();
-        let minutes = elapsed_secs / 60;
-        let seconds = elapsed_secs % 60;
-        let centisecs = elapsed.subsec_millis() / 10;
-        
-        // Format time
-        let display_time = format!("{}:{:02}.{:02}", minutes, seconds, centisecs);
-        
-        // If blinking is enabled, alternate the colon visibility
-        let display_with_blink = if config.blink_separator {
-            // Toggle blink state about once per second
-            let blink_on = (elapsed.as_millis() / 500) % 2 == 0;
-            
-            if blink_on {
-                display_time
-            } else {
-                // Replace colons with spaces when blinked off
-                display_time.replace(':', " ")
+/// Run `mode`, followed by the `--then` steps
+async fn run_chain<M: Mode + 'static>(
+    mut mode: M,
+    steps: Vec<Step>,
+    exit_when_finished: bool,
+    options: RunOptions,
+) -> io::Result<()> {
+    if steps.is_empty() && !exit_when_finished {
+        return app::run(&mut mode, options).await;
+    }
+    let mut chain = Chain::new(Box::new(mode), steps);
+    if exit_when_finished {
+        chain = chain.exit_when_finished();
+    }
+    app::run(&mut chain, options).await
+}
+
+/// Parse a `--then` argument into a step that builds the mode when its turn comes
+fn chain_step(step: &str, config: &Config, chime_every: Option<Duration>) -> Result<Step, String> {
+    let config = config.clone();
+    match step.to_lowercase().as_str() {
+        "stopwatch" => Ok(Box::new(move || {
+            let mut stopwatch = Stopwatch::new(&config);
+            if let Some(interval) = chime_every {
+                stopwatch = stopwatch.with_chime(interval, SoundPlayer::spawn(true, &config.sounds));
             }
-        } else {
-            display_time
-        };
-        
-        // Get ASCII art representation
-        let ascii_time = digit::render_time(&display_with_blink);
-        
-        // Display ASCII art time centered on screen
-        let (term_width, term_height) = terminal::size()?;
-        let time_width = ascii_time[0].len() as u16;
-        let time_height = ascii_time.len() as u16;
-        
-        let x_pos = (term_width - time_width) / 2;
-        let y_pos = (term_height - time_height) / 2;
-        
-        // Use our stable display function
-        stable_display(&mut stdout, &ascii_time, &mut last_display, x_pos, y_pos, config.stopwatch_color())?;
-        
-        stdout.flush()?;
-        thread::sleep(Duration::from_millis(config.stopwatch_refresh_rate));
+            Box::new(stopwatch)
+        })),
+        "pomodoro" => Ok(Box::new(move || {
+            let settings = &config.pomodoro;
+            let sequence = Sequence::from_config(
+                &config,
+                Duration::from_secs(settings.work_duration * 60),
+                Duration::from_secs(settings.break_duration * 60),
+            );
+            Box::new(Pomodoro::new(&config, sequence, settings.cycles, SoundPlayer::spawn(true, &config.sounds)))
+        })),
+        time => match parse_time_string(time) {
+            Ok(total_seconds) if total_seconds > 0 => Ok(Box::new(move || {
+                let mut countdown = Countdown::new(total_seconds, &config);
+                if let Some(interval) = chime_every {
+                    countdown = countdown.with_chime(interval, SoundPlayer::spawn(true, &config.sounds));
+                }
+                Box::new(countdown)
+            })),
+            _ => Err(format!(
+                "Unknown --then step: {}. Use stopwatch, pomodoro, or a countdown time like 5:00.",
+                step
+            )),
+        },
     }
+}
 
-    // Cleanup
-    stdout.execute(cursor::Show)?;
-    stdout.execute(terminal::LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
-    
-    println!("Stopwatch stopped!");
-    Ok(())
-}
\ No newline at end of file
+/// Number of Pomodoro cycles for display, where 0 means no limit
+fn cycles_text(cycles: u64) -> String {
+    if cycles == 0 {
+        "∞".to_string()
+    } else {
+        cycles.to_string()
+    }
+}
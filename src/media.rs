@@ -0,0 +1,118 @@
+// src/media.rs
+//! Module for pausing media players during Pomodoro breaks
+//!
+//! Players are controlled through `playerctl`, which talks to anything that
+//! supports MPRIS on Linux. Only players that were playing get paused, and
+//! only those are resumed afterwards.
+
+use std::{
+    process::{Command, Stdio},
+    sync::mpsc,
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Pause,
+    Resume,
+}
+
+/// Handle for pausing and resuming media players on a background thread
+#[derive(Clone)]
+pub struct MediaControl {
+    queue: Option<mpsc::Sender<Action>>,
+}
+
+impl MediaControl {
+    /// Start the control thread, or return a handle that does nothing when disabled
+    pub fn spawn(enabled: bool) -> Self {
+        if !enabled {
+            return MediaControl { queue: None };
+        }
+        if !cfg!(target_os = "linux") {
+            warn!("media control is only supported on Linux");
+            return MediaControl { queue: None };
+        }
+
+        // Actions run in order on one thread, so a quick resume can't overtake a pause
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut paused: Vec<String> = Vec::new();
+            for action in rx {
+                match action {
+                    Action::Pause => {
+                        let players = playing_players();
+                        for player in &players {
+                            playerctl(&["--player", player, "pause"]);
+                        }
+                        if !players.is_empty() {
+                            info!(?players, "paused media players");
+                        }
+                        paused.extend(players);
+                    }
+                    Action::Resume => {
+                        for player in &paused {
+                            playerctl(&["--player", player, "play"]);
+                        }
+                        if !paused.is_empty() {
+                            info!(players = ?paused, "resumed media players");
+                        }
+                        paused.clear();
+                    }
+                }
+            }
+        });
+
+        MediaControl { queue: Some(tx) }
+    }
+
+    /// Pause every player that is currently playing
+    pub fn pause(&self) {
+        self.send(Action::Pause);
+    }
+
+    /// Resume the players paused by `pause`
+    pub fn resume(&self) {
+        self.send(Action::Resume);
+    }
+
+    fn send(&self, action: Action) {
+        if let Some(queue) = &self.queue {
+            let _ = queue.send(action);
+        }
+    }
+}
+
+/// Names of the players that are playing right now
+fn playing_players() -> Vec<String> {
+    let output = Command::new("playerctl")
+        .args(["--all-players", "metadata", "--format", "{{playerInstance}}\t{{status}}"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(_, status)| *status == "Playing")
+            .map(|(player, _)| player.to_string())
+            .collect(),
+        Err(e) => {
+            warn!(error = %e, "could not run playerctl");
+            Vec::new()
+        }
+    }
+}
+
+fn playerctl(args: &[&str]) {
+    let status = Command::new("playerctl")
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if let Err(e) = status {
+        warn!(?args, error = %e, "could not run playerctl");
+    }
+}
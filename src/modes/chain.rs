@@ -0,0 +1,133 @@
+// src/modes/chain.rs
+//! Runs modes one after another in the same session (`--then`), and can end
+//! the session once the last of them finishes (`--exit-when-finished`)
+
+use super::{Flow, Mode, Status, Summary};
+use crate::{control::Command, theme::Theme, ui::Screen};
+use crossterm::event::KeyEvent;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// Builds the next mode when its turn comes, so its clock starts then
+pub type Step = Box<dyn FnOnce() -> Box<dyn Mode>>;
+
+/// Runs the current mode until it finishes, then moves on to the next step
+pub struct Chain {
+    current: Box<dyn Mode>,
+    steps: VecDeque<Step>,
+    /// Quit once the last mode finishes instead of waiting for a key
+    exit_when_finished: bool,
+    /// The figures of the modes already finished
+    finished: Summary,
+    /// Theme switched to while running, for the steps still to come
+    theme: Option<Theme>,
+}
+
+impl Chain {
+    pub fn new(first: Box<dyn Mode>, steps: Vec<Step>) -> Self {
+        Chain {
+            current: first,
+            steps: steps.into(),
+            exit_when_finished: false,
+            finished: Summary::default(),
+            theme: None,
+        }
+    }
+
+    /// Quit as soon as the last mode finishes
+    pub fn exit_when_finished(mut self) -> Self {
+        self.exit_when_finished = true;
+        self
+    }
+
+    /// Start the next step once the current mode has finished, or quit if
+    /// that was the last one and the chain should exit
+    fn advance(&mut self, flow: Flow, now: Instant) -> Flow {
+        if flow == Flow::Quit || !self.current.status(now).finished {
+            return flow;
+        }
+        match self.steps.pop_front() {
+            Some(step) => {
+                let summary = self.current.summary(now);
+                self.finished = std::mem::take(&mut self.finished).merge(summary);
+                self.current = step();
+                if let Some(theme) = &self.theme {
+                    self.current.set_theme(theme.clone());
+                }
+                info!(mode = self.current.status(now).mode, "starting next mode in chain");
+                Flow::Continue
+            }
+            None if self.exit_when_finished => {
+                info!("last mode finished, exiting");
+                Flow::Quit
+            }
+            None => Flow::Continue,
+        }
+    }
+}
+
+impl Mode for Chain {
+    fn refresh_rate(&self) -> Duration {
+        self.current.refresh_rate()
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        let flow = self.current.on_tick(now);
+        self.advance(flow, now)
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        let flow = self.current.on_key(key, now);
+        self.advance(flow, now)
+    }
+
+    fn wants_key_release(&self) -> bool {
+        self.current.wants_key_release()
+    }
+
+    fn on_key_release(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        let flow = self.current.on_key_release(key, now);
+        self.advance(flow, now)
+    }
+
+    fn typing(&self) -> bool {
+        self.current.typing()
+    }
+
+    fn guards_quit(&self, now: Instant) -> bool {
+        self.current.guards_quit(now)
+    }
+
+    fn animating(&self) -> bool {
+        self.current.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.current.set_theme(theme.clone());
+        self.theme = Some(theme);
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        let flow = self.current.on_command(command, now)?;
+        Ok(self.advance(flow, now))
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        self.current.status(now)
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        self.current.draw(frame, now);
+    }
+
+    fn exit_message(&self) -> String {
+        self.current.exit_message()
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        self.finished.clone().merge(self.current.summary(now))
+    }
+}
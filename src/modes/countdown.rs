@@ -0,0 +1,516 @@
+// src/modes/countdown.rs
+//! Countdown timer mode
+//!
+//! Once time is up, the number keys start another countdown straight away
+//! with the times in `countdown_presets`: 1 the first, 2 the second and so on.
+//!
+//! With `--start-in` or `--start-at` the countdown waits before it starts,
+//! counting the wait down in dimmed digits.
+
+use super::{
+    blink, clock_time, instruction_lines, instructions, is_quit_key, timer_state::TimerState, Chime, Flow, Mode, Status,
+    Summary,
+};
+use crate::{
+    alerts::Alerts,
+    animation::{self, Animation, ScreenFlash},
+    caps,
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::{self, DigitOptions},
+    hooks::{HookContext, HookEvent, Hooks},
+    locale::{fill, messages},
+    parse_time_string,
+    progress::{self, ProgressStyle},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    time_format::TimeFormat,
+    ui::{self, Badge, DigitState, Screen},
+};
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+pub struct Countdown {
+    theme: Theme,
+    instructions: InstructionSettings,
+    blink_separator: bool,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// Format for the time instead of the usual one
+    time_format: Option<TimeFormat>,
+    refresh_rate: Duration,
+    /// Draw the banner with plain ASCII characters
+    ascii_only: bool,
+    hooks: Hooks,
+    chime: Option<Chime>,
+    alerts: Option<Alerts>,
+    alert_sound: SoundPlayer,
+    /// Fill the whole screen with this color, or with the latest alert color
+    /// once one has been crossed
+    backdrop: Option<Color>,
+    /// Played over the "TIME'S UP!" banner when the countdown finishes
+    animation: Option<Box<dyn Animation>>,
+    /// Flashes the whole screen when the countdown finishes
+    screen_flash: Option<ScreenFlash>,
+    /// Show the time elapsed and the share done beneath the digits
+    show_elapsed: bool,
+    progress: ProgressStyle,
+    /// Whether a key press cut the animation and screen flash short
+    animation_skipped: bool,
+    total_seconds: u64,
+    timer: TimerState,
+    /// When the countdown reached zero, if it has
+    finished_at: Option<Instant>,
+    /// Times the countdown was paused
+    pauses: u32,
+    /// Lengths of the countdowns the number keys start once time is up
+    presets: Vec<u64>,
+    /// Countdown time run before the last preset was started
+    earlier: Duration,
+    /// When the countdown starts, while it waits to (`--start-in`, `--start-at`)
+    starts_at: Option<Instant>,
+}
+
+impl Countdown {
+    pub fn new(total_seconds: u64, config: &Config) -> Self {
+        Countdown {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            blink_separator: config.blink_separator,
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            time_format: TimeFormat::from_setting(&config.time_format.countdown, "countdown"),
+            refresh_rate: Duration::from_millis(config.countdown_refresh_rate),
+            ascii_only: config.ascii_only || !caps::supports_unicode(),
+            hooks: Hooks::new(&config.hooks),
+            chime: None,
+            alerts: None,
+            alert_sound: SoundPlayer::silent(),
+            backdrop: None,
+            animation: animation::from_config(config),
+            screen_flash: ScreenFlash::from_config(config),
+            show_elapsed: config.show_elapsed,
+            progress: ProgressStyle::from_config(config),
+            animation_skipped: false,
+            total_seconds,
+            timer: TimerState::new(Duration::from_secs(total_seconds), clock::now()),
+            finished_at: None,
+            pauses: 0,
+            presets: presets(&config.countdown_presets),
+            earlier: Duration::ZERO,
+            starts_at: None,
+        }
+    }
+
+    /// Wait `delay` before counting down, showing the wait in dimmed digits
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        let starts_at = self.timer.start() + delay;
+        self.timer = TimerState::new(Duration::from_secs(self.total_seconds), starts_at);
+        self.starts_at = Some(starts_at);
+        self
+    }
+
+    /// Ring and pulse the digits every `interval` of countdown time
+    pub fn with_chime(mut self, interval: Duration, sound: SoundPlayer) -> Self {
+        self.chime = Some(Chime::new(interval, sound));
+        self
+    }
+
+    /// Alert at points in the countdown, like halfway through
+    pub fn with_alerts(mut self, alerts: Alerts, sound: SoundPlayer) -> Self {
+        self.alerts = Some(alerts);
+        self.alert_sound = sound;
+        self
+    }
+
+    /// Color the whole screen like a conference speaker timer, changing
+    /// color as the alerts are crossed
+    pub fn with_backdrop(mut self, color: Color) -> Self {
+        self.backdrop = Some(color);
+        self
+    }
+
+    /// Show the time elapsed and the share done beneath the digits
+    pub fn with_elapsed_line(mut self) -> Self {
+        self.show_elapsed = true;
+        self
+    }
+
+    /// Start with only `remaining` of the countdown left, as when resuming
+    /// one suspended earlier
+    pub fn with_remaining(mut self, remaining: Duration) -> Self {
+        self.timer = self.timer.with_remaining(remaining);
+        self
+    }
+
+    /// Start out paused, as when resuming one suspended while paused
+    pub fn start_paused(mut self) -> Self {
+        let start = self.timer.start();
+        self.timer.pause(start);
+        self
+    }
+
+    /// Length of the countdown, including any time added since it started
+    pub fn length(&self) -> Duration {
+        self.timer.length()
+    }
+
+    fn remaining(&self, now: Instant) -> Duration {
+        self.timer.remaining(now)
+    }
+
+    /// Countdown time that has passed, not counting pauses
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.timer.elapsed(now)
+    }
+
+    /// Time left to wait before the countdown starts, if it hasn't yet
+    fn waiting(&self, now: Instant) -> Option<Duration> {
+        self.starts_at.filter(|starts_at| *starts_at > now).map(|starts_at| starts_at - now)
+    }
+
+    fn draw_waiting(&self, frame: &mut Screen, wait: Duration) {
+        let mut header = instruction_lines(instructions(&self.instructions.countdown, &[]));
+        let starts_at = Local::now() + wait;
+        header.push(Line::raw(fill(&messages().starts_at, &[("time", &starts_at.format("%H:%M:%S").to_string())])));
+        let wait = clock_time(wait.as_secs() + u64::from(wait.subsec_nanos() > 0));
+        let style = self.theme.countdown.add_modifier(Modifier::DIM);
+        ui::draw_timer(frame, &header, self.theme.ui_text, &wait, self.digits, &self.digit_state, style);
+    }
+
+    fn display_time(&self, now: Instant) -> String {
+        match &self.time_format {
+            Some(format) => format.format_remaining(self.remaining(now)),
+            None => clock_time(self.remaining(now).as_secs()),
+        }
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.timer.pause(now) {
+            self.pauses += 1;
+            self.fire_hook(HookEvent::Pause);
+        }
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if self.timer.resume(now).is_some() {
+            self.fire_hook(HookEvent::Resume);
+        }
+    }
+
+    /// Start the countdown again with the preset for `key`, if there is one
+    fn start_preset(&mut self, key: char, now: Instant) {
+        let Some(&secs) = key.to_digit(10).and_then(|n| self.presets.get((n as usize).checked_sub(1)?)) else {
+            return;
+        };
+        info!(secs, "countdown preset started");
+        self.earlier += self.elapsed(now);
+        self.total_seconds = secs;
+        self.timer = TimerState::new(Duration::from_secs(secs), now);
+        self.finished_at = None;
+        self.animation_skipped = false;
+        if let Some(chime) = self.chime.as_mut() {
+            chime.reset();
+        }
+        if let Some(alerts) = self.alerts.as_mut() {
+            alerts.reset();
+        }
+    }
+
+    /// The presets by number key, e.g. "1) 5:00  2) 10:00"
+    fn presets_text(&self) -> String {
+        let presets: Vec<String> =
+            self.presets.iter().enumerate().map(|(i, secs)| format!("{}) {}", i + 1, clock_time(*secs))).collect();
+        fill(&messages().presets, &[("presets", &presets.join("  "))])
+    }
+
+    fn fire_hook(&self, event: HookEvent) {
+        let context = HookContext {
+            mode: "countdown",
+            duration_secs: Some(self.total_seconds),
+            ..Default::default()
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    fn fire_alert_hook(&self, message: Option<String>) {
+        let context = HookContext {
+            mode: "countdown",
+            label: message,
+            duration_secs: Some(self.total_seconds),
+            ..Default::default()
+        };
+        self.hooks.fire(HookEvent::Alert, &context);
+    }
+
+    /// The animation, while it is still playing
+    fn playing_animation(&self, now: Instant) -> Option<&dyn Animation> {
+        let since = now - self.finished_at?;
+        self.animation
+            .as_deref()
+            .filter(|animation| !self.animation_skipped && since < animation.duration())
+    }
+
+    /// The screen flash, while it is still flashing
+    fn flashing_screen(&self, now: Instant) -> Option<ScreenFlash> {
+        let since = now - self.finished_at?;
+        self.screen_flash
+            .filter(|flash| !self.animation_skipped && since < flash.duration())
+    }
+
+    fn effects_playing(&self, now: Instant) -> bool {
+        self.playing_animation(now).is_some() || self.flashing_screen(now).is_some()
+    }
+
+    /// How much of the countdown has run, e.g. "30:00 elapsed (25%)"
+    fn elapsed_text(&self, now: Instant) -> String {
+        let elapsed = self.elapsed(now);
+        let percent = (self.timer.done(now) * 100.0).floor() as u64;
+        fill(&messages().elapsed, &[("time", &clock_time(elapsed.as_secs())), ("percent", &percent.to_string())])
+    }
+
+    /// Fill the screen with the backdrop color, with the text in black on top
+    fn fill_backdrop(&self, frame: &mut Screen) {
+        let Some(backdrop) = self.backdrop else {
+            return;
+        };
+        let color = self.alerts.as_ref().and_then(|alerts| alerts.color(self.length())).unwrap_or(backdrop);
+        let area = frame.area();
+        frame.buffer_mut().set_style(area, Style::default().fg(Color::Black).bg(color));
+    }
+
+    fn draw_time_up(&self, frame: &mut Screen, now: Instant) {
+        // The banner stays on screen until the user exits, with the animation over it at first
+        // The small lettering only spells it in English; other languages get a
+        // framed line unless the large letters can spell them
+        let times_up = &messages().times_up;
+        let lines: Vec<String> = if let Some(large) = ui::large_text(times_up, self.digits, frame.area().width) {
+            large
+        } else if messages().english_times_up() {
+            digit::get_time_up_banner(self.ascii_only).into_iter().map(str::to_string).collect()
+        } else {
+            ui::framed(times_up, self.ascii_only)
+        };
+        let mut header = instruction_lines(instructions(&self.instructions.countdown, &[]));
+        if !self.presets.is_empty() {
+            header.push(Line::raw(self.presets_text()));
+        }
+        ui::draw_banner_with_header(frame, &header, self.theme.ui_text, &lines, self.theme.times_up);
+        self.fill_backdrop(frame);
+
+        if let (Some(animation), Some(finished_at)) = (self.playing_animation(now), self.finished_at) {
+            // Leave the instructions line alone
+            let area = frame.area();
+            let area = Rect { y: area.y + 1, height: area.height.saturating_sub(1), ..area };
+            animation.render(now - finished_at, area, frame.buffer_mut());
+        }
+
+        if let (Some(flash), Some(finished_at)) = (self.flashing_screen(now), self.finished_at) {
+            let color = self.theme.times_up.fg.unwrap_or(Color::Red);
+            let area = frame.area();
+            flash.render(now - finished_at, color, area, frame.buffer_mut());
+        }
+    }
+}
+
+impl Mode for Countdown {
+    fn refresh_rate(&self) -> Duration {
+        if self.effects_playing(clock::now()) {
+            self.refresh_rate.min(animation::FRAME_INTERVAL)
+        } else {
+            self.refresh_rate
+        }
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if self.starts_at.is_some_and(|starts_at| starts_at <= now) {
+            info!("wait over, countdown started");
+            self.starts_at = None;
+        }
+        if self.finished_at.is_none() && self.timer.is_done(now) {
+            info!("countdown finished");
+            self.finished_at = Some(now);
+            self.fire_hook(HookEvent::Complete);
+        }
+
+        if self.finished_at.is_none() {
+            let elapsed = self.elapsed(now);
+            if let Some(chime) = self.chime.as_mut() {
+                chime.check(elapsed, now);
+            }
+
+            let remaining = self.remaining(now);
+            let triggered = match self.alerts.as_mut() {
+                Some(alerts) => alerts.check(self.timer.length(), remaining),
+                None => Vec::new(),
+            };
+            for alert in triggered {
+                if alert.sound {
+                    self.alert_sound.play(Sound::Alert);
+                }
+                self.fire_alert_hook(alert.message);
+            }
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+
+        if let (Some(_), KeyCode::Char(key @ '1'..='9')) = (self.finished_at, key.code) {
+            self.start_preset(key, now);
+            return Flow::Continue;
+        }
+
+        // Any other key skips the completion animation and screen flash
+        if self.effects_playing(now) {
+            self.animation_skipped = true;
+        }
+        Flow::Continue
+    }
+
+    fn guards_quit(&self, _now: Instant) -> bool {
+        self.finished_at.is_none()
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        if self.finished_at.is_some() {
+            return Err("countdown has already finished".to_string());
+        }
+        if self.waiting(now).is_some() && !matches!(command, Command::Add(_) | Command::Status | Command::Quit) {
+            return Err("countdown hasn't started yet".to_string());
+        }
+
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.timer.is_paused() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            Command::Add(secs) => self.timer.extend(Duration::from_secs(secs)),
+            Command::Skip => self.timer.skip(now),
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        Status {
+            mode: "countdown",
+            label: self.waiting(now).map(|_| messages().waiting_to_start.clone()),
+            time: self.display_time(now),
+            remaining: Some(self.remaining(now)),
+            elapsed: None,
+            paused: self.timer.is_paused(),
+            finished: self.finished_at.is_some(),
+            prompt: self.finished_at.map(|_| instructions(&messages().times_up_prompt, &[])),
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        if self.finished_at.is_some() {
+            self.draw_time_up(frame, now);
+            return;
+        }
+        if let Some(wait) = self.waiting(now) {
+            self.draw_waiting(frame, wait);
+            return;
+        }
+
+        let mut header = instruction_lines(instructions(&self.instructions.countdown, &[]));
+        if self.timer.is_paused() {
+            header.extend(ui::status_line(&[Badge::Paused], &self.theme));
+        }
+        if let Some(message) = self.alerts.as_ref().and_then(|alerts| alerts.message(self.length())) {
+            header.push(Line::raw(message.to_string()));
+        }
+        let remaining = self.remaining(now);
+        let next_checkpoint = self.alerts.as_ref().and_then(|alerts| alerts.next_checkpoint(self.length(), remaining));
+        if let Some((checkpoint, at)) = next_checkpoint {
+            let until = clock_time((remaining - at).as_secs());
+            header.push(Line::raw(fill(&messages().next_checkpoint, &[("checkpoint", checkpoint), ("time", &until)])));
+        }
+
+        // Use the time since start for consistent blinking
+        let display_time = self.display_time(now);
+        let digits = blink(self.digits, self.blink_separator, now - self.timer.start());
+        let mut time_style = self.theme.countdown;
+        if let Some(color) = self.alerts.as_ref().and_then(|alerts| alerts.color(self.length())) {
+            time_style = time_style.fg(color);
+        }
+        if self.chime.as_ref().is_some_and(|chime| chime.pulsing(now)) {
+            time_style = time_style.add_modifier(Modifier::REVERSED);
+        }
+        if self.show_elapsed {
+            let caption = Line::styled(self.elapsed_text(now), self.theme.ui_text);
+            ui::draw_timer_with_caption(
+                frame,
+                &header,
+                self.theme.ui_text,
+                &display_time,
+                digits,
+                &self.digit_state,
+                time_style,
+                caption,
+            );
+        } else {
+            ui::draw_timer(frame, &header, self.theme.ui_text, &display_time, digits, &self.digit_state, time_style);
+        }
+        progress::draw(frame, self.progress, self.timer.done(now), &header, time_style, self.ascii_only);
+        self.fill_backdrop(frame);
+    }
+
+    fn exit_message(&self) -> String {
+        if self.finished_at.is_some() {
+            "Timer complete!".to_string()
+        } else {
+            "Timer stopped.".to_string()
+        }
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        let focused = self.earlier + self.elapsed(now);
+        Summary {
+            requested: Some(self.earlier + self.length()),
+            focused: Some(focused),
+            interruptions: Some(self.pauses),
+            ..Summary::default()
+        }
+    }
+}
+
+/// The preset lengths in seconds, skipping any that can't be understood and
+/// any past the ninth number key. Countdowns are also built while the screen
+/// is up, for `--then` and `--watch-file`, so bad presets are only logged;
+/// `clockit config validate` points them out.
+fn presets(settings: &[String]) -> Vec<u64> {
+    settings
+        .iter()
+        .filter_map(|preset| match parse_time_string(preset) {
+            Ok(secs) if secs > 0 => Some(secs),
+            _ => {
+                warn!(preset, "invalid countdown preset");
+                None
+            }
+        })
+        .take(9)
+        .collect()
+}
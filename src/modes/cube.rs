@@ -0,0 +1,298 @@
+// src/modes/cube.rs
+//! Speedcubing mode: a 15-second inspection, then the solve timed to the
+//! hundredth, with a session of solves and their best and average times
+
+use super::{instruction_lines, instructions, Flow, Mode, Status, QUIT_KEY};
+use crate::{
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    hooks::{HookContext, HookEvent, Hooks},
+    theme::Theme,
+    ui::{self, DigitState, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{style::Color, text::Line};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Inspection time allowed before a solve, as in WCA competitions
+const INSPECTION: Duration = Duration::from_secs(15);
+
+/// Solves shown above the timer
+const RECENT_SOLVES: usize = 5;
+
+enum Phase {
+    /// Between solves, showing the last time
+    Ready,
+    /// Looking the cube over before the solve
+    Inspection {
+        start_time: Instant,
+        /// Whether space is held down, ready to start the solve when let go
+        armed: bool,
+    },
+    Solving { start_time: Instant },
+}
+
+pub struct Cube {
+    theme: Theme,
+    instructions: InstructionSettings,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    refresh_rate: Duration,
+    hooks: Hooks,
+    /// Whether the terminal reports keys being let go, so the solve starts
+    /// when space is released rather than when it's pressed
+    hold_to_start: bool,
+    solves: Vec<Duration>,
+    phase: Phase,
+}
+
+impl Cube {
+    pub fn new(config: &Config, hold_to_start: bool) -> Self {
+        Cube {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            refresh_rate: Duration::from_millis(config.stopwatch_refresh_rate),
+            hooks: Hooks::new(&config.hooks),
+            hold_to_start,
+            solves: Vec::new(),
+            phase: Phase::Ready,
+        }
+    }
+
+    fn start_inspection(&mut self, now: Instant) {
+        self.phase = Phase::Inspection { start_time: now, armed: false };
+    }
+
+    fn start_solve(&mut self, now: Instant) {
+        info!(solve = self.solves.len() + 1, "solve started");
+        self.phase = Phase::Solving { start_time: now };
+        self.fire_hook(HookEvent::WorkStart, None);
+    }
+
+    fn stop_solve(&mut self, now: Instant) {
+        let Phase::Solving { start_time } = self.phase else {
+            return;
+        };
+        let time = now - start_time;
+        info!(solve = self.solves.len() + 1, millis = time.as_millis() as u64, "solve finished");
+        self.fire_hook(HookEvent::SessionEnd, Some(time));
+        self.solves.push(time);
+        self.phase = Phase::Ready;
+    }
+
+    /// Move on to the next step, as space does
+    fn advance(&mut self, now: Instant) {
+        match self.phase {
+            Phase::Ready => self.start_inspection(now),
+            Phase::Inspection { .. } => self.start_solve(now),
+            Phase::Solving { .. } => self.stop_solve(now),
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent, time: Option<Duration>) {
+        let context = HookContext {
+            mode: "cube",
+            label: Some(self.solve_name()),
+            duration_secs: time.map(|time| time.as_secs()),
+            cycle: None,
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    /// Name of the solve under way, or the next one between solves
+    fn solve_name(&self) -> String {
+        format!("Solve {}", self.solves.len() + 1)
+    }
+
+    fn best(&self) -> Option<Duration> {
+        self.solves.iter().min().copied()
+    }
+
+    /// One line of session statistics, e.g. "Best 9.87  ao5 11.20  ao12 -"
+    fn stats(&self) -> String {
+        let show = |time: Option<Duration>| time.map_or_else(|| "-".to_string(), solve_time);
+        format!(
+            "Best {}  ao5 {}  ao12 {}",
+            show(self.best()),
+            show(average_of(&self.solves, 5)),
+            show(average_of(&self.solves, 12))
+        )
+    }
+
+    fn display_time(&self, now: Instant) -> String {
+        match self.phase {
+            Phase::Ready => solve_time(self.solves.last().copied().unwrap_or_default()),
+            // Whole seconds left, counting down to 1
+            Phase::Inspection { start_time, .. } => {
+                let left = INSPECTION.saturating_sub(now - start_time);
+                (left.as_secs() + u64::from(left.subsec_nanos() > 0)).to_string()
+            }
+            Phase::Solving { start_time } => solve_time(now - start_time),
+        }
+    }
+}
+
+/// Format a solve as seconds and hundredths, with minutes once it passes one
+fn solve_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    let centisecs = time.subsec_millis() / 10;
+    if secs >= 60 {
+        format!("{}:{:02}.{:02}", secs / 60, secs % 60, centisecs)
+    } else {
+        format!("{}.{:02}", secs, centisecs)
+    }
+}
+
+/// Mean of the last `n` solves without the best and worst, as WCA averages are taken
+fn average_of(solves: &[Duration], n: usize) -> Option<Duration> {
+    if n < 3 || solves.len() < n {
+        return None;
+    }
+    let mut last = solves[solves.len() - n..].to_vec();
+    last.sort();
+    let counted = &last[1..n - 1];
+    Some(counted.iter().sum::<Duration>() / counted.len() as u32)
+}
+
+impl Mode for Cube {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        // The solve starts by itself once inspection time runs out
+        if let Phase::Inspection { start_time, .. } = self.phase {
+            if now - start_time >= INSPECTION {
+                info!("inspection time up");
+                self.start_solve(now);
+            }
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Flow::Quit;
+        }
+
+        match &mut self.phase {
+            // Any key stops the clock, so a slap anywhere on the keyboard works
+            Phase::Solving { .. } => self.stop_solve(now),
+            Phase::Ready | Phase::Inspection { .. } if key.code == KeyCode::Char(QUIT_KEY) => return Flow::Quit,
+            Phase::Ready if key.code == KeyCode::Char(' ') => self.start_inspection(now),
+            Phase::Inspection { .. } if key.code == KeyCode::Esc => self.phase = Phase::Ready,
+            Phase::Inspection { armed, .. } if key.code == KeyCode::Char(' ') => {
+                if self.hold_to_start {
+                    *armed = true;
+                } else {
+                    self.start_solve(now);
+                }
+            }
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn wants_key_release(&self) -> bool {
+        self.hold_to_start
+    }
+
+    fn on_key_release(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if let Phase::Inspection { armed: true, .. } = self.phase {
+            if key.code == KeyCode::Char(' ') {
+                self.start_solve(now);
+            }
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        match command {
+            // Like pressing space
+            Command::Toggle | Command::Skip => self.advance(now),
+            Command::Pause | Command::Resume | Command::Add(_) => {
+                return Err("cube solves can only be started and stopped".to_string())
+            }
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let label = match self.phase {
+            Phase::Inspection { .. } => "Inspection".to_string(),
+            _ => self.solve_name(),
+        };
+        Status {
+            mode: "cube",
+            label: Some(label),
+            time: self.display_time(now),
+            remaining: match self.phase {
+                Phase::Inspection { start_time, .. } => Some(INSPECTION.saturating_sub(now - start_time)),
+                _ => None,
+            },
+            elapsed: match self.phase {
+                Phase::Solving { start_time } => Some(now - start_time),
+                _ => None,
+            },
+            paused: false,
+            finished: false,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        let (instructions, time_style) = match self.phase {
+            Phase::Ready => (instructions(&self.instructions.cube, &[]), theme.stopwatch),
+            // Green when it's safe to let go, as on a stackmat timer
+            Phase::Inspection { armed: true, .. } => {
+                ("Let go of space to start".to_string(), theme.countdown.fg(Color::Green))
+            }
+            Phase::Inspection { .. } if self.hold_to_start => {
+                ("Hold space and let go to start, Esc to cancel".to_string(), theme.countdown)
+            }
+            Phase::Inspection { .. } => ("Press space to start, Esc to cancel".to_string(), theme.countdown),
+            Phase::Solving { .. } => ("Press space to stop".to_string(), theme.stopwatch),
+        };
+
+        let mut header = instruction_lines(instructions);
+        header.push(Line::raw(self.solve_name()));
+        if !self.solves.is_empty() {
+            let recent: Vec<String> =
+                self.solves.iter().rev().take(RECENT_SOLVES).map(|solve| solve_time(*solve)).collect();
+            header.push(Line::raw(format!("Last: {}", recent.join("  "))));
+        }
+        ui::draw_timer_with_caption(
+            frame,
+            &header,
+            theme.ui_text,
+            &self.display_time(now),
+            self.digits,
+            &self.digit_state,
+            time_style,
+            Line::styled(self.stats(), theme.ui_text),
+        );
+    }
+
+    fn exit_message(&self) -> String {
+        match self.solves.len() {
+            0 => "No solves this session.".to_string(),
+            1 => format!("1 solve this session. {}", self.stats()),
+            count => format!("{} solves this session. {}", count, self.stats()),
+        }
+    }
+}
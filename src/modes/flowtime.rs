@@ -0,0 +1,373 @@
+// src/modes/flowtime.rs
+//! Flowtime mode: work for as long as the focus lasts, then take a break
+//! sized to match
+
+use super::{
+    blink, clock_time, instruction_lines, instructions, is_quit_key, Flow, Mode, Status, Summary, TOO_MUCH_TIME,
+};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    history::{self, SessionKind, SessionRecord},
+    hooks::{HookContext, HookEvent, Hooks},
+    locale::{fill, messages},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    time_format::TimeFormat,
+    ui::{self, Badge, DigitState, Screen},
+};
+use chrono::{Local, SubsecRound};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{text::Line};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Where the Flowtime run currently is
+enum Phase {
+    /// Counting up the work time
+    Work {
+        start_time: Instant,
+        /// Work time counted before the current run
+        banked: Duration,
+        /// When the work time last started counting, or None while paused
+        running_since: Option<Instant>,
+    },
+    /// Counting down the break earned by the last work session
+    Break {
+        start_time: Instant,
+        end_time: Instant,
+        paused_at: Option<Instant>,
+        /// Total time spent paused so far
+        paused_for: Duration,
+    },
+    /// The break is over; waiting for the user to start working or quit
+    BreakOver,
+}
+
+pub struct Flowtime {
+    theme: Theme,
+    instructions: InstructionSettings,
+    blink_separator: bool,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// Format for the time instead of the usual one
+    time_format: Option<TimeFormat>,
+    refresh_rate: Duration,
+    /// Length of a break as a share of the work before it
+    break_fraction: f64,
+    sound: SoundPlayer,
+    record_history: bool,
+    hooks: Hooks,
+    /// Number of the current work session and the break after it
+    session: u64,
+    /// Work time over all finished work sessions
+    total_work: Duration,
+    /// Times work or a break was paused
+    pauses: u32,
+    phase: Phase,
+}
+
+impl Flowtime {
+    pub fn new(config: &Config, sound: SoundPlayer) -> Self {
+        let mut flowtime = Flowtime {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            blink_separator: config.blink_separator,
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            time_format: TimeFormat::from_setting(&config.time_format.flowtime, "flowtime"),
+            refresh_rate: Duration::from_millis(config.countdown_refresh_rate),
+            break_fraction: config.flowtime.break_fraction.clamp(0.0, 1.0),
+            sound,
+            record_history: config.record_history,
+            hooks: Hooks::new(&config.hooks),
+            session: 1,
+            total_work: Duration::ZERO,
+            pauses: 0,
+            phase: Phase::BreakOver,
+        };
+        flowtime.start_work(clock::now());
+        flowtime
+    }
+
+    fn start_work(&mut self, now: Instant) {
+        info!(session = self.session, "flow started");
+        self.phase = Phase::Work { start_time: now, banked: Duration::ZERO, running_since: Some(now) };
+        self.fire_hook(HookEvent::WorkStart, None);
+    }
+
+    /// End the work session and start a break in proportion to it
+    fn start_break(&mut self, now: Instant) {
+        let Phase::Work { start_time, .. } = self.phase else {
+            return;
+        };
+        let worked = self.work_time(now);
+        self.total_work += worked;
+        self.record(SessionKind::Work, start_time, worked, now);
+        self.fire_hook(HookEvent::SessionEnd, Some(worked));
+
+        // Whole seconds, so the countdown starts on a round number
+        let length = Duration::from_secs(worked.mul_f64(self.break_fraction).as_secs_f64().round().max(1.0) as u64);
+        info!(worked_secs = worked.as_secs(), break_secs = length.as_secs(), "break started");
+        self.phase = Phase::Break { start_time: now, end_time: now + length, paused_at: None, paused_for: Duration::ZERO };
+        self.fire_hook(HookEvent::BreakStart, Some(length));
+    }
+
+    /// End the break, whether its time is up or the user cut it short
+    fn end_break(&mut self, now: Instant) {
+        let Phase::Break { start_time, paused_at, paused_for, .. } = self.phase else {
+            return;
+        };
+        let paused_for = paused_for + paused_at.map_or(Duration::ZERO, |paused_at| now - paused_at);
+        let rested = (now - start_time).saturating_sub(paused_for);
+        self.record(SessionKind::Break, start_time, rested, now);
+        self.fire_hook(HookEvent::SessionEnd, Some(rested));
+        self.session += 1;
+        self.phase = Phase::BreakOver;
+    }
+
+    /// Work time in the current work session
+    fn work_time(&self, now: Instant) -> Duration {
+        match self.phase {
+            Phase::Work { banked, running_since: Some(since), .. } => banked + (now - since),
+            Phase::Work { banked, running_since: None, .. } => banked,
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn break_remaining(&self, now: Instant) -> Duration {
+        match self.phase {
+            Phase::Break { end_time, paused_at, .. } => end_time.saturating_duration_since(paused_at.unwrap_or(now)),
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn paused(&self) -> bool {
+        matches!(self.phase, Phase::Work { running_since: None, .. } | Phase::Break { paused_at: Some(_), .. })
+    }
+
+    fn pause(&mut self, now: Instant) {
+        match &mut self.phase {
+            Phase::Work { banked, running_since, .. } => {
+                let Some(since) = running_since.take() else {
+                    return;
+                };
+                *banked += now - since;
+            }
+            Phase::Break { paused_at: paused_at @ None, .. } => *paused_at = Some(now),
+            _ => return,
+        }
+        self.pauses += 1;
+        self.fire_hook(HookEvent::Pause, None);
+    }
+
+    fn resume(&mut self, now: Instant) {
+        match &mut self.phase {
+            Phase::Work { running_since: running_since @ None, .. } => *running_since = Some(now),
+            Phase::Break { end_time, paused_at, paused_for, .. } => {
+                let Some(paused) = paused_at.take() else {
+                    return;
+                };
+                *end_time += now - paused;
+                *paused_for += now - paused;
+            }
+            _ => return,
+        }
+        self.fire_hook(HookEvent::Resume, None);
+    }
+
+    fn session_name(&self) -> String {
+        match self.phase {
+            Phase::Work { .. } | Phase::BreakOver => format!("{} #{}", messages().flow, self.session),
+            Phase::Break { .. } => format!("{} #{}", messages().break_session, self.session),
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent, duration: Option<Duration>) {
+        let context = HookContext {
+            mode: "flowtime",
+            label: Some(self.session_name()),
+            duration_secs: duration.map(|duration| duration.as_secs()),
+            cycle: Some(self.session),
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    /// Add a finished session to the history file
+    fn record(&self, kind: SessionKind, start_time: Instant, duration: Duration, now: Instant) {
+        if !self.record_history {
+            return;
+        }
+
+        let ended_at = Local::now();
+        let session = SessionRecord {
+            kind,
+            label: self.session_name(),
+            task: None,
+            started_at: (ended_at - (now - start_time)).trunc_subsecs(0),
+            ended_at: ended_at.trunc_subsecs(0),
+            duration_secs: duration.as_secs(),
+            idle_secs: 0,
+            extended_secs: 0,
+            interruptions: 0,
+            paused_secs: 0,
+            notes: Vec::new(),
+        };
+        // Losing a history entry shouldn't interrupt the timer
+        if let Err(e) = history::record(&session) {
+            warn!(error = %e, "could not record session");
+        }
+    }
+
+    /// The break the current work time has earned
+    fn earned_break(&self, now: Instant) -> Duration {
+        self.work_time(now).mul_f64(self.break_fraction)
+    }
+
+    fn display_time(&self, now: Instant) -> String {
+        match (&self.phase, &self.time_format) {
+            (Phase::Work { .. }, Some(format)) => format.format(self.work_time(now)),
+            (Phase::Break { .. }, Some(format)) => format.format_remaining(self.break_remaining(now)),
+            (Phase::BreakOver, Some(format)) => format.format(Duration::ZERO),
+            (Phase::Work { .. }, None) => clock_time(self.work_time(now).as_secs()),
+            (Phase::Break { .. }, None) => {
+                let remaining = self.break_remaining(now);
+                // Round up so the break shows 0:00 only once it is over
+                clock_time(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+            }
+            (Phase::BreakOver, None) => clock_time(0),
+        }
+    }
+}
+
+impl Mode for Flowtime {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if let Phase::Break { end_time, paused_at: None, .. } = self.phase {
+            if now >= end_time {
+                info!(session = self.session, "break complete");
+                self.sound.play(Sound::BreakEnd);
+                self.end_break(now);
+            }
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+
+        match self.phase {
+            Phase::Work { .. } if key.code == KeyCode::Char('b') => self.start_break(now),
+            Phase::Break { .. } if key.code == KeyCode::Char('b') => self.end_break(now),
+            Phase::Work { .. } | Phase::Break { .. } => {}
+            // Any key gets back to work
+            Phase::BreakOver => self.start_work(now),
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        if matches!(self.phase, Phase::BreakOver) && command != Command::Status {
+            return Err("waiting to start the next work session".to_string());
+        }
+
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.paused() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            Command::Add(secs) => match &mut self.phase {
+                Phase::Break { end_time, .. } => {
+                    let added = end_time.checked_add(Duration::from_secs(secs));
+                    *end_time = added.ok_or_else(|| TOO_MUCH_TIME.to_string())?;
+                }
+                _ => return Err("time can only be added to a break".to_string()),
+            },
+            // Skipping takes the break now, or cuts it short
+            Command::Skip if matches!(self.phase, Phase::Work { .. }) => self.start_break(now),
+            Command::Skip => self.end_break(now),
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let working = matches!(self.phase, Phase::Work { .. });
+        let on_break = matches!(self.phase, Phase::Break { .. });
+        Status {
+            mode: "flowtime",
+            label: Some(self.session_name()),
+            time: self.display_time(now),
+            remaining: on_break.then(|| self.break_remaining(now)),
+            elapsed: working.then(|| self.work_time(now)),
+            paused: self.paused(),
+            finished: false,
+            prompt: matches!(self.phase, Phase::BreakOver)
+                .then(|| "Break over! Press q to exit, any other key to start working.".to_string()),
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        let (instructions, style, elapsed) = match self.phase {
+            Phase::Work { start_time, .. } => (
+                instructions(
+                    &self.instructions.flowtime_work,
+                    &[("earned", &clock_time(self.earned_break(now).as_secs()))],
+                ),
+                theme.pomodoro_work,
+                now - start_time,
+            ),
+            Phase::Break { start_time, .. } => {
+                (instructions(&self.instructions.flowtime_break, &[]), theme.pomodoro_break, now - start_time)
+            }
+            Phase::BreakOver => {
+                ui::draw_message(
+                    frame,
+                    &instructions(&self.instructions.flowtime_next, &[]),
+                    theme.ui_text,
+                    &messages().break_over,
+                    theme.times_up,
+                );
+                return;
+            }
+        };
+
+        let mut header = instruction_lines(instructions);
+        header.push(Line::raw(fill(&messages().current, &[("session", &self.session_name())])));
+        if self.paused() {
+            header.extend(ui::status_line(&[Badge::Paused], theme));
+        }
+        let digits = blink(self.digits, self.blink_separator, elapsed);
+        ui::draw_timer(frame, &header, theme.ui_text, &self.display_time(now), digits, &self.digit_state, style);
+    }
+
+    fn exit_message(&self) -> String {
+        let total_work = self.total_work + self.work_time(clock::now());
+        format!("Flowtime ended. Worked {} in total.", clock_time(total_work.as_secs()))
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        Summary {
+            focused: Some(self.total_work + self.work_time(now)),
+            interruptions: Some(self.pauses),
+            ..Summary::default()
+        }
+    }
+}
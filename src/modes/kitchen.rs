@@ -0,0 +1,346 @@
+// src/modes/kitchen.rs
+//! Kitchen mode: any number of named countdowns running side by side, with
+//! new ones added while it runs
+
+use super::{
+    clock_time, instruction_lines, instructions, is_quit_key,
+    line_input::{Edit, LineInput},
+    Flow, Mode, Status, TOO_MUCH_TIME,
+};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    hooks::{HookContext, HookEvent, Hooks},
+    parse_time_string,
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    ui::{self, Badge, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    style::Modifier,
+    text::{Line, Span},
+};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// One dish on the go
+struct Timer {
+    name: String,
+    length: Duration,
+    end_time: Instant,
+    /// When the timer reached zero, if it has
+    finished_at: Option<Instant>,
+}
+
+impl Timer {
+    fn remaining(&self, now: Instant) -> Duration {
+        self.end_time.saturating_duration_since(now)
+    }
+}
+
+/// Parse a timer like "pasta 9:00": a name, then the time. Without a name
+/// the timer is called `fallback_name`.
+pub fn parse_timer(text: &str, fallback_name: &str) -> Result<(String, Duration), String> {
+    let text = text.trim();
+    let (name, time) = match text.rsplit_once(char::is_whitespace) {
+        Some((name, time)) => (name.trim(), time),
+        None => ("", text),
+    };
+    match parse_time_string(time) {
+        Ok(secs) if secs > 0 => {
+            let name = if name.is_empty() { fallback_name } else { name };
+            Ok((name.to_string(), Duration::from_secs(secs)))
+        }
+        Ok(_) => Err("the time must be greater than zero".to_string()),
+        Err(e) => Err(format!("`{}`: {}", time, e.to_lowercase())),
+    }
+}
+
+pub struct Kitchen {
+    theme: Theme,
+    instructions: InstructionSettings,
+    refresh_rate: Duration,
+    sound: SoundPlayer,
+    hooks: Hooks,
+    timers: Vec<Timer>,
+    /// Number of timers added so far, for naming unnamed ones
+    added: usize,
+    paused_at: Option<Instant>,
+    /// What has been typed for a new timer, while adding one
+    input: Option<LineInput>,
+    /// Why the last timer typed in couldn't be added
+    error: Option<String>,
+}
+
+impl Kitchen {
+    pub fn new(config: &Config, timers: Vec<(String, Duration)>, sound: SoundPlayer) -> Self {
+        let mut kitchen = Kitchen {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            refresh_rate: Duration::from_millis(config.countdown_refresh_rate),
+            sound,
+            hooks: Hooks::new(&config.hooks),
+            timers: Vec::new(),
+            added: 0,
+            paused_at: None,
+            input: None,
+            error: None,
+        };
+        let now = clock::now();
+        for (name, length) in timers {
+            kitchen.add(name, length, now);
+        }
+        kitchen
+    }
+
+    fn add(&mut self, name: String, length: Duration, now: Instant) {
+        info!(name, secs = length.as_secs(), "kitchen timer added");
+        self.added += 1;
+        self.timers.push(Timer { name, length, end_time: now + length, finished_at: None });
+    }
+
+    /// Name for the next timer if none is typed
+    fn next_name(&self) -> String {
+        format!("Timer {}", self.added + 1)
+    }
+
+    /// Add the typed timer, or keep the text so it can be corrected
+    fn submit(&mut self, text: String, now: Instant) {
+        self.input = None;
+        if text.trim().is_empty() {
+            self.error = None;
+            return;
+        }
+        match parse_timer(&text, &self.next_name()) {
+            Ok((name, length)) => {
+                self.error = None;
+                // A timer added while paused starts when the others do
+                self.add(name, length, self.paused_at.unwrap_or(now));
+            }
+            Err(e) => {
+                self.error = Some(format!("Couldn't add {}: {}", text.trim(), e));
+                self.input = Some(LineInput::with_text(text));
+            }
+        }
+    }
+
+    fn remaining(&self, timer: &Timer, now: Instant) -> Duration {
+        timer.remaining(self.paused_at.unwrap_or(now))
+    }
+
+    /// Index of the running timer that will finish first
+    fn soonest_index(&self, now: Instant) -> Option<usize> {
+        (0..self.timers.len())
+            .filter(|&i| self.timers[i].finished_at.is_none())
+            .min_by_key(|&i| self.remaining(&self.timers[i], now))
+    }
+
+    fn soonest(&self, now: Instant) -> Option<&Timer> {
+        self.soonest_index(now).map(|i| &self.timers[i])
+    }
+
+    fn soonest_mut(&mut self, now: Instant) -> Result<&mut Timer, String> {
+        let i = self.soonest_index(now).ok_or_else(|| "no kitchen timers are running".to_string())?;
+        Ok(&mut self.timers[i])
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now);
+            self.fire_hook(HookEvent::Pause, None);
+        }
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            for timer in self.timers.iter_mut().filter(|timer| timer.finished_at.is_none()) {
+                timer.end_time += now - paused_at;
+            }
+            self.fire_hook(HookEvent::Resume, None);
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent, timer: Option<&Timer>) {
+        let context = HookContext {
+            mode: "kitchen",
+            label: timer.map(|timer| timer.name.clone()),
+            duration_secs: timer.map(|timer| timer.length.as_secs()),
+            cycle: None,
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    fn on_input_key(&mut self, key: KeyEvent, now: Instant) {
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        match input.on_key(&key) {
+            Edit::Submit(text) => self.submit(text, now),
+            Edit::Cancel => {
+                self.input = None;
+                self.error = None;
+            }
+            Edit::Editing => {}
+        }
+    }
+
+    /// One line of the list: the name, then the time left or "DONE"
+    fn timer_line(&self, timer: &Timer, name_width: usize, now: Instant) -> Line<'static> {
+        let name = format!("{:<width$}  ", timer.name, width = name_width);
+        match timer.finished_at {
+            Some(finished_at) => {
+                // Flash until it's cleared
+                let mut style = self.theme.times_up;
+                if ((now - finished_at).as_millis() / 500).is_multiple_of(2) {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                Line::from(vec![Span::styled(name, style), Span::styled(format!("{:>8}", "DONE"), style)])
+            }
+            None => {
+                let remaining = self.remaining(timer, now);
+                // Round up so 0:00 only shows once the time is up
+                let time = clock_time(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0));
+                Line::from(vec![
+                    Span::styled(name, self.theme.ui_text),
+                    Span::styled(format!("{:>8}", time), self.theme.countdown),
+                ])
+            }
+        }
+    }
+}
+
+impl Mode for Kitchen {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if self.paused_at.is_some() {
+            return Flow::Continue;
+        }
+
+        // Each timer rings on its own as it runs out
+        let mut finished = Vec::new();
+        for (i, timer) in self.timers.iter_mut().enumerate() {
+            if timer.finished_at.is_none() && now >= timer.end_time {
+                info!(name = timer.name, "kitchen timer finished");
+                timer.finished_at = Some(now);
+                finished.push(i);
+            }
+        }
+        for i in finished {
+            self.sound.play(Sound::Complete);
+            self.fire_hook(HookEvent::Complete, Some(&self.timers[i]));
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if self.input.is_some() {
+            // Only Ctrl+C quits while typing, so names can contain a q
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Flow::Quit;
+            }
+            self.on_input_key(key, now);
+            return Flow::Continue;
+        }
+
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+        match key.code {
+            KeyCode::Char('a') => self.input = Some(LineInput::new()),
+            KeyCode::Char('x') => self.timers.retain(|timer| timer.finished_at.is_none()),
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn typing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.paused_at.is_some() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            // Adding time and skipping apply to the timer that will finish first
+            Command::Add(secs) => {
+                let timer = self.soonest_mut(now)?;
+                let added = Duration::from_secs(secs);
+                timer.end_time = timer.end_time.checked_add(added).ok_or_else(|| TOO_MUCH_TIME.to_string())?;
+                timer.length = timer.length.saturating_add(added);
+            }
+            Command::Skip => {
+                let end_time = self.paused_at.unwrap_or(now);
+                self.soonest_mut(now)?.end_time = end_time;
+            }
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let soonest = self.soonest(now);
+        let done: Vec<&str> = self
+            .timers
+            .iter()
+            .filter(|timer| timer.finished_at.is_some())
+            .map(|timer| timer.name.as_str())
+            .collect();
+        Status {
+            mode: "kitchen",
+            label: soonest.map(|timer| timer.name.clone()),
+            time: soonest.map_or_else(|| clock_time(0), |timer| clock_time(self.remaining(timer, now).as_secs())),
+            remaining: soonest.map(|timer| self.remaining(timer, now)),
+            elapsed: None,
+            paused: self.paused_at.is_some(),
+            finished: false,
+            prompt: (!done.is_empty()).then(|| format!("Done: {}. Press x to clear.", done.join(", "))),
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        let mut header = if self.input.is_some() {
+            vec![Line::raw("Type a name and time, e.g. pasta 9:00, then Enter (Esc to cancel)")]
+        } else {
+            instruction_lines(instructions(&self.instructions.kitchen, &[]))
+        };
+        if self.paused_at.is_some() {
+            header.extend(ui::status_line(&[Badge::Paused], theme));
+        }
+
+        // Finished timers first, then the rest by how soon they finish
+        let mut timers: Vec<&Timer> = self.timers.iter().collect();
+        timers.sort_by_key(|timer| (timer.finished_at.is_none(), self.remaining(timer, now)));
+        let lines: Vec<Line> = if timers.is_empty() {
+            vec![Line::styled("No timers yet. Press a to add one.", theme.ui_text)]
+        } else {
+            let name_width = timers.iter().map(|timer| timer.name.chars().count()).max().unwrap_or(0);
+            timers.iter().map(|timer| self.timer_line(timer, name_width, now)).collect()
+        };
+
+        let mut footer = Vec::new();
+        if let Some(input) = &self.input {
+            footer.push(input.line("New timer: ", theme.ui_text));
+        }
+        if let Some(error) = &self.error {
+            footer.push(Line::styled(error.clone(), theme.times_up));
+        }
+        ui::draw_list(frame, &header, theme.ui_text, lines, footer);
+    }
+
+    fn exit_message(&self) -> String {
+        let done = self.timers.iter().filter(|timer| timer.finished_at.is_some()).count();
+        format!("Kitchen timers closed ({} of {} done).", done, self.timers.len())
+    }
+}
@@ -0,0 +1,58 @@
+// src/modes/line_input.rs
+//! A one-line text input, typed into while the timer keeps running
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{style::Style, text::Line};
+use std::mem;
+
+/// What a key did to the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// The text changed, or the key was ignored
+    Editing,
+    /// Enter was pressed with this text
+    Submit(String),
+    /// Esc was pressed
+    Cancel,
+}
+
+/// Text typed a key at a time: characters are added, Backspace deletes the
+/// last, Enter submits and Esc cancels
+#[derive(Debug, Clone, Default)]
+pub struct LineInput {
+    text: String,
+}
+
+impl LineInput {
+    pub fn new() -> Self {
+        LineInput::default()
+    }
+
+    /// An input holding `text`, to correct something that couldn't be used
+    pub fn with_text(text: String) -> Self {
+        LineInput { text }
+    }
+
+    /// What has been typed so far
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    pub fn on_key(&mut self, key: &KeyEvent) -> Edit {
+        match key.code {
+            KeyCode::Enter => return Edit::Submit(mem::take(&mut self.text)),
+            KeyCode::Esc => return Edit::Cancel,
+            KeyCode::Backspace => {
+                self.text.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.text.push(c),
+            _ => {}
+        }
+        Edit::Editing
+    }
+
+    /// The input after `prompt`, with a cursor at the end
+    pub fn line(&self, prompt: &str, style: Style) -> Line<'static> {
+        Line::styled(format!("{}{}_", prompt, self.text), style)
+    }
+}
@@ -0,0 +1,281 @@
+// src/modes/metronome.rs
+//! Metronome mode: a beat at a steady tempo, flashed on screen and clicked
+//!
+//! The big digits count the beats of each bar, and the whole screen flashes
+//! on every beat, in the alert color on the first beat of a bar. The tempo
+//! can be tapped in with space or nudged with `+` and `-` while it runs.
+
+use super::{instruction_lines, instructions, is_quit_key, Flow, Mode, Status};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    locale::{fill, messages},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    ui::{self, Badge, DigitState, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Slowest and fastest tempos, in beats per minute
+pub const MIN_BPM: u32 = 20;
+pub const MAX_BPM: u32 = 300;
+
+/// Redraw often enough for a short flash on every beat
+const REFRESH_RATE: Duration = Duration::from_millis(20);
+
+/// Longest the screen stays lit after a beat
+const FLASH_FOR: Duration = Duration::from_millis(150);
+
+/// Taps further apart than this start counting the tempo afresh
+const TAP_GAP: Duration = Duration::from_secs(2);
+
+/// Most recent taps the tapped tempo is averaged over
+const TAPS_KEPT: usize = 5;
+
+/// Parse a tempo like `60bpm`, `60 bpm` or `60`
+pub fn parse_tempo(text: &str) -> Result<u32, String> {
+    let text = text.trim();
+    let number = text.strip_suffix("bpm").or_else(|| text.strip_suffix("BPM")).unwrap_or(text).trim();
+    match number.parse::<u32>() {
+        Ok(bpm) if (MIN_BPM..=MAX_BPM).contains(&bpm) => Ok(bpm),
+        Ok(_) => Err(format!("{} is outside {} to {} bpm", text, MIN_BPM, MAX_BPM)),
+        Err(_) => Err(format!("`{}` is not a tempo", text)),
+    }
+}
+
+pub struct Metronome {
+    theme: Theme,
+    instructions: InstructionSettings,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    sound: SoundPlayer,
+    bpm: u32,
+    beats_per_bar: u32,
+    /// A beat and when it fell, which the beats after it are counted from
+    anchor: (u64, Instant),
+    /// Beats played so far, counting from 1
+    beats: u64,
+    /// When the last beat played
+    last_beat_at: Option<Instant>,
+    paused_at: Option<Instant>,
+    /// Recent taps of the tempo key
+    taps: Vec<Instant>,
+}
+
+impl Metronome {
+    pub fn new(config: &Config, bpm: u32, beats_per_bar: u32, sound: SoundPlayer) -> Self {
+        Metronome {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            sound,
+            bpm,
+            beats_per_bar: beats_per_bar.max(1),
+            anchor: (1, clock::now()),
+            beats: 0,
+            last_beat_at: None,
+            paused_at: None,
+            taps: Vec::new(),
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60) / self.bpm
+    }
+
+    /// The last beat due by `now`, and when it fell
+    fn due(&self, now: Instant) -> (u64, Instant) {
+        let (beat, at) = self.anchor;
+        let interval = self.interval();
+        let since = now.saturating_duration_since(at);
+        let count = (since.as_nanos() / interval.as_nanos()) as u32;
+        (beat + u64::from(count), at + interval * count)
+    }
+
+    /// Change the tempo, carrying on from the last beat played
+    fn set_tempo(&mut self, bpm: u32, now: Instant) {
+        let bpm = bpm.clamp(MIN_BPM, MAX_BPM);
+        if bpm == self.bpm {
+            return;
+        }
+        info!(bpm, "metronome tempo changed");
+        let last = self.last_beat_at.unwrap_or(now);
+        self.bpm = bpm;
+        self.anchor = (self.beats + 1, last + self.interval());
+    }
+
+    /// Count a tap of the tempo key, setting the tempo once there are two
+    fn tap(&mut self, now: Instant) {
+        self.resume(now);
+        if self.taps.last().is_some_and(|last| now.saturating_duration_since(*last) > TAP_GAP) {
+            self.taps.clear();
+        }
+        self.taps.push(now);
+        if self.taps.len() > TAPS_KEPT {
+            self.taps.remove(0);
+        }
+        let [first, .., last] = self.taps[..] else {
+            return;
+        };
+        let average = (last - first) / (self.taps.len() as u32 - 1);
+        let bpm = (60.0 / average.as_secs_f64()).round() as u32;
+        self.set_tempo(bpm, now);
+        // The tap is the beat, so the next one follows it
+        self.beats += 1;
+        self.last_beat_at = Some(now);
+        self.anchor = (self.beats + 1, now + self.interval());
+    }
+
+    fn pause(&mut self, now: Instant) {
+        self.paused_at.get_or_insert(now);
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.anchor.1 += now.saturating_duration_since(paused_at);
+        }
+    }
+
+    /// The beat within the bar, from 1, and the bar, from 1
+    fn position(&self) -> (u64, u64) {
+        let beat = self.beats.max(1) - 1;
+        let per_bar = u64::from(self.beats_per_bar);
+        (beat % per_bar + 1, beat / per_bar + 1)
+    }
+
+    /// The tempo, like "120 bpm"
+    fn tempo(&self) -> String {
+        fill(&messages().bpm, &[("bpm", &self.bpm.to_string())])
+    }
+
+    /// Where the metronome is, filled into `template`, like "Bar 3, beat 2 of 4"
+    fn bar_beat(&self, template: &str) -> String {
+        let (beat, bar) = self.position();
+        fill(
+            template,
+            &[("bar", &bar.to_string()), ("beat", &beat.to_string()), ("beats", &self.beats_per_bar.to_string())],
+        )
+    }
+
+    /// Color to flash the screen with at `now`, if a beat just fell
+    fn flash(&self, now: Instant) -> Option<Color> {
+        let since = now.saturating_duration_since(self.last_beat_at?);
+        if self.paused_at.is_some() || since >= FLASH_FOR.min(self.interval() / 3) {
+            return None;
+        }
+        let style = if self.position().0 == 1 { self.theme.times_up } else { self.theme.countdown };
+        Some(style.fg.unwrap_or(Color::White))
+    }
+}
+
+impl Mode for Metronome {
+    fn refresh_rate(&self) -> Duration {
+        REFRESH_RATE
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if self.paused_at.is_some() {
+            return Flow::Continue;
+        }
+        // Beats missed while the terminal was busy aren't played late
+        let (beat, at) = self.due(now);
+        if beat > self.beats {
+            self.beats = beat;
+            self.last_beat_at = Some(at);
+            self.sound.play(Sound::Beat);
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+        match key.code {
+            KeyCode::Char(' ') => self.tap(now),
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => self.set_tempo(self.bpm + 1, now),
+            KeyCode::Char('-') | KeyCode::Down => self.set_tempo(self.bpm - 1, now),
+            KeyCode::Char('p') if self.paused_at.is_some() => self.resume(now),
+            KeyCode::Char('p') => self.pause(now),
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.paused_at.is_some() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            Command::Add(_) | Command::Skip => return Err(format!("{} is not supported by the metronome", command)),
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, _now: Instant) -> Status {
+        Status {
+            mode: "metronome",
+            label: Some(self.bar_beat(&messages().bar_beat_short)),
+            time: self.tempo(),
+            remaining: None,
+            elapsed: None,
+            paused: self.paused_at.is_some(),
+            finished: false,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        let mut header = instruction_lines(instructions(&self.instructions.metronome, &[]));
+        if self.paused_at.is_some() {
+            header.extend(ui::status_line(&[Badge::Paused], theme));
+        }
+
+        let (beat, _) = self.position();
+        let panel = vec![
+            Line::styled(self.tempo(), theme.ui_text),
+            Line::styled(self.bar_beat(&messages().bar_beat), theme.ui_text),
+        ];
+        let time_style = if beat == 1 { theme.times_up } else { theme.countdown };
+        ui::draw_timer_with_panel(
+            frame,
+            &header,
+            theme.ui_text,
+            &beat.to_string(),
+            self.digits,
+            &self.digit_state,
+            time_style,
+            panel,
+        );
+
+        if let Some(color) = self.flash(now) {
+            let area = frame.area();
+            frame.buffer_mut().set_style(area, Style::default().fg(Color::Black).bg(color));
+        }
+    }
+
+    fn exit_message(&self) -> String {
+        format!("Metronome stopped at {} bpm.", self.bpm)
+    }
+}
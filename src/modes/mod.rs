@@ -0,0 +1,347 @@
+// src/modes/mod.rs
+//! Timer modes driven by the event loop in `app`
+
+mod chain;
+mod countdown;
+mod cube;
+mod flowtime;
+mod kitchen;
+mod line_input;
+mod metronome;
+mod multi_stopwatch;
+mod plan;
+mod pomodoro;
+mod sequence;
+mod standup;
+mod stopwatch;
+mod timer_state;
+mod wall_clock;
+mod watch;
+
+pub use chain::{Chain, Step};
+pub use countdown::Countdown;
+pub use cube::Cube;
+pub use flowtime::Flowtime;
+pub use kitchen::{parse_timer, Kitchen};
+pub use metronome::{parse_tempo, Metronome};
+pub use multi_stopwatch::{MultiStopwatch, MAX_STOPWATCHES};
+pub use plan::{load_plan, parse_block, Block, Plan};
+pub use pomodoro::{Pomodoro, TotalTime};
+pub use sequence::{Sequence, TECHNIQUES};
+pub use standup::Standup;
+pub use stopwatch::{Precision, Stopwatch};
+pub use wall_clock::{is_valid_date_format, WallClock};
+pub use watch::{parse_deadline, parse_target, Build, Target, Watched};
+
+use crate::{
+    control::Command,
+    digit::DigitOptions,
+    locale::{self, Messages},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    ui::Screen,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::text::Line;
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// What the event loop should do after a mode handles an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Snapshot of a mode's state, reported to control clients
+#[derive(Debug, Clone)]
+pub struct Status {
+    /// Name of the running mode
+    pub mode: &'static str,
+    /// Current session or phase, if the mode has one
+    pub label: Option<String>,
+    /// The time as shown on screen
+    pub time: String,
+    /// Time left, for modes that count down
+    pub remaining: Option<Duration>,
+    /// Time elapsed, for modes that count up
+    pub elapsed: Option<Duration>,
+    pub paused: bool,
+    pub finished: bool,
+    /// Message shown while waiting for the user, e.g. between Pomodoro sessions
+    pub prompt: Option<String>,
+}
+
+impl Status {
+    /// The state as JSON, for the HTTP server and watching control clients
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mode": self.mode,
+            "label": self.label,
+            "time": self.time,
+            "remaining_secs": self.remaining.map(|remaining| remaining.as_secs_f64()),
+            "elapsed_secs": self.elapsed.map(|elapsed| elapsed.as_secs_f64()),
+            "paused": self.paused,
+            "finished": self.finished,
+            "prompt": self.prompt,
+        })
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mode)?;
+        if let Some(label) = &self.label {
+            write!(f, " [{}]", label)?;
+        }
+        write!(f, " {}", self.time)?;
+        if self.paused {
+            write!(f, " (paused)")?;
+        }
+        if self.finished {
+            write!(f, " (finished)")?;
+        }
+        Ok(())
+    }
+}
+
+/// What a run came to, summed up once the timer exits. Modes fill in the
+/// figures they keep track of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// How long the run lasted, pauses included, filled in by the event loop
+    pub duration: Duration,
+    /// How long the timer was set to run, for timers that count down
+    pub requested: Option<Duration>,
+    /// Time the timer spent running rather than paused, or the work time
+    /// for Pomodoro and Flowtime
+    pub focused: Option<Duration>,
+    /// Laps marked on the stopwatch
+    pub laps: Option<usize>,
+    /// Pomodoro cycles completed
+    pub cycles: Option<u64>,
+    /// Times the timer was paused, by hand or while idle
+    pub interruptions: Option<u32>,
+}
+
+impl Summary {
+    /// The figures of two timers run one after the other, added up
+    pub fn merge(self, other: Summary) -> Summary {
+        fn add<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            }
+        }
+        Summary {
+            duration: self.duration + other.duration,
+            requested: add(self.requested, other.requested),
+            focused: add(self.focused, other.focused),
+            laps: add(self.laps, other.laps),
+            cycles: add(self.cycles, other.cycles),
+            interruptions: add(self.interruptions, other.interruptions),
+        }
+    }
+
+    /// The figures as JSON, along with the mode that ran and whether it
+    /// finished, for `--json-summary`
+    pub fn to_json(&self, mode: &str, completed: bool) -> Value {
+        json!({
+            "mode": mode,
+            "requested_secs": self.requested.map(|requested| requested.as_secs_f64()),
+            "elapsed_secs": self.duration.as_secs_f64(),
+            "focused_secs": self.focused.map(|focused| focused.as_secs_f64()),
+            "completed": completed,
+            "laps": self.laps,
+            "cycles": self.cycles,
+            "interruptions": self.interruptions,
+        })
+    }
+
+    /// One line for each figure, labelled in `messages`, label and value lined up
+    pub fn lines(&self, messages: &Messages) -> Vec<String> {
+        let mut figures = vec![(&messages.summary_duration, clock_time(self.duration.as_secs()))];
+        if let Some(focused) = self.focused {
+            figures.push((&messages.summary_focused, clock_time(focused.as_secs())));
+        }
+        if let Some(laps) = self.laps {
+            figures.push((&messages.summary_laps, laps.to_string()));
+        }
+        if let Some(cycles) = self.cycles {
+            figures.push((&messages.summary_cycles, cycles.to_string()));
+        }
+        if let Some(interruptions) = self.interruptions {
+            figures.push((&messages.summary_interruptions, interruptions.to_string()));
+        }
+        // Translated labels can run longer than the English ones
+        let width = figures.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0).max(14);
+        figures.into_iter().map(|(label, value)| format!("{:<width$} {}", label, value)).collect()
+    }
+}
+
+/// A timer mode: a state machine fed by ticks, key presses and control commands
+pub trait Mode {
+    /// How often the display should be refreshed
+    fn refresh_rate(&self) -> Duration;
+
+    /// Advance time-based state
+    fn on_tick(&mut self, _now: Instant) -> Flow {
+        Flow::Continue
+    }
+
+    /// Handle a key press
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow;
+
+    /// Whether the mode needs to hear when keys are let go, as well as pressed
+    fn wants_key_release(&self) -> bool {
+        false
+    }
+
+    /// Handle a key being let go, if `wants_key_release` asked for it
+    fn on_key_release(&mut self, _key: KeyEvent, _now: Instant) -> Flow {
+        Flow::Continue
+    }
+
+    /// Whether the mode is reading typed text, so keys that work in every
+    /// mode, like `t`, are left to it
+    fn typing(&self) -> bool {
+        false
+    }
+
+    /// Whether quitting now would cut a running timer short, so that with
+    /// `confirm_quit` on, `q` asks first
+    fn guards_quit(&self, _now: Instant) -> bool {
+        false
+    }
+
+    /// Whether something on screen is part way through changing, like digits
+    /// with `digit_transition`, so the next frame should come sooner
+    fn animating(&self) -> bool {
+        false
+    }
+
+    /// Restyle the mode with a theme picked while it runs
+    fn set_theme(&mut self, theme: Theme);
+
+    /// Handle a control command (other than status and quit, which are generic)
+    /// Returns an error message if the command doesn't apply to this mode
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String>;
+
+    /// Describe the current state for control clients
+    fn status(&self, now: Instant) -> Status;
+
+    /// Draw the current state
+    fn draw(&self, frame: &mut Screen, now: Instant);
+
+    /// Message printed to the terminal once the mode exits
+    fn exit_message(&self) -> String;
+
+    /// The figures for the summary shown once the mode exits, beyond how long
+    /// the run lasted
+    fn summary(&self, _now: Instant) -> Summary {
+        Summary::default()
+    }
+}
+
+/// Key that exits every mode, along with Ctrl+C
+pub const QUIT_KEY: char = 'q';
+
+/// Reply to an `add` that would push a timer past what a clock can hold
+pub const TOO_MUCH_TIME: &str = "too much time to add";
+
+/// Returns true if the key event asks to quit (q or Ctrl+C)
+pub fn is_quit_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char(QUIT_KEY) ||
+        (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Fill in a configured instruction line, replacing `{quit_key}` and each
+/// `{name}` in `values`
+pub fn instructions(template: &str, values: &[(&str, &str)]) -> String {
+    locale::fill(template, values).replace("{quit_key}", &QUIT_KEY.to_string())
+}
+
+/// Header lines starting with the instructions, or none when they're turned off
+pub fn instruction_lines(instructions: String) -> Vec<Line<'static>> {
+    if instructions.trim().is_empty() {
+        Vec::new()
+    } else {
+        vec![Line::raw(instructions)]
+    }
+}
+
+/// Hide the colons during the "off" half of each second when blinking is enabled.
+/// They keep their place, so only their style changes from frame to frame.
+fn blink(digits: DigitOptions, enabled: bool, elapsed: Duration) -> DigitOptions {
+    // Toggle blink state about once per second
+    let off = enabled && !(elapsed.as_millis() / 500).is_multiple_of(2);
+    DigitOptions { colons_hidden: off, ..digits }
+}
+
+/// How long the digits stay highlighted after a chime
+const CHIME_PULSE: Duration = Duration::from_millis(800);
+
+/// Rings each time another interval of timer time has passed (`--chime-every`)
+struct Chime {
+    interval: Duration,
+    sound: SoundPlayer,
+    /// Number of whole intervals at the last check
+    intervals: Option<u128>,
+    /// When the chime last rang
+    rang_at: Option<Instant>,
+}
+
+impl Chime {
+    fn new(interval: Duration, sound: SoundPlayer) -> Self {
+        Chime { interval, sound, intervals: None, rang_at: None }
+    }
+
+    /// Ring if the timer has passed another interval since the last check.
+    /// The first check only takes note, so a resumed timer doesn't ring straight away.
+    fn check(&mut self, elapsed: Duration, now: Instant) {
+        let intervals = elapsed.as_millis() / self.interval.as_millis().max(1);
+        if self.intervals.is_some_and(|previous| intervals > previous) {
+            info!(elapsed_secs = elapsed.as_secs(), "chime");
+            self.sound.play(Sound::Chime);
+            self.rang_at = Some(now);
+        }
+        self.intervals = Some(intervals);
+    }
+
+    /// Start counting intervals again, for a timer starting afresh
+    fn reset(&mut self) {
+        self.intervals = None;
+        self.rang_at = None;
+    }
+
+    /// Whether the digits should be highlighted for a recent chime
+    fn pulsing(&self, now: Instant) -> bool {
+        self.rang_at.is_some_and(|rang_at| now - rang_at < CHIME_PULSE)
+    }
+}
+
+/// Format whole seconds as M:SS, or H:MM:SS once there is at least an hour
+fn clock_time(total_secs: u64) -> String {
+    let minutes = total_secs / 60;
+    let seconds = total_secs % 60;
+
+    if minutes >= 60 {
+        format!("{}:{:02}:{:02}", minutes / 60, minutes % 60, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Format a duration as e.g. "1h 05m", or "25m" under an hour
+pub fn hours_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
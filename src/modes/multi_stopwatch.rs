@@ -0,0 +1,233 @@
+// src/modes/multi_stopwatch.rs
+//! Multi-stopwatch mode: a stopwatch for each task, started and stopped with
+//! its number key, to see where the time went while switching between them
+//!
+//! With `multi_stopwatch_exclusive` on, starting one stopwatch stops the one
+//! running, so the time is split between the tasks; off, any of them can run
+//! at once.
+
+use super::{clock_time, instruction_lines, instructions, is_quit_key, Flow, Mode, Status, Summary};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    locale::{fill, messages},
+    theme::Theme,
+    ui::{self, Badge, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::Modifier,
+    text::{Line, Span},
+};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Most stopwatches, one for each number key
+pub const MAX_STOPWATCHES: usize = 9;
+
+/// One task's stopwatch
+struct Watch {
+    label: String,
+    /// Time run before the current stretch
+    ran: Duration,
+    /// When the current stretch started, while it runs
+    running_since: Option<Instant>,
+}
+
+impl Watch {
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.ran + self.running_since.map_or(Duration::ZERO, |since| now.saturating_duration_since(since))
+    }
+
+    fn start(&mut self, now: Instant) {
+        self.running_since.get_or_insert(now);
+    }
+
+    fn stop(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.ran += now.saturating_duration_since(since);
+        }
+    }
+}
+
+pub struct MultiStopwatch {
+    theme: Theme,
+    instructions: InstructionSettings,
+    refresh_rate: Duration,
+    /// Whether starting a stopwatch stops the others
+    exclusive: bool,
+    watches: Vec<Watch>,
+    /// The stopwatch started last, which added time goes to
+    current: Option<usize>,
+    /// The stopwatches stopped by a pause, to start again on resuming
+    paused: Option<Vec<usize>>,
+}
+
+impl MultiStopwatch {
+    pub fn new(config: &Config, labels: Vec<String>) -> Self {
+        MultiStopwatch {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            refresh_rate: Duration::from_millis(config.stopwatch_refresh_rate),
+            exclusive: config.multi_stopwatch_exclusive,
+            watches: labels
+                .into_iter()
+                .take(MAX_STOPWATCHES)
+                .map(|label| Watch { label, ran: Duration::ZERO, running_since: None })
+                .collect(),
+            current: None,
+            paused: None,
+        }
+    }
+
+    fn running(&self) -> impl Iterator<Item = &Watch> {
+        self.watches.iter().filter(|watch| watch.running_since.is_some())
+    }
+
+    /// Start the stopwatch at `index` if it is stopped, or stop it
+    fn toggle(&mut self, index: usize, now: Instant) {
+        if index >= self.watches.len() {
+            return;
+        }
+        // Switching tasks carries on from a pause
+        self.paused = None;
+        if self.watches[index].running_since.is_some() {
+            info!(label = self.watches[index].label, "stopwatch stopped");
+            self.watches[index].stop(now);
+            return;
+        }
+        if self.exclusive {
+            for watch in &mut self.watches {
+                watch.stop(now);
+            }
+        }
+        info!(label = self.watches[index].label, "stopwatch started");
+        self.watches[index].start(now);
+        self.current = Some(index);
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.paused.is_some() {
+            return;
+        }
+        let running = (0..self.watches.len()).filter(|&i| self.watches[i].running_since.is_some()).collect();
+        for watch in &mut self.watches {
+            watch.stop(now);
+        }
+        self.paused = Some(running);
+    }
+
+    fn resume(&mut self, now: Instant) {
+        for i in self.paused.take().unwrap_or_default() {
+            self.watches[i].start(now);
+        }
+    }
+
+    fn total(&self, now: Instant) -> Duration {
+        self.watches.iter().map(|watch| watch.elapsed(now)).sum()
+    }
+
+    /// One line of the list: the key, the label and the time run
+    fn watch_line(&self, number: usize, watch: &Watch, label_width: usize, now: Instant) -> Line<'static> {
+        let (marker, label_style, time_style) = if watch.running_since.is_some() {
+            let marker = if self.theme.badge_icons { "▶" } else { ">" };
+            (marker, self.theme.ui_text.add_modifier(Modifier::BOLD), self.theme.stopwatch.add_modifier(Modifier::BOLD))
+        } else {
+            (" ", self.theme.ui_text, self.theme.ui_text.add_modifier(Modifier::DIM))
+        };
+        Line::from(vec![
+            Span::styled(format!("{} {}  {:<width$}  ", marker, number, watch.label, width = label_width), label_style),
+            Span::styled(format!("{:>8}", clock_time(watch.elapsed(now).as_secs())), time_style),
+        ])
+    }
+}
+
+impl Mode for MultiStopwatch {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+        match key.code {
+            KeyCode::Char(c @ '1'..='9') => self.toggle(c as usize - '1' as usize, now),
+            KeyCode::Char(' ') if self.paused.is_some() => self.resume(now),
+            KeyCode::Char(' ') => self.pause(now),
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.paused.is_some() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            // Time added goes to the stopwatch started last
+            Command::Add(secs) => {
+                let current = self.current.ok_or_else(|| "no stopwatch has been started".to_string())?;
+                let watch = &mut self.watches[current];
+                watch.ran = watch.ran.saturating_add(Duration::from_secs(secs));
+            }
+            Command::Skip => return Err("skip is not supported by multiple stopwatches".to_string()),
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let running: Vec<&str> = self.running().map(|watch| watch.label.as_str()).collect();
+        let total = self.total(now);
+        Status {
+            mode: "multi-stopwatch",
+            label: (!running.is_empty()).then(|| running.join(", ")),
+            time: clock_time(total.as_secs()),
+            remaining: None,
+            elapsed: Some(total),
+            paused: self.paused.is_some(),
+            finished: false,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        let mut header = instruction_lines(instructions(&self.instructions.multi_stopwatch, &[]));
+        if self.paused.is_some() {
+            header.extend(ui::status_line(&[Badge::Paused], theme));
+        }
+
+        let label_width = self.watches.iter().map(|watch| watch.label.chars().count()).max().unwrap_or(0);
+        let lines = self
+            .watches
+            .iter()
+            .enumerate()
+            .map(|(i, watch)| self.watch_line(i + 1, watch, label_width, now))
+            .collect();
+        let total = fill(&messages().total, &[("time", &clock_time(self.total(now).as_secs()))]);
+        let footer = vec![Line::styled(total, theme.ui_text)];
+        ui::draw_list(frame, &header, theme.ui_text, lines, footer);
+    }
+
+    fn exit_message(&self) -> String {
+        let now = clock::now();
+        let times: Vec<String> = self
+            .watches
+            .iter()
+            .map(|watch| format!("{} {}", watch.label, clock_time(watch.elapsed(now).as_secs())))
+            .collect();
+        format!("Stopwatches stopped: {}.", times.join(", "))
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        Summary { focused: Some(self.total(now)), ..Summary::default() }
+    }
+}
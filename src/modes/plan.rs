@@ -0,0 +1,317 @@
+// src/modes/plan.rs
+//! Plan mode: a day of time blocks from a plan file, following the wall clock
+//!
+//! The current block counts down to its end while the blocks still to come
+//! are listed beside it. Blocks start and end at their times of day however
+//! long clockit has been running, so starting late joins the block under way.
+
+use super::{clock_time, instruction_lines, instructions, is_quit_key, Flow, Mode, Status};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    locale::{fill, messages},
+    parse_time_string,
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    ui::{self, DigitState, Screen},
+};
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, Timelike};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    style::Modifier,
+    text::{Line, Span},
+};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// Most upcoming blocks listed beside the timer
+const UPCOMING_SHOWN: usize = 8;
+
+/// One block of the plan, like "10:30 break 15m"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub start: NaiveTime,
+    pub name: String,
+    /// How long the block lasts, or None to run until the next one starts
+    pub length: Option<Duration>,
+}
+
+/// Parse a block: a start time, what it is for, and optionally how long it
+/// lasts (`90m`, `1h30m`, `1:30:00`)
+pub fn parse_block(text: &str) -> Result<Block, String> {
+    let text = text.trim();
+    let (start, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let start = NaiveTime::parse_from_str(start, "%H:%M")
+        .map_err(|_| format!("`{}` doesn't start with a time of day like 09:00", text))?;
+
+    let rest = rest.trim();
+    let (name, length) = match rest.rsplit_once(char::is_whitespace) {
+        Some((name, length)) => match parse_length(length) {
+            Some(length) => (name.trim(), Some(length)),
+            None => (rest, None),
+        },
+        None => (rest, None),
+    };
+    if name.is_empty() {
+        return Err(format!("`{}` doesn't say what the block is for", text));
+    }
+    if length.is_some_and(|length| length.is_zero()) {
+        return Err(format!("`{}` lasts no time at all", text));
+    }
+    Ok(Block { start, name: name.to_string(), length })
+}
+
+/// A length like `90m`, `2h`, `1h30m` or `45s`, or a time like `1:30:00`
+fn parse_length(text: &str) -> Option<Duration> {
+    // Every number needs its unit, so a name ending in a number isn't a length
+    if !text.contains(':') && text.ends_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    parse_time_string(text).ok().map(Duration::from_secs)
+}
+
+/// Read the blocks listed in a plan file, in the order they start
+pub fn load_plan(path: &Path) -> Result<Vec<Block>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let lines: Vec<String> =
+        serde_yaml::from_str(&contents).map_err(|e| format!("{} isn't a list of blocks: {}", path.display(), e))?;
+
+    let mut blocks = lines.iter().map(|line| parse_block(line)).collect::<Result<Vec<_>, _>>()?;
+    if blocks.is_empty() {
+        return Err(format!("{} has no blocks", path.display()));
+    }
+    blocks.sort_by_key(|block| block.start);
+    if blocks.last().is_some_and(|block| block.length.is_none()) {
+        return Err("the last block needs a length, like 30m".to_string());
+    }
+    Ok(blocks)
+}
+
+/// A block placed on today's clock
+struct Scheduled {
+    block: Block,
+    /// Seconds from midnight the block starts and ends at
+    start: u64,
+    end: u64,
+}
+
+pub struct Plan {
+    theme: Theme,
+    instructions: InstructionSettings,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    refresh_rate: Duration,
+    sound: SoundPlayer,
+    blocks: Vec<Scheduled>,
+    /// The wall clock time when the plan started, and the matching instant,
+    /// so the time of day follows the timer's clock
+    started: (DateTime<Local>, Instant),
+    /// Index of the block under way at the last tick
+    current: Option<usize>,
+    finished: bool,
+}
+
+impl Plan {
+    pub fn new(config: &Config, blocks: Vec<Block>, sound: SoundPlayer) -> Self {
+        // Blocks without a length run until the next one starts
+        let starts: Vec<u64> = blocks.iter().map(|block| u64::from(block.start.num_seconds_from_midnight())).collect();
+        let blocks = blocks
+            .into_iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let start = starts[i];
+                let end = match block.length {
+                    Some(length) => start + length.as_secs(),
+                    None => starts.get(i + 1).copied().unwrap_or(start),
+                };
+                Scheduled { block, start, end }
+            })
+            .collect();
+
+        let mut plan = Plan {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            refresh_rate: Duration::from_millis(config.countdown_refresh_rate),
+            sound,
+            blocks,
+            started: (Local::now(), clock::now()),
+            current: None,
+            finished: false,
+        };
+        let now = plan.started.1;
+        plan.current = plan.current_block(now);
+        plan.finished = plan.all_done(now);
+        plan
+    }
+
+    /// Seconds since midnight at `now`, by the wall clock
+    fn time_of_day(&self, now: Instant) -> u64 {
+        let (wall, instant) = self.started;
+        let elapsed = TimeDelta::from_std(now.saturating_duration_since(instant)).unwrap_or_default();
+        let wall = wall + elapsed;
+        let midnight = wall.date_naive().and_time(NaiveTime::MIN);
+        (wall.naive_local() - midnight).num_seconds().max(0) as u64
+    }
+
+    /// The block under way at `now`. Where blocks overlap, the one that
+    /// started last wins.
+    fn current_block(&self, now: Instant) -> Option<usize> {
+        let time = self.time_of_day(now);
+        self.blocks.iter().rposition(|block| block.start <= time && time < block.end)
+    }
+
+    /// The first block still to start at `now`
+    fn next_block(&self, now: Instant) -> Option<usize> {
+        let time = self.time_of_day(now);
+        self.blocks.iter().position(|block| block.start > time)
+    }
+
+    fn all_done(&self, now: Instant) -> bool {
+        let time = self.time_of_day(now);
+        self.blocks.iter().all(|block| block.end <= time)
+    }
+
+    /// The label above the time and the time itself: the current block
+    /// counting down, or the wait until the next one
+    fn display(&self, now: Instant) -> (String, u64) {
+        let time = self.time_of_day(now);
+        if let Some(current) = self.current_block(now) {
+            let block = &self.blocks[current];
+            return (block.block.name.clone(), block.end - time);
+        }
+        match self.next_block(now) {
+            Some(next) => {
+                let block = &self.blocks[next];
+                let starts = block.block.start.format("%H:%M").to_string();
+                (fill(&messages().free_until, &[("time", &starts)]), block.start - time)
+            }
+            None => (messages().plan_complete.clone(), 0),
+        }
+    }
+
+    /// The blocks under way and still to come, for the list beside the timer
+    fn upcoming_lines(&self, now: Instant) -> Vec<Line<'static>> {
+        let time = self.time_of_day(now);
+        let current = self.current_block(now);
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(i, block)| Some(*i) == current || block.start > time)
+            .take(UPCOMING_SHOWN)
+            .map(|(i, block)| {
+                let minutes = (block.end - block.start) / 60;
+                let text = format!("{}  {} ({}m)", block.block.start.format("%H:%M"), block.block.name, minutes);
+                if Some(i) == current {
+                    Line::from(Span::styled(text, self.theme.ui_text.add_modifier(Modifier::BOLD)))
+                } else {
+                    Line::from(Span::styled(text, self.theme.ui_text))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Mode for Plan {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        let current = self.current_block(now);
+        if current != self.current {
+            if let Some(ended) = self.current {
+                info!(block = self.blocks[ended].block.name, "plan block ended");
+                self.sound.play(Sound::Complete);
+            }
+            if let Some(started) = current {
+                info!(block = self.blocks[started].block.name, "plan block started");
+            }
+            self.current = current;
+        }
+
+        if !self.finished && self.all_done(now) {
+            info!("plan complete");
+            self.finished = true;
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, _now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, _now: Instant) -> Result<Flow, String> {
+        match command {
+            Command::Status | Command::Quit => Ok(Flow::Continue),
+            // The plan follows the clock, so there is nothing to pause or skip
+            _ => Err(format!("{} is not supported by the plan", command)),
+        }
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let (label, remaining) = self.display(now);
+        Status {
+            mode: "plan",
+            label: Some(label),
+            time: clock_time(remaining),
+            remaining: Some(Duration::from_secs(remaining)),
+            elapsed: None,
+            paused: false,
+            finished: self.finished,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let mut header = instruction_lines(instructions(&self.instructions.plan, &[]));
+        let (label, remaining) = self.display(now);
+        header.push(Line::raw(label));
+
+        let time_style = if self.finished {
+            self.theme.times_up
+        } else if self.current_block(now).is_some() {
+            self.theme.countdown
+        } else {
+            self.theme.pomodoro_break
+        };
+        ui::draw_timer_with_sidebar(
+            frame,
+            &header,
+            self.theme.ui_text,
+            &clock_time(remaining),
+            self.digits,
+            &self.digit_state,
+            time_style,
+            Vec::new(),
+            self.upcoming_lines(now),
+        );
+    }
+
+    fn exit_message(&self) -> String {
+        if self.finished {
+            "Plan complete!".to_string()
+        } else {
+            "Plan stopped.".to_string()
+        }
+    }
+}
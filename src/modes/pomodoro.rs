@@ -0,0 +1,816 @@
+// src/modes/pomodoro.rs
+//! Pomodoro timer mode: work and break sessions, run through the configured sequence
+
+use super::{
+    blink, clock_time, hours_minutes, instruction_lines, instructions, is_quit_key,
+    line_input::{Edit, LineInput},
+    sequence::Sequence,
+    timer_state::TimerState,
+    Flow, Mode, Status, Summary,
+};
+use crate::{
+    alerts::Alerts,
+    caps, clock,
+    config::{AlertSettings, Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    focus_log,
+    history::{self, Note, SessionKind, SessionRecord},
+    hooks::{HookContext, HookEvent, Hooks},
+    idle::IdleMonitor,
+    locale::{fill, messages},
+    media::MediaControl,
+    progress::{self, ProgressStyle},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    time_format::TimeFormat,
+    ui::{self, Badge, DigitState, Screen},
+};
+use chrono::{Local, SubsecRound};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    style::{Color, Modifier},
+    text::Line,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// How long the digits pulse after the break warning
+const WARNING_PULSE: Duration = Duration::from_secs(5);
+
+/// What the run's total time in the corner counts (`pomodoro.total_time`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TotalTime {
+    /// Nothing; the total isn't shown
+    Off,
+    /// Everything since the run began, breaks included, except time paused
+    #[default]
+    All,
+    /// Only the time spent in work sessions
+    Work,
+}
+
+impl TotalTime {
+    /// Names accepted for `pomodoro.total_time` in the configuration
+    pub const NAMES: [&'static str; 3] = ["off", "all", "work"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "off" => Some(TotalTime::Off),
+            "all" => Some(TotalTime::All),
+            "work" => Some(TotalTime::Work),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        TotalTime::from_name(&config.pomodoro.total_time).unwrap_or_else(|| {
+            warn!(setting = config.pomodoro.total_time, "unknown pomodoro total time");
+            eprintln!("Unknown pomodoro total_time: {}. Using all.", config.pomodoro.total_time);
+            TotalTime::default()
+        })
+    }
+}
+
+/// Where the Pomodoro run currently is
+enum Phase {
+    /// A work or break session is counting down
+    Session {
+        timer: TimerState,
+        /// Part of the time paused spent idle
+        idle_for: Duration,
+        /// Times the session was paused, by hand or while idle
+        interruptions: u32,
+    },
+    /// A session just ended; waiting for any key, or for `auto_continue` to
+    /// run out. Keeps how often and how long it was paused, to show with a
+    /// finished work session.
+    SessionComplete { interruptions: u32, paused_for: Duration },
+    /// Announcing the next session; waiting for the user to continue or quit,
+    /// or for `auto_continue` to run out
+    Transition,
+    /// All configured cycles are done; waiting for any key to exit
+    AllDone,
+}
+
+pub struct Pomodoro {
+    theme: Theme,
+    instructions: InstructionSettings,
+    blink_separator: bool,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// Format for the time instead of the usual one
+    time_format: Option<TimeFormat>,
+    progress: ProgressStyle,
+    /// Draw the progress with plain ASCII characters
+    ascii_only: bool,
+    refresh_rate: Duration,
+    sound: SoundPlayer,
+    /// Save completed sessions to the history file
+    record_history: bool,
+    /// Journal file for completed work sessions
+    focus_log: Option<PathBuf>,
+    /// What the work sessions are for
+    task: Option<String>,
+    hooks: Hooks,
+    media: MediaControl,
+    idle: IdleMonitor,
+    /// When the session was paused automatically because the user went idle
+    idle_paused: Option<Instant>,
+    /// Alert for warning that a break is about to end
+    break_warning: Option<AlertSettings>,
+    /// The warning for the running break, if it is a break
+    warning: Option<Alerts>,
+    /// When the break warning went off
+    warned_at: Option<Instant>,
+    /// Work time completed today, from the history and this run
+    focus_today: Duration,
+    /// What the total in the bottom left corner counts
+    total_time: TotalTime,
+    /// When the run began, for its total time
+    run_started: Instant,
+    /// Time the run has spent paused, not counting the running session's
+    /// current pause
+    run_paused: Duration,
+    /// Time spent in completed work sessions this run
+    worked: Duration,
+    /// Times a session was paused this run, by hand or while idle
+    pauses: u32,
+    /// Time added to a work session each time the extend key is pressed
+    extend_by: Duration,
+    /// How long the screens between sessions wait for a key before the next
+    /// session starts by itself
+    auto_continue: Option<Duration>,
+    /// When the screen between sessions went up
+    screen_since: Instant,
+    /// Interruptions noted during the running session
+    notes: Vec<Note>,
+    /// The note being typed, while one is
+    note_input: Option<LineInput>,
+    sequence: Sequence,
+    /// Number of cycles to run, 0 means run indefinitely
+    cycles: u64,
+    cycle: u64,
+    phase: Phase,
+}
+
+impl Pomodoro {
+    pub fn new(config: &Config, sequence: Sequence, cycles: u64, sound: SoundPlayer) -> Self {
+        let mut pomodoro = Pomodoro {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            blink_separator: config.blink_separator,
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            time_format: TimeFormat::from_setting(&config.time_format.pomodoro, "pomodoro"),
+            progress: ProgressStyle::from_config(config),
+            ascii_only: config.ascii_only || !caps::supports_unicode(),
+            refresh_rate: Duration::from_millis(config.pomodoro.refresh_rate),
+            sound,
+            record_history: config.record_history,
+            focus_log: None,
+            task: None,
+            hooks: Hooks::new(&config.hooks),
+            media: MediaControl::spawn(config.pomodoro.media_control),
+            idle: IdleMonitor::new(&config.idle),
+            idle_paused: None,
+            break_warning: (config.pomodoro.break_warning > 0).then(|| AlertSettings {
+                at: clock_time(config.pomodoro.break_warning),
+                sound: config.pomodoro.break_warning_sound,
+                color: None,
+                message: Some(messages().break_ending.clone()),
+                checkpoint: false,
+            }),
+            warning: None,
+            warned_at: None,
+            focus_today: focus_so_far_today(),
+            total_time: TotalTime::from_config(config),
+            run_started: clock::now(),
+            run_paused: Duration::ZERO,
+            worked: Duration::ZERO,
+            pauses: 0,
+            extend_by: Duration::from_secs(config.pomodoro.extend_by * 60),
+            auto_continue: (config.pomodoro.auto_continue > 0)
+                .then(|| Duration::from_secs(config.pomodoro.auto_continue)),
+            screen_since: clock::now(),
+            notes: Vec::new(),
+            note_input: None,
+            sequence,
+            cycles,
+            cycle: 1,
+            phase: Phase::AllDone,
+        };
+        pomodoro.start_session(pomodoro.run_started);
+        pomodoro
+    }
+
+    /// Start the current phase of the sequence
+    fn start_session(&mut self, now: Instant) {
+        let kind = self.sequence.current().kind;
+        let duration = self.sequence.current().duration;
+        info!(cycle = self.cycle, ?kind, ?duration, "session started");
+        self.phase = Phase::Session {
+            timer: TimerState::new(duration, now),
+            idle_for: Duration::ZERO,
+            interruptions: 0,
+        };
+        self.idle_paused = None;
+        self.notes.clear();
+        // The warning has no color to parse
+        self.warning = self
+            .break_warning
+            .as_ref()
+            .filter(|_| kind == SessionKind::Break)
+            .map(|warning| Alerts::new(std::slice::from_ref(warning), |_| Color::Reset));
+        self.warned_at = None;
+
+        let event = match kind {
+            SessionKind::Work => {
+                self.media.resume();
+                HookEvent::WorkStart
+            }
+            SessionKind::Break => {
+                self.media.pause();
+                HookEvent::BreakStart
+            }
+        };
+        self.fire_hook(event);
+    }
+
+    /// Pause the running session as of `at`, returning whether it was running
+    fn pause_session(&mut self, at: Instant) -> bool {
+        let Phase::Session { timer, interruptions, .. } = &mut self.phase else {
+            return false;
+        };
+        if !timer.pause(at) {
+            return false;
+        }
+        *interruptions += 1;
+        self.pauses += 1;
+        self.fire_hook(HookEvent::Pause);
+        true
+    }
+
+    /// Resume a paused session, returning how long it was paused
+    fn resume_session(&mut self, now: Instant) -> Option<Duration> {
+        let Phase::Session { timer, .. } = &mut self.phase else {
+            return None;
+        };
+
+        let paused = timer.resume(now)?;
+        self.run_paused += paused;
+        self.fire_hook(HookEvent::Resume);
+        Some(paused)
+    }
+
+    /// Give a work session more time, for finishing a thought before the bell
+    fn extend_session(&mut self) {
+        if self.sequence.current().kind != SessionKind::Work {
+            return;
+        }
+        if let Phase::Session { timer, .. } = &mut self.phase {
+            timer.extend(self.extend_by);
+            info!(extended_secs = timer.extended().as_secs(), "session extended");
+        }
+    }
+
+    /// Open the input for noting an interruption to a work session
+    fn start_note(&mut self) {
+        if self.sequence.current().kind == SessionKind::Work {
+            self.note_input = Some(LineInput::new());
+        }
+    }
+
+    /// Note what interrupted the work session, to keep with it in the history
+    fn add_note(&mut self, text: String) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        info!(note = text, "interruption noted");
+        self.notes.push(Note { at: Local::now().trunc_subsecs(0), text: text.to_string() });
+    }
+
+    /// Apply a key to the note being typed
+    fn on_note_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.note_input.as_mut() else {
+            return;
+        };
+        match input.on_key(&key) {
+            Edit::Submit(text) => {
+                self.note_input = None;
+                self.add_note(text);
+            }
+            Edit::Cancel => self.note_input = None,
+            Edit::Editing => {}
+        }
+    }
+
+    /// Resume a session that idle detection paused, noting the time as idle
+    fn resume_from_idle(&mut self, now: Instant) {
+        self.idle_paused = None;
+        if let Some(paused) = self.resume_session(now) {
+            info!(idle_secs = paused.as_secs(), "resuming after idle");
+            if let Phase::Session { idle_for, .. } = &mut self.phase {
+                *idle_for += paused;
+            }
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent) {
+        let context = HookContext {
+            mode: "pomodoro",
+            label: Some(self.session_name()),
+            duration_secs: Some(self.sequence.current().duration.as_secs()),
+            cycle: Some(self.cycle),
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    /// Note what the work sessions are spent on in the history and focus log
+    pub fn with_task(mut self, task: String) -> Self {
+        self.task = Some(task);
+        self
+    }
+
+    /// Append a line to `path` for every completed work session
+    pub fn with_focus_log(mut self, path: PathBuf) -> Self {
+        self.focus_log = Some(path);
+        self
+    }
+
+    /// Add a finished session to the history file and focus log
+    fn record_session(&self, timer: &TimerState, idle_for: Duration, interruptions: u32, now: Instant) {
+        let kind = self.sequence.current().kind;
+        let focus_log = self.focus_log.as_deref().filter(|_| kind == SessionKind::Work);
+        if !self.record_history && focus_log.is_none() {
+            return;
+        }
+
+        let ended_at = Local::now();
+        let session = SessionRecord {
+            kind,
+            label: self.session_name(),
+            task: self.task.clone(),
+            started_at: (ended_at - (now - timer.start())).trunc_subsecs(0),
+            ended_at: ended_at.trunc_subsecs(0),
+            duration_secs: timer.elapsed(now).as_secs(),
+            idle_secs: idle_for.as_secs(),
+            extended_secs: timer.extended().as_secs(),
+            interruptions,
+            paused_secs: timer.paused_for(now).as_secs(),
+            notes: self.notes.clone(),
+        };
+
+        // Losing a history entry shouldn't interrupt the timer
+        if self.record_history {
+            if let Err(e) = history::record(&session) {
+                warn!(error = %e, "could not record session");
+            }
+        }
+        if let Some(path) = focus_log {
+            if let Err(e) = focus_log::append(path, &session) {
+                warn!(path = ?path, error = %e, "could not write focus log");
+            }
+        }
+    }
+
+    /// Name of the current phase, e.g. "Work Session #2"
+    fn session_name(&self) -> String {
+        format!("{} #{}", self.sequence.current().name, self.cycle)
+    }
+
+    /// Text tag identifying the session type when the theme asks for one
+    fn session_tag(&self) -> &'static str {
+        match self.sequence.current().kind {
+            _ if !self.theme.session_tags => "",
+            SessionKind::Work => "[WORK] ",
+            SessionKind::Break => "[BREAK] ",
+        }
+    }
+
+    /// Whether the running session is the last of the final cycle
+    fn last_session(&self) -> bool {
+        self.sequence.at_end() && self.cycles > 0 && self.cycle >= self.cycles
+    }
+
+    /// What comes after the running session and when, e.g. "Next: Break (5:00) at 14:35"
+    fn next_info(&self, remaining: Duration) -> String {
+        let ends_at = (Local::now() + remaining).format("%H:%M");
+        if self.last_session() {
+            return fill(&messages().last_session, &[("time", &ends_at.to_string())]);
+        }
+
+        let next = self.sequence.next();
+        fill(
+            &messages().next,
+            &[("session", &next.name), ("duration", &clock_time(next.duration.as_secs())), ("time", &ends_at.to_string())],
+        )
+    }
+
+    /// Move on from a finished session to the next screen
+    fn after_session_complete(&mut self, now: Instant) {
+        if self.sequence.at_end() {
+            let more_cycles = self.cycles == 0 || self.cycle < self.cycles;
+            if !more_cycles {
+                info!(cycles = self.cycles, "all cycles complete");
+                self.fire_hook(HookEvent::Complete);
+            }
+            self.cycle += 1;
+            if !more_cycles {
+                self.phase = Phase::AllDone;
+                return;
+            }
+        }
+        self.sequence.advance();
+        self.phase = Phase::Transition;
+        self.screen_since = now;
+    }
+
+    /// Time left before the screen between sessions carries on by itself
+    fn continuing_in(&self, now: Instant) -> Option<Duration> {
+        if !matches!(self.phase, Phase::SessionComplete { .. } | Phase::Transition) {
+            return None;
+        }
+        Some(self.auto_continue?.saturating_sub(now - self.screen_since))
+    }
+
+    /// Count down to carrying on in the corner of the screens between sessions
+    fn draw_continuing_in(&self, frame: &mut Screen, now: Instant) {
+        if let Some(left) = self.continuing_in(now) {
+            let text = fill(&messages().continuing_in, &[("time", &clock_time(left.as_secs_f64().ceil() as u64))]);
+            ui::draw_corner(frame, &text, self.theme.ui_text);
+        }
+    }
+
+    /// Announcement for the session about to start
+    fn transition_message(&self) -> &'static str {
+        match self.sequence.current().kind {
+            SessionKind::Work => &messages().back_to_work,
+            SessionKind::Break => &messages().break_time,
+        }
+    }
+
+    /// The run's total time at `now`, and the message to show it with
+    fn run_total(&self, now: Instant) -> Option<(&'static str, Duration)> {
+        let paused_since = match &self.phase {
+            Phase::Session { timer, .. } => timer.paused_since(now),
+            _ => None,
+        };
+        match self.total_time {
+            TotalTime::Off => None,
+            TotalTime::All => {
+                let paused = self.run_paused + paused_since.unwrap_or_default();
+                Some((&messages().run_total, (now - self.run_started).saturating_sub(paused)))
+            }
+            TotalTime::Work => Some((&messages().run_worked, self.worked_so_far(now))),
+        }
+    }
+
+    /// Time spent in work sessions this run, including the one under way
+    fn worked_so_far(&self, now: Instant) -> Duration {
+        match &self.phase {
+            Phase::Session { timer, .. } if self.sequence.current().kind == SessionKind::Work => {
+                self.worked + timer.elapsed(now)
+            }
+            _ => self.worked,
+        }
+    }
+
+    fn display_time(&self, remaining: Duration) -> String {
+        if let Some(format) = &self.time_format {
+            return format.format_remaining(remaining);
+        }
+        let remaining_secs = remaining.as_secs();
+        let minutes = remaining_secs / 60;
+        let seconds = remaining_secs % 60;
+
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+impl Mode for Pomodoro {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        // Only work sessions pause for idleness; walking away is the point of a break
+        let kind = self.sequence.current().kind;
+        if let (Phase::Session { timer, .. }, SessionKind::Work) = (&self.phase, kind) {
+            let running = !timer.is_paused() && !timer.is_done(now);
+            match self.idle.idle_since(now) {
+                Some(idle_since) if running => {
+                    info!(idle_secs = (now - idle_since).as_secs(), "pausing work session while idle");
+                    self.pause_session(idle_since);
+                    self.idle_paused = Some(now);
+                }
+                _ if self.idle_paused.is_some_and(|paused| self.idle.active_since(paused)) => {
+                    self.resume_from_idle(now)
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(warning), Phase::Session { timer, .. }) = (self.warning.as_mut(), &self.phase) {
+            let remaining = timer.remaining(now);
+            for triggered in warning.check(self.sequence.current().duration, remaining) {
+                info!(remaining_secs = remaining.as_secs(), "break ending soon");
+                if triggered.sound {
+                    self.sound.play(Sound::Alert);
+                }
+                self.warned_at = Some(now);
+            }
+        }
+
+        if let Phase::Session { timer, idle_for, interruptions } = self.phase {
+            if timer.is_done(now) {
+                info!(cycle = self.cycle, ?kind, "session complete");
+                // A note still being typed is kept with the session it was about
+                if let Some(input) = self.note_input.take() {
+                    self.add_note(input.into_text());
+                }
+                if self.sequence.current().sound {
+                    self.sound.play(match kind {
+                        _ if self.last_session() => Sound::Complete,
+                        SessionKind::Work => Sound::WorkEnd,
+                        SessionKind::Break => Sound::BreakEnd,
+                    });
+                }
+                self.record_session(&timer, idle_for, interruptions, now);
+                if kind == SessionKind::Work {
+                    let worked = timer.elapsed(now);
+                    self.focus_today += worked;
+                    self.worked += worked;
+                }
+                self.fire_hook(HookEvent::SessionEnd);
+                self.phase = Phase::SessionComplete { interruptions, paused_for: timer.paused_for(now) };
+                self.screen_since = now;
+            }
+        }
+
+        // Nobody pressed a key in time: go straight on to the next session
+        if self.continuing_in(now).is_some_and(|left| left.is_zero()) {
+            info!("continuing without a key press");
+            if let Phase::SessionComplete { .. } = self.phase {
+                self.after_session_complete(now);
+            }
+            if let Phase::Transition = self.phase {
+                self.start_session(now);
+            }
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if self.note_input.is_some() {
+            // Only Ctrl+C quits while typing, so notes can contain a q
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Flow::Quit;
+            }
+            self.idle.activity(now);
+            self.on_note_key(key);
+            return Flow::Continue;
+        }
+        if !is_quit_key(&key) {
+            self.idle.activity(now);
+        }
+
+        match self.phase {
+            Phase::Session { .. } if is_quit_key(&key) => return Flow::Quit,
+            // Any other key shows the user is back
+            Phase::Session { .. } if self.idle_paused.is_some() => self.resume_from_idle(now),
+            Phase::Session { .. } if key.code == KeyCode::Char('e') => self.extend_session(),
+            Phase::Session { .. } if key.code == KeyCode::Char('i') => self.start_note(),
+            Phase::Session { .. } => {}
+            // Any key acknowledges a completed session
+            Phase::SessionComplete { .. } => self.after_session_complete(now),
+            Phase::Transition if is_quit_key(&key) => return Flow::Quit,
+            Phase::Transition => self.start_session(now),
+            Phase::AllDone => return Flow::Quit,
+        }
+        Flow::Continue
+    }
+
+    fn typing(&self) -> bool {
+        self.note_input.is_some()
+    }
+
+    fn guards_quit(&self, _now: Instant) -> bool {
+        matches!(self.phase, Phase::Session { .. }) && self.sequence.current().kind == SessionKind::Work
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        let Phase::Session { timer, .. } = &self.phase else {
+            return Err("no session is running".to_string());
+        };
+        let paused = timer.is_paused();
+
+        // Commands show someone is there, and an explicit pause or resume
+        // takes over from idle detection
+        self.idle.activity(now);
+        if matches!(command, Command::Pause | Command::Resume | Command::Toggle) {
+            self.idle_paused = None;
+        }
+
+        match command {
+            Command::Pause | Command::Toggle if !paused => {
+                self.pause_session(now);
+            }
+            Command::Resume | Command::Toggle => {
+                self.resume_session(now);
+            }
+            Command::Pause => {}
+            Command::Add(secs) => {
+                if let Phase::Session { timer, .. } = &mut self.phase {
+                    timer.extend(Duration::from_secs(secs));
+                }
+            }
+            Command::Skip => {
+                if let Phase::Session { timer, .. } = &mut self.phase {
+                    self.run_paused += timer.paused_since(now).unwrap_or_default();
+                    timer.skip(now);
+                }
+            }
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        let (label, remaining, paused) = match &self.phase {
+            Phase::Session { timer, .. } => (self.session_name(), timer.remaining(now), timer.is_paused()),
+            Phase::SessionComplete { .. } | Phase::Transition => (self.session_name(), Duration::ZERO, false),
+            Phase::AllDone => ("All cycles completed".to_string(), Duration::ZERO, false),
+        };
+
+        let prompt = match &self.phase {
+            Phase::Session { .. } => None,
+            Phase::SessionComplete { .. } => Some(format!(
+                "{} {}.",
+                fill(&messages().session_complete, &[("session", &self.session_name())]),
+                messages().any_key_to_continue
+            )),
+            Phase::Transition => Some(format!(
+                "{} {}.",
+                self.transition_message(),
+                instructions(&self.instructions.pomodoro_next, &[])
+            )),
+            Phase::AllDone => Some(format!(
+                "{} {}.",
+                fill(&messages().all_cycles_complete, &[("cycles", &self.cycles.to_string())]),
+                messages().any_key_to_exit
+            )),
+        };
+
+        Status {
+            mode: "pomodoro",
+            label: Some(label),
+            time: self.display_time(remaining),
+            remaining: Some(remaining),
+            elapsed: None,
+            paused,
+            finished: matches!(self.phase, Phase::AllDone),
+            prompt,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        match &self.phase {
+            Phase::Session { timer, .. } => {
+                // Use the phase's own color, or else the one for its session type
+                let current = self.sequence.current();
+                let style = current.style.unwrap_or(match current.kind {
+                    SessionKind::Work => theme.pomodoro_work,
+                    SessionKind::Break => theme.pomodoro_break,
+                });
+
+                // Instructions and session info shown above the timer
+                let instructions = match current.kind {
+                    SessionKind::Work => instructions(
+                        &self.instructions.pomodoro_work,
+                        &[("extend_by", &clock_time(self.extend_by.as_secs()))],
+                    ),
+                    SessionKind::Break => instructions(&self.instructions.pomodoro_break, &[]),
+                };
+                let session = format!("{}{}", self.session_tag(), self.session_name());
+                let mut current_line = fill(&messages().current, &[("session", &session)]);
+                if !timer.extended().is_zero() {
+                    current_line.push_str(&format!(" (+{})", clock_time(timer.extended().as_secs())));
+                }
+                let remaining = timer.remaining(now);
+                let mut header = match &self.note_input {
+                    Some(_) => vec![Line::raw(messages().note_prompt.as_str())],
+                    None => instruction_lines(instructions),
+                };
+                let mut badges = vec![Badge::Cycle(self.cycle, self.cycles)];
+                if timer.is_paused() {
+                    badges.push(Badge::Paused);
+                }
+                header.extend(ui::status_line(&badges, theme));
+                header.extend([Line::raw(current_line), Line::raw(self.next_info(remaining))]);
+                if self.idle_paused.is_some() {
+                    header.push(Line::raw(messages().idle_paused.as_str()));
+                }
+                if let Some(message) = self.warning.as_ref().and_then(|warning| warning.message(current.duration)) {
+                    header.push(Line::raw(message.to_string()));
+                }
+                if let Some(input) = &self.note_input {
+                    header.push(input.line("> ", theme.ui_text));
+                }
+                // Flash the digits for a few seconds when the break warning goes off
+                let pulse = self.warned_at.map(|warned_at| now - warned_at).filter(|since| *since < WARNING_PULSE);
+                let style = match pulse {
+                    Some(since) if (since.as_millis() / 500).is_multiple_of(2) => style.add_modifier(Modifier::REVERSED),
+                    _ => style,
+                };
+
+                let display_time = self.display_time(remaining);
+                let digits = blink(self.digits, self.blink_separator, now - timer.start());
+                ui::draw_timer(frame, &header, theme.ui_text, &display_time, digits, &self.digit_state, style);
+                let focused = fill(&messages().focused_today, &[("time", &hours_minutes(self.focus_today))]);
+                ui::draw_corner(frame, &focused, theme.ui_text);
+                if let Some((message, total)) = self.run_total(now) {
+                    let total = fill(message, &[("time", &clock_time(total.as_secs()))]);
+                    ui::draw_left_corner(frame, &total, theme.ui_text);
+                }
+                progress::draw(frame, self.progress, timer.done(now), &header, style, self.ascii_only);
+            }
+            Phase::SessionComplete { interruptions, paused_for } => {
+                let mut lines = vec![fill(&messages().session_complete, &[("session", &self.session_name())])];
+                if self.sequence.current().kind == SessionKind::Work {
+                    lines.push(match interruptions {
+                        0 => messages().no_interruptions.clone(),
+                        _ => fill(
+                            &messages().session_interruptions,
+                            &[("count", &interruptions.to_string()), ("time", &clock_time(paused_for.as_secs()))],
+                        ),
+                    });
+                }
+                ui::draw_banner(frame, &messages().any_key_to_continue, theme.ui_text, &lines, theme.times_up);
+                self.draw_continuing_in(frame, now);
+            }
+            Phase::Transition => {
+                let mut lines = vec![self.transition_message()];
+                lines.extend(self.sequence.current().message.as_deref());
+                ui::draw_banner(
+                    frame,
+                    &instructions(&self.instructions.pomodoro_next, &[]),
+                    theme.ui_text,
+                    &lines,
+                    theme.times_up,
+                );
+                self.draw_continuing_in(frame, now);
+            }
+            Phase::AllDone => {
+                let message = fill(&messages().all_cycles_complete, &[("cycles", &self.cycles.to_string())]);
+                ui::draw_message(frame, &messages().any_key_to_exit, theme.ui_text, &message, theme.times_up);
+            }
+        }
+    }
+
+    fn exit_message(&self) -> String {
+        format!("Pomodoro timer ended. Completed {} full cycles.", self.cycle - 1)
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        Summary {
+            focused: Some(self.worked_so_far(now)),
+            cycles: Some(self.cycle - 1),
+            interruptions: Some(self.pauses),
+            ..Summary::default()
+        }
+    }
+}
+
+/// Work time already saved in the history for today
+fn focus_so_far_today() -> Duration {
+    let today = Local::now().date_naive();
+    match history::load(Some(today)) {
+        Ok(sessions) => {
+            let secs = sessions
+                .iter()
+                .filter(|session| session.kind == SessionKind::Work && session.started_at.date_naive() == today)
+                .map(|session| session.duration_secs)
+                .sum();
+            Duration::from_secs(secs)
+        }
+        Err(e) => {
+            warn!(error = %e, "could not read today's sessions from the history");
+            Duration::ZERO
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// src/modes/sequence.rs
+//! The phases a Pomodoro run steps through, built from `pomodoro.sequence`
+//! or the classic alternation of work and break
+
+use crate::{config::Config, history::SessionKind, locale::messages};
+use ratatui::style::{Color, Style};
+use std::time::Duration;
+use tracing::warn;
+
+/// One phase of a Pomodoro cycle
+#[derive(Debug, Clone)]
+pub struct SessionPhase {
+    /// Name shown while the phase runs, e.g. "Work Session"
+    pub name: String,
+    pub kind: SessionKind,
+    pub duration: Duration,
+    /// Digit style instead of the theme's work or break style
+    pub style: Option<Style>,
+    /// Ring the bell when the phase ends
+    pub sound: bool,
+    /// Shown under the announcement when the phase is about to start
+    pub message: Option<String>,
+}
+
+impl SessionPhase {
+    fn built_in(kind: SessionKind, duration: Duration, sound: bool) -> Self {
+        let name = match kind {
+            SessionKind::Work => &messages().work_session,
+            SessionKind::Break => &messages().break_session,
+        };
+        SessionPhase { name: name.clone(), kind, duration, style: None, sound, message: None }
+    }
+}
+
+/// Names accepted by `--technique`
+pub const TECHNIQUES: [&str; 3] = ["classic", "52-17", "ultradian"];
+
+/// Steps through the phases of each cycle in order
+pub struct Sequence {
+    phases: Vec<SessionPhase>,
+    step: usize,
+}
+
+impl Sequence {
+    pub fn new(phases: Vec<SessionPhase>) -> Self {
+        Sequence { phases, step: 0 }
+    }
+
+    /// Build the configured sequence, with `work` and `break` lasting `work_time`
+    /// and `break_time` unless `phases` defines them
+    pub fn from_config(config: &Config, work_time: Duration, break_time: Duration) -> Self {
+        let settings = &config.pomodoro;
+        let phases: Vec<SessionPhase> = settings
+            .sequence
+            .iter()
+            .filter_map(|name| {
+                if let Some(phase) = settings.phases.get(name) {
+                    return Some(SessionPhase {
+                        name: display_name(name),
+                        kind: phase.kind,
+                        duration: Duration::from_secs(phase.duration * 60),
+                        style: phase.color.as_deref().map(|color| Style::default().fg(config.parse_color(color).into())),
+                        sound: phase.sound.unwrap_or(settings.sound_enabled),
+                        message: None,
+                    });
+                }
+                match name.as_str() {
+                    "work" => Some(SessionPhase::built_in(SessionKind::Work, work_time, settings.sound_enabled)),
+                    "break" => Some(SessionPhase::built_in(SessionKind::Break, break_time, settings.sound_enabled)),
+                    _ => {
+                        warn!(phase = name, "unknown pomodoro phase");
+                        eprintln!("Unknown Pomodoro phase: {}. Skipping it.", name);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if phases.is_empty() {
+            return Sequence::new(vec![
+                SessionPhase::built_in(SessionKind::Work, work_time, settings.sound_enabled),
+                SessionPhase::built_in(SessionKind::Break, break_time, settings.sound_enabled),
+            ]);
+        }
+        Sequence::new(phases)
+    }
+
+    /// The built-in sequence for a named technique, with its own colors
+    /// unless a built-in theme picks them
+    pub fn technique(name: &str, config: &Config) -> Option<Self> {
+        let sound = config.pomodoro.sound_enabled;
+        let own_colors = config.theme.eq_ignore_ascii_case("default");
+        let phase = |name: &str, kind, minutes: u64, color, message: &str| SessionPhase {
+            name: name.to_string(),
+            kind,
+            duration: Duration::from_secs(minutes * 60),
+            style: own_colors.then(|| Style::default().fg(color)),
+            sound,
+            message: Some(message.to_string()),
+        };
+
+        let phases = match name.to_lowercase().as_str() {
+            // Four pomodoros, then a longer break
+            "classic" => {
+                let messages = messages();
+                let work = phase(&messages.work_session, SessionKind::Work, 25, Color::Red, "Pick up where you left off.");
+                let short =
+                    phase(&messages.break_session, SessionKind::Break, 5, Color::Green, "Stand up and stretch for 5 minutes.");
+                let long = phase(
+                    "Long Break",
+                    SessionKind::Break,
+                    15,
+                    Color::Blue,
+                    "Four down: take 15 minutes well away from the desk.",
+                );
+                vec![work.clone(), short.clone(), work.clone(), short.clone(), work.clone(), short, work, long]
+            }
+            // The work rhythm of the most productive people in the DeskTime study
+            "52-17" => vec![
+                phase("Focus", SessionKind::Work, 52, Color::LightBlue, "52 minutes of focus. One thing at a time."),
+                phase("Break", SessionKind::Break, 17, Color::LightGreen, "Take 17 minutes away from the screen."),
+            ],
+            // Work in step with the body's roughly 90-minute ultradian rhythm
+            "ultradian" => vec![
+                phase("Deep Work", SessionKind::Work, 90, Color::Magenta, "90 minutes of deep work. Silence your phone."),
+                phase("Recovery", SessionKind::Break, 20, Color::Cyan, "Rest for 20 minutes: walk, nap, or look out a window."),
+            ],
+            _ => return None,
+        };
+        Some(Sequence::new(phases))
+    }
+
+    pub fn current(&self) -> &SessionPhase {
+        &self.phases[self.step]
+    }
+
+    /// The phase after the current one
+    pub fn next(&self) -> &SessionPhase {
+        &self.phases[(self.step + 1) % self.phases.len()]
+    }
+
+    /// The phases after the current one, up to the end of the cycle
+    pub fn upcoming(&self) -> &[SessionPhase] {
+        &self.phases[self.step + 1..]
+    }
+
+    /// Position of the current phase in the cycle, counting from 1, and the number of phases
+    pub fn position(&self) -> (usize, usize) {
+        (self.step + 1, self.phases.len())
+    }
+
+    /// Whether the current phase is the last of the cycle
+    pub fn at_end(&self) -> bool {
+        self.step + 1 == self.phases.len()
+    }
+
+    /// Move on to the next phase, starting over after the last one
+    pub fn advance(&mut self) {
+        self.step = (self.step + 1) % self.phases.len();
+    }
+}
+
+/// Turn a phase name like "deep_work" into "Deep work"
+fn display_name(name: &str) -> String {
+    let name = name.replace(['_', '-'], " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
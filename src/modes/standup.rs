@@ -0,0 +1,265 @@
+// src/modes/standup.rs
+//! Standup mode: a countdown for each speaker in turn, moving on when their
+//! time runs out or a key is pressed
+
+use super::{
+    blink, clock_time, instruction_lines, instructions, is_quit_key,
+    sequence::{Sequence, SessionPhase},
+    Flow, Mode, Status, TOO_MUCH_TIME,
+};
+use crate::{
+    caps,
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    history::SessionKind,
+    hooks::{HookContext, HookEvent, Hooks},
+    locale::{fill, messages},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    time_format::TimeFormat,
+    ui::{self, Badge, DigitState, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{text::Line};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+pub struct Standup {
+    theme: Theme,
+    instructions: InstructionSettings,
+    blink_separator: bool,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// Format for the time instead of the usual one
+    time_format: Option<TimeFormat>,
+    refresh_rate: Duration,
+    /// Draw the name card with plain ASCII characters
+    ascii_only: bool,
+    sound: SoundPlayer,
+    hooks: Hooks,
+    /// The speakers in order, each with their time
+    speakers: Sequence,
+    start_time: Instant,
+    end_time: Instant,
+    paused_at: Option<Instant>,
+    /// Whether everyone has had their turn
+    done: bool,
+}
+
+impl Standup {
+    pub fn new(config: &Config, names: &[String], per_speaker: Duration, sound: SoundPlayer) -> Self {
+        let speakers = names
+            .iter()
+            .map(|name| SessionPhase {
+                name: name.clone(),
+                kind: SessionKind::Work,
+                duration: per_speaker,
+                style: None,
+                sound: true,
+                message: None,
+            })
+            .collect();
+
+        let now = clock::now();
+        let standup = Standup {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            blink_separator: config.blink_separator,
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            time_format: TimeFormat::from_setting(&config.time_format.standup, "standup"),
+            refresh_rate: Duration::from_millis(config.countdown_refresh_rate),
+            ascii_only: config.ascii_only || !caps::supports_unicode(),
+            sound,
+            hooks: Hooks::new(&config.hooks),
+            speakers: Sequence::new(speakers),
+            start_time: now,
+            end_time: now + per_speaker,
+            paused_at: None,
+            done: false,
+        };
+        standup.fire_hook(HookEvent::WorkStart);
+        standup
+    }
+
+    /// Hand over to the next speaker, or finish after the last one
+    fn next_speaker(&mut self, now: Instant) {
+        self.fire_hook(HookEvent::SessionEnd);
+        if self.speakers.at_end() {
+            info!("standup complete");
+            self.done = true;
+            self.fire_hook(HookEvent::Complete);
+            return;
+        }
+
+        self.speakers.advance();
+        info!(speaker = self.speakers.current().name, "next speaker");
+        self.start_time = now;
+        self.end_time = now + self.speakers.current().duration;
+        self.paused_at = None;
+        self.fire_hook(HookEvent::WorkStart);
+    }
+
+    fn remaining(&self, now: Instant) -> Duration {
+        self.end_time.saturating_duration_since(self.paused_at.unwrap_or(now))
+    }
+
+    fn display_time(&self, now: Instant) -> String {
+        let remaining = self.remaining(now);
+        if let Some(format) = &self.time_format {
+            return format.format_remaining(remaining);
+        }
+        // Round up so 0:00 only shows once the time is up
+        clock_time(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now);
+            self.fire_hook(HookEvent::Pause);
+        }
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.end_time += now - paused_at;
+            self.fire_hook(HookEvent::Resume);
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent) {
+        let (position, _) = self.speakers.position();
+        let context = HookContext {
+            mode: "standup",
+            label: Some(self.speakers.current().name.clone()),
+            duration_secs: Some(self.speakers.current().duration.as_secs()),
+            cycle: Some(position as u64),
+        };
+        self.hooks.fire(event, &context);
+    }
+
+    /// The speakers still to come, e.g. "Up next: bob, carol"
+    fn queue(&self) -> String {
+        let upcoming: Vec<&str> = self.speakers.upcoming().iter().map(|speaker| speaker.name.as_str()).collect();
+        if upcoming.is_empty() {
+            "Last speaker".to_string()
+        } else {
+            format!("Up next: {}", upcoming.join(", "))
+        }
+    }
+}
+
+impl Mode for Standup {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if !self.done && self.paused_at.is_none() && now >= self.end_time {
+            info!(speaker = self.speakers.current().name, "speaker out of time");
+            self.sound.play(Sound::Complete);
+            self.next_speaker(now);
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        // Once everyone has spoken, any key exits
+        if is_quit_key(&key) || self.done {
+            return Flow::Quit;
+        }
+        if matches!(key.code, KeyCode::Char(' ') | KeyCode::Char('n') | KeyCode::Enter) {
+            self.next_speaker(now);
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        if self.done {
+            return Err("standup has already finished".to_string());
+        }
+
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.paused_at.is_some() => self.resume(now),
+            Command::Toggle => self.pause(now),
+            Command::Add(secs) => {
+                self.end_time =
+                    self.end_time.checked_add(Duration::from_secs(secs)).ok_or_else(|| TOO_MUCH_TIME.to_string())?;
+            }
+            Command::Skip => self.next_speaker(now),
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        Status {
+            mode: "standup",
+            label: Some(self.speakers.current().name.clone()),
+            time: self.display_time(now),
+            remaining: Some(self.remaining(now)),
+            elapsed: None,
+            paused: self.paused_at.is_some(),
+            finished: self.done,
+            prompt: self.done.then(|| "Standup complete! Press any key to exit.".to_string()),
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let theme = &self.theme;
+        if self.done {
+            let messages = messages();
+            ui::draw_message(frame, &messages.any_key_to_exit, theme.ui_text, &messages.standup_complete, theme.times_up);
+            return;
+        }
+
+        let (position, speakers) = self.speakers.position();
+        let mut header = instruction_lines(instructions(&self.instructions.standup, &[]));
+        header.push(Line::raw(fill(
+            &messages().speaker,
+            &[("speaker", &position.to_string()), ("speakers", &speakers.to_string())],
+        )));
+        if self.paused_at.is_some() {
+            header.extend(ui::status_line(&[Badge::Paused], theme));
+        }
+
+        let name = &self.speakers.current().name;
+        let name = ui::large_text(name, self.digits, frame.area().width)
+            .unwrap_or_else(|| ui::framed(name, self.ascii_only));
+        let display_time = self.display_time(now);
+        let digits = blink(self.digits, self.blink_separator, now - self.start_time);
+        ui::draw_titled_timer(
+            frame,
+            &header,
+            theme.ui_text,
+            &name,
+            theme.times_up,
+            &display_time,
+            digits,
+            &self.digit_state,
+            theme.countdown,
+            Line::styled(self.queue(), theme.ui_text),
+        );
+    }
+
+    fn exit_message(&self) -> String {
+        if self.done {
+            "Standup complete!".to_string()
+        } else {
+            "Standup ended.".to_string()
+        }
+    }
+}
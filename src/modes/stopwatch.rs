@@ -0,0 +1,619 @@
+// src/modes/stopwatch.rs
+//! Stopwatch mode
+
+use super::{blink, clock_time, instruction_lines, instructions, is_quit_key, Chime, Flow, Mode, Status, Summary};
+use crate::{
+    clock,
+    config::{Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    hooks::{HookContext, HookEvent, Hooks},
+    idle::IdleMonitor,
+    locale::{fill, messages},
+    sound::{Sound, SoundPlayer},
+    theme::Theme,
+    time_format::TimeFormat,
+    ui::{self, Badge, DigitState, Screen},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Color, Modifier},
+    text::{Line, Span},
+};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const HOUR_SECS: u64 = 60 * 60;
+const DAY_SECS: u64 = 24 * HOUR_SECS;
+
+/// How long before a rollover the display makes room for the wider time
+const ROLLOVER_LEAD: Duration = Duration::from_secs(60);
+
+/// Slowest redraw with whole seconds, so pausing still shows promptly
+const SECONDS_REFRESH: Duration = Duration::from_millis(250);
+
+/// Redraw interval with milliseconds, about 60 frames a second
+const MILLIS_REFRESH: Duration = Duration::from_millis(16);
+
+/// The smallest unit the stopwatch shows (`stopwatch_precision`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    Seconds,
+    /// Hundredths of a second
+    #[default]
+    Centis,
+    /// Thousandths of a second
+    Millis,
+}
+
+impl Precision {
+    /// Names accepted for `stopwatch_precision` in the configuration
+    pub const NAMES: [&'static str; 3] = ["seconds", "centis", "millis"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "seconds" => Some(Precision::Seconds),
+            "centis" => Some(Precision::Centis),
+            "millis" => Some(Precision::Millis),
+            _ => None,
+        }
+    }
+
+    /// The configured precision. A stopwatch can be started with the screen
+    /// already up, so an unknown one is only logged (and reported by
+    /// `clockit config validate`).
+    pub fn from_config(config: &Config) -> Self {
+        Precision::from_name(&config.stopwatch_precision).unwrap_or_else(|| {
+            warn!(precision = config.stopwatch_precision, "unknown stopwatch precision, using centis");
+            Precision::default()
+        })
+    }
+
+    /// How often to redraw: the configured rate, but no faster than whole
+    /// seconds need and fast enough for milliseconds to visibly run
+    fn refresh_rate(self, configured: Duration) -> Duration {
+        match self {
+            Precision::Seconds => configured.max(SECONDS_REFRESH),
+            Precision::Centis => configured,
+            Precision::Millis => configured.min(MILLIS_REFRESH),
+        }
+    }
+
+    /// The part of `elapsed` below a second, with its dot
+    fn fraction(self, elapsed: Duration) -> String {
+        match self {
+            Precision::Seconds => String::new(),
+            Precision::Centis => format!(".{:02}", elapsed.subsec_millis() / 10),
+            Precision::Millis => format!(".{:03}", elapsed.subsec_millis()),
+        }
+    }
+
+    /// A lap time or difference between laps: M:SS, or H:MM:SS once it
+    /// passes an hour, with the fraction
+    fn lap_time(self, time: Duration) -> String {
+        let secs = time.as_secs();
+        if secs >= HOUR_SECS {
+            format!("{}:{:02}:{:02}{}", secs / HOUR_SECS, secs / 60 % 60, secs % 60, self.fraction(time))
+        } else {
+            format!("{}:{:02}{}", secs / 60, secs % 60, self.fraction(time))
+        }
+    }
+}
+
+/// Laps marked with l, and the elapsed time the current one started at
+#[derive(Default)]
+struct Laps {
+    times: Vec<Duration>,
+    current_start: Duration,
+}
+
+impl Laps {
+    /// Finish the current lap at `elapsed`, unless no time has passed in it
+    fn mark(&mut self, elapsed: Duration) {
+        if elapsed > self.current_start {
+            self.times.push(elapsed - self.current_start);
+            self.current_start = elapsed;
+        }
+    }
+
+    /// The last lap and how much slower (positive) or faster than the one
+    /// before it it was
+    fn last(&self) -> Option<(Duration, Option<(bool, Duration)>)> {
+        let (&last, earlier) = self.times.split_last()?;
+        let delta = earlier.last().map(|&previous| {
+            if last >= previous {
+                (true, last - previous)
+            } else {
+                (false, previous - last)
+            }
+        });
+        Some((last, delta))
+    }
+
+    /// The fastest and slowest laps, numbered from 1
+    fn extremes(&self) -> Option<((usize, Duration), (usize, Duration))> {
+        let numbered = || self.times.iter().copied().enumerate().map(|(i, time)| (i + 1, time));
+        // The first of several equal laps counts as the best and the worst
+        let best = numbered().min_by_key(|&(_, time)| time)?;
+        let worst = numbered().rev().max_by_key(|&(_, time)| time)?;
+        Some((best, worst))
+    }
+
+    fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.times.len()).ok().filter(|&count| count > 0)?;
+        Some(self.times.iter().sum::<Duration>() / count)
+    }
+}
+
+/// A target time for the stopwatch to reach
+struct Goal {
+    target: Duration,
+    /// Stop the stopwatch once the target is reached
+    auto_stop: bool,
+    reached: bool,
+    sound: SoundPlayer,
+}
+
+/// A count down to the start of the stopwatch, with a beep on each second
+struct Warmup {
+    ends_at: Instant,
+    /// The count when the last beep played
+    beeped: u64,
+    sound: SoundPlayer,
+}
+
+pub struct Stopwatch {
+    theme: Theme,
+    instructions: InstructionSettings,
+    blink_separator: bool,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// Format for the time instead of the usual one
+    time_format: Option<TimeFormat>,
+    precision: Precision,
+    refresh_rate: Duration,
+    /// Time accumulated before the current run started
+    banked: Duration,
+    /// Time carried over from an earlier run with `--persist`
+    resumed: Duration,
+    /// When the stopwatch was last started, or None while paused
+    running_since: Option<Instant>,
+    goal: Option<Goal>,
+    chime: Option<Chime>,
+    /// The 3-2-1 count before the stopwatch starts (`--warmup`)
+    warmup: Option<Warmup>,
+    hooks: Hooks,
+    idle: IdleMonitor,
+    /// When the stopwatch was paused automatically because the user went idle
+    idle_paused: Option<Instant>,
+    /// What the time is being spent on, shown above it
+    label: Option<String>,
+    laps: Laps,
+    /// Times the stopwatch was paused, by hand or while idle
+    pauses: u32,
+}
+
+impl Stopwatch {
+    pub fn new(config: &Config) -> Self {
+        let precision = Precision::from_config(config);
+        Stopwatch {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            blink_separator: config.blink_separator,
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            time_format: TimeFormat::from_setting(&config.time_format.stopwatch, "stopwatch"),
+            precision,
+            refresh_rate: precision.refresh_rate(Duration::from_millis(config.stopwatch_refresh_rate)),
+            banked: Duration::ZERO,
+            resumed: Duration::ZERO,
+            running_since: Some(clock::now()),
+            goal: None,
+            chime: None,
+            warmup: None,
+            hooks: Hooks::new(&config.hooks),
+            idle: IdleMonitor::new(&config.idle),
+            idle_paused: None,
+            label: None,
+            laps: Laps::default(),
+            pauses: 0,
+        }
+    }
+
+    /// Continue counting from time saved by an earlier run
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.banked = elapsed;
+        self.resumed = elapsed;
+        self.laps.current_start = elapsed;
+        self
+    }
+
+    /// Show what the time is being spent on, such as a tracked project
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Ring and pulse the digits every `interval` of elapsed time
+    pub fn with_chime(mut self, interval: Duration, sound: SoundPlayer) -> Self {
+        self.chime = Some(Chime::new(interval, sound));
+        self
+    }
+
+    /// Show a target under the elapsed time and alert when it is reached
+    pub fn with_goal(mut self, target: Duration, auto_stop: bool, sound: SoundPlayer) -> Self {
+        // A resumed stopwatch may already be past its goal, which needs no alert
+        let reached = self.banked >= target;
+        if reached && auto_stop {
+            self.running_since = None;
+        }
+        self.goal = Some(Goal { target, auto_stop, reached, sound });
+        self
+    }
+
+    /// Count down `secs` seconds in big digits, beeping on each, before the
+    /// stopwatch starts
+    pub fn with_warmup(mut self, secs: u64, sound: SoundPlayer) -> Self {
+        // A stopwatch resumed past its goal has already stopped for good
+        if secs > 0 && !self.stopped_at_goal() {
+            self.running_since = None;
+            // One above the count, so the first tick beeps for it
+            self.warmup = Some(Warmup { ends_at: clock::now() + Duration::from_secs(secs), beeped: secs + 1, sound });
+        }
+        self
+    }
+
+    /// The whole seconds still to count before the stopwatch starts
+    fn warmup_count(&self, now: Instant) -> Option<u64> {
+        let left = self.warmup.as_ref()?.ends_at.saturating_duration_since(now);
+        Some(left.as_secs() + u64::from(left.subsec_nanos() > 0))
+    }
+
+    /// Beep as the warm-up counts down, and start the stopwatch once it's over
+    fn warm_up(&mut self, now: Instant) {
+        let Some(count) = self.warmup_count(now) else {
+            return;
+        };
+        let Some(warmup) = self.warmup.as_mut() else {
+            return;
+        };
+        if count > 0 {
+            if count < warmup.beeped {
+                warmup.beeped = count;
+                warmup.sound.play(Sound::Beat);
+            }
+            return;
+        }
+        info!("warm-up over, starting stopwatch");
+        warmup.sound.play(Sound::Complete);
+        // Start on the dot rather than wherever the tick landed
+        self.running_since = Some(warmup.ends_at);
+        self.warmup = None;
+    }
+
+    fn goal_reached(&self) -> bool {
+        self.goal.as_ref().is_some_and(|goal| goal.reached)
+    }
+
+    /// Whether the stopwatch was stopped for good by reaching its goal
+    fn stopped_at_goal(&self) -> bool {
+        self.goal.as_ref().is_some_and(|goal| goal.reached && goal.auto_stop)
+    }
+
+    fn goal_caption(&self, now: Instant) -> Option<String> {
+        let goal = self.goal.as_ref()?;
+        let target = clock_time(goal.target.as_secs());
+        if goal.reached {
+            return Some(fill(&messages().goal_reached, &[("time", &target)]));
+        }
+
+        let to_go = goal.target.saturating_sub(self.elapsed(now));
+        let to_go_secs = to_go.as_secs() + u64::from(to_go.subsec_nanos() > 0);
+        Some(fill(&messages().goal, &[("time", &target), ("left", &clock_time(to_go_secs))]))
+    }
+
+    /// Total time counted, including any resumed from an earlier run
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        match self.running_since {
+            Some(since) => self.banked + (now - since),
+            None => self.banked,
+        }
+    }
+
+    /// M:SS.cc, then H:MM:SS.cc after an hour and Dd HH:MM:SS after a day,
+    /// unless a time format is set. The fraction follows the precision.
+    fn display_time(&self, now: Instant) -> String {
+        let elapsed = self.elapsed(now);
+        if let Some(format) = &self.time_format {
+            return format.format(elapsed);
+        }
+        let secs = elapsed.as_secs();
+        let fraction = self.precision.fraction(elapsed);
+
+        // Size each field for where the time will be shortly, so the digits
+        // don't jump sideways at the moment a new field appears
+        let ahead = (elapsed + ROLLOVER_LEAD).as_secs();
+        let width = |value: u64| value.to_string().len();
+
+        if ahead >= DAY_SECS {
+            format!(
+                "{:0w$}d {:02}:{:02}:{:02}",
+                secs / DAY_SECS,
+                secs / HOUR_SECS % 24,
+                secs / 60 % 60,
+                secs % 60,
+                w = width(ahead / DAY_SECS)
+            )
+        } else if ahead >= HOUR_SECS {
+            format!(
+                "{:0w$}:{:02}:{:02}{}",
+                secs / HOUR_SECS,
+                secs / 60 % 60,
+                secs % 60,
+                fraction,
+                w = width(ahead / HOUR_SECS)
+            )
+        } else {
+            format!("{:0w$}:{:02}{}", secs / 60, secs % 60, fraction, w = width(ahead / 60))
+        }
+    }
+
+    /// The lap statistics shown under the time once there are laps: the last
+    /// lap against the one before, then the best, worst and average
+    fn lap_panel(&self) -> Vec<Line<'static>> {
+        let (Some((last, delta)), Some(((best_lap, best), (worst_lap, worst))), Some(average)) =
+            (self.laps.last(), self.laps.extremes(), self.laps.average())
+        else {
+            return Vec::new();
+        };
+        let text = self.theme.ui_text;
+        let faster = text.fg(Color::Green);
+        let slower = text.fg(Color::Red);
+        let time = |time: Duration| self.precision.lap_time(time);
+
+        let messages = messages();
+        let lap = self.laps.times.len().to_string();
+        let mut last_line = vec![Span::styled(fill(&messages.lap, &[("lap", &lap), ("time", &time(last))]), text)];
+        if let Some((slower_than_previous, difference)) = delta {
+            let (sign, style) = if slower_than_previous { ('+', slower) } else { ('-', faster) };
+            last_line.push(Span::styled(format!("  {}{}", sign, time(difference)), style));
+        }
+
+        let stats_line = vec![
+            Span::styled(fill(&messages.best_lap, &[("time", &time(best)), ("lap", &best_lap.to_string())]), faster),
+            Span::styled("   ", text),
+            Span::styled(fill(&messages.worst_lap, &[("time", &time(worst)), ("lap", &worst_lap.to_string())]), slower),
+            Span::styled(format!("   {}", fill(&messages.average_lap, &[("time", &time(average))])), text),
+        ];
+        vec![Line::from(last_line), Line::from(stats_line)]
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.banked += now - since;
+            self.pauses += 1;
+            self.fire_hook(HookEvent::Pause);
+        }
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if self.running_since.is_none() {
+            self.running_since = Some(now);
+            self.fire_hook(HookEvent::Resume);
+        }
+    }
+
+    fn fire_hook(&self, event: HookEvent) {
+        let context = HookContext {
+            mode: "stopwatch",
+            duration_secs: self.goal.as_ref().map(|goal| goal.target.as_secs()),
+            ..Default::default()
+        };
+        self.hooks.fire(event, &context);
+    }
+}
+
+impl Mode for Stopwatch {
+    fn refresh_rate(&self) -> Duration {
+        self.refresh_rate
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if self.warmup.is_some() {
+            self.warm_up(now);
+            return Flow::Continue;
+        }
+        if let (Some(since), Some(idle_since)) = (self.running_since, self.idle.idle_since(now)) {
+            // Don't count the time spent away
+            info!(idle_secs = (now - idle_since).as_secs(), "pausing stopwatch while idle");
+            self.pause(idle_since.max(since));
+            self.idle_paused = Some(now);
+        } else if self.idle_paused.is_some_and(|paused| self.idle.active_since(paused)) {
+            self.idle_paused = None;
+            self.resume(now);
+        }
+
+        let elapsed = self.elapsed(now);
+        if let Some(chime) = self.chime.as_mut() {
+            chime.check(elapsed, now);
+        }
+
+        let Some(goal) = self.goal.as_mut() else {
+            return Flow::Continue;
+        };
+
+        if !goal.reached && elapsed >= goal.target {
+            info!(target_secs = goal.target.as_secs(), "stopwatch goal reached");
+            goal.reached = true;
+            goal.sound.play(Sound::GoalReached);
+            if goal.auto_stop {
+                // Stop exactly on the goal rather than wherever the tick landed
+                let target = goal.target;
+                self.running_since = None;
+                self.banked = target;
+            }
+            self.fire_hook(HookEvent::Complete);
+        }
+        Flow::Continue
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            return Flow::Quit;
+        }
+        if self.warmup.is_some() {
+            return Flow::Continue;
+        }
+
+        // Any other key shows the user is back
+        self.idle.activity(now);
+        if self.idle_paused.take().is_some() {
+            self.resume(now);
+        }
+        if key.code == KeyCode::Char('l') && !self.stopped_at_goal() {
+            self.laps.mark(self.elapsed(now));
+            info!(lap = self.laps.times.len(), "lap marked");
+        }
+        Flow::Continue
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        if self.stopped_at_goal() && command != Command::Status {
+            return Err("stopwatch has already reached its goal".to_string());
+        }
+        if self.warmup.is_some() && !matches!(command, Command::Status | Command::Quit) {
+            return Err("stopwatch is still warming up".to_string());
+        }
+        // Commands show someone is there, and an explicit pause or resume
+        // takes over from idle detection
+        self.idle.activity(now);
+        if matches!(command, Command::Pause | Command::Resume | Command::Toggle) {
+            self.idle_paused = None;
+        }
+
+        match command {
+            Command::Pause => self.pause(now),
+            Command::Resume => self.resume(now),
+            Command::Toggle if self.running_since.is_some() => self.pause(now),
+            Command::Toggle => self.resume(now),
+            Command::Add(_) | Command::Skip => {
+                return Err(format!("{} is not supported by the stopwatch", command));
+            }
+            Command::Status | Command::Quit => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        if let Some(count) = self.warmup_count(now) {
+            return Status {
+                mode: "stopwatch",
+                label: Some("Warm-up".to_string()),
+                time: count.to_string(),
+                remaining: None,
+                elapsed: Some(self.elapsed(now)),
+                paused: false,
+                finished: false,
+                prompt: None,
+            };
+        }
+        Status {
+            mode: "stopwatch",
+            label: self.goal_reached().then(|| "Goal reached".to_string()).or_else(|| self.label.clone()),
+            time: self.display_time(now),
+            remaining: None,
+            elapsed: Some(self.elapsed(now)),
+            paused: self.running_since.is_none() && !self.stopped_at_goal(),
+            finished: self.stopped_at_goal(),
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        let mut header = instruction_lines(instructions(&self.instructions.stopwatch, &[]));
+        if let Some(label) = &self.label {
+            header.push(Line::raw(label.clone()));
+        }
+        if let Some(count) = self.warmup_count(now) {
+            header.push(Line::raw(messages().get_ready.as_str()));
+            ui::draw_timer(
+                frame,
+                &header,
+                self.theme.ui_text,
+                &count.to_string(),
+                self.digits,
+                &self.digit_state,
+                self.theme.countdown,
+            );
+            return;
+        }
+        if self.stopped_at_goal() {
+            header.push(Line::raw(messages().stopped_at_goal.as_str()));
+        } else {
+            let mut badges = Vec::new();
+            if self.running_since.is_none() {
+                badges.push(Badge::Paused);
+            }
+            if self.goal_reached() {
+                badges.push(Badge::Overtime);
+            }
+            header.extend(ui::status_line(&badges, &self.theme));
+            if self.idle_paused.is_some() {
+                header.push(Line::raw(messages().idle_paused.as_str()));
+            }
+        }
+
+        let display_time = self.display_time(now);
+        let digits = blink(self.digits, self.blink_separator, self.elapsed(now));
+        // Switch to the alert color once the goal is reached
+        let time_style = if self.goal_reached() { self.theme.times_up } else { self.theme.stopwatch };
+        let digits_style = if self.chime.as_ref().is_some_and(|chime| chime.pulsing(now)) {
+            time_style.add_modifier(Modifier::REVERSED)
+        } else {
+            time_style
+        };
+
+        let mut panel = Vec::new();
+        if let Some(caption) = self.goal_caption(now) {
+            panel.push(Line::styled(caption, time_style));
+        }
+        panel.extend(self.lap_panel());
+
+        if panel.is_empty() {
+            ui::draw_timer(frame, &header, self.theme.ui_text, &display_time, digits, &self.digit_state, digits_style);
+        } else {
+            ui::draw_timer_with_panel(
+                frame,
+                &header,
+                self.theme.ui_text,
+                &display_time,
+                digits,
+                &self.digit_state,
+                digits_style,
+                panel,
+            );
+        }
+    }
+
+    fn exit_message(&self) -> String {
+        "Stopwatch stopped!".to_string()
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        Summary {
+            focused: Some(self.elapsed(now).saturating_sub(self.resumed)),
+            laps: Some(self.laps.times.len()),
+            interruptions: Some(self.pauses),
+            ..Summary::default()
+        }
+    }
+}
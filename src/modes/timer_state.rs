@@ -0,0 +1,121 @@
+// src/modes/timer_state.rs
+//! Bookkeeping for a timer counting down a set length
+//!
+//! The time left is always worked out from the length the timer started
+//! with, the time added since and the time spent paused, rather than from an
+//! end time moved around by each pause, resume and extension, so any mix of
+//! them adds up to the same thing.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerState {
+    start: Instant,
+    /// Length the timer started with
+    duration: Duration,
+    /// Time added since it started
+    extended: Duration,
+    /// Time counted as already run when it started, as when resuming one
+    /// suspended earlier
+    ahead: Duration,
+    /// Time left over when it was skipped to the end
+    skipped: Duration,
+    /// Time spent in finished pauses
+    paused_for: Duration,
+    paused_at: Option<Instant>,
+}
+
+impl TimerState {
+    pub fn new(duration: Duration, start: Instant) -> Self {
+        TimerState {
+            start,
+            duration,
+            extended: Duration::ZERO,
+            ahead: Duration::ZERO,
+            skipped: Duration::ZERO,
+            paused_for: Duration::ZERO,
+            paused_at: None,
+        }
+    }
+
+    /// Start with only `remaining` of the length left
+    pub fn with_remaining(mut self, remaining: Duration) -> Self {
+        self.ahead = self.duration.saturating_sub(remaining);
+        self
+    }
+
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    /// Length of the timer, including any time added since it started
+    pub fn length(&self) -> Duration {
+        (self.duration + self.extended).saturating_sub(self.skipped)
+    }
+
+    /// Time added since the timer started
+    pub fn extended(&self) -> Duration {
+        self.extended
+    }
+
+    /// Time run so far, not counting pauses
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        let until = self.paused_at.unwrap_or(now);
+        let ran = until.saturating_duration_since(self.start).saturating_sub(self.paused_for);
+        (self.ahead + ran).min(self.length())
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.length() - self.elapsed(now)
+    }
+
+    /// Share of the timer run so far, from 0 to 1
+    pub fn done(&self, now: Instant) -> f64 {
+        self.elapsed(now).as_secs_f64() / self.length().as_secs_f64().max(1.0)
+    }
+
+    /// Whether the timer has run its whole length by `now`
+    pub fn is_done(&self, now: Instant) -> bool {
+        self.paused_at.is_none() && self.remaining(now).is_zero()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// How long the current pause has lasted by `now`, if it is paused
+    pub fn paused_since(&self, now: Instant) -> Option<Duration> {
+        self.paused_at.map(|paused_at| now.saturating_duration_since(paused_at))
+    }
+
+    /// Time spent paused by `now`, the current pause included
+    pub fn paused_for(&self, now: Instant) -> Duration {
+        self.paused_for + self.paused_since(now).unwrap_or_default()
+    }
+
+    /// Pause as of `at`, returning whether it was running
+    pub fn pause(&mut self, at: Instant) -> bool {
+        if self.paused_at.is_some() {
+            return false;
+        }
+        self.paused_at = Some(at.max(self.start));
+        true
+    }
+
+    /// Resume after a pause, returning how long it was paused
+    pub fn resume(&mut self, now: Instant) -> Option<Duration> {
+        let paused = now.saturating_duration_since(self.paused_at.take()?);
+        self.paused_for += paused;
+        Some(paused)
+    }
+
+    pub fn extend(&mut self, by: Duration) {
+        self.extended = self.extended.saturating_add(by);
+    }
+
+    /// End the timer at `now`, leaving what was run as its length
+    pub fn skip(&mut self, now: Instant) {
+        self.resume(now);
+        self.skipped += self.remaining(now);
+    }
+}
@@ -0,0 +1,149 @@
+// src/modes/wall_clock.rs
+//! Clock mode (`--clock`): the time of day in big digits, for a terminal
+//! left up as a desk or wall clock
+//!
+//! With `clock.show_date` on, the date and ISO week number are written in
+//! small text beneath the digits, in the `clock.date_format` chosen.
+
+use super::{instruction_lines, instructions, is_quit_key, Flow, Mode, Status};
+use crate::{
+    config::{ClockSettings, Config, InstructionSettings},
+    control::Command,
+    digit::DigitOptions,
+    theme::Theme,
+    ui::{self, DigitState, Screen},
+};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Local,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{text::Line};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Redraw often enough that the seconds turn over on time
+const REFRESH_RATE: Duration = Duration::from_millis(100);
+
+/// Whether `format` is one chrono can write a date with
+pub fn is_valid_date_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+pub struct WallClock {
+    theme: Theme,
+    instructions: InstructionSettings,
+    digits: DigitOptions,
+    /// The big digits as drawn last, kept for the next frame
+    digit_state: DigitState,
+    /// The strftime format of the line beneath the digits, if it is shown
+    date_format: Option<String>,
+}
+
+impl WallClock {
+    pub fn new(config: &Config) -> Self {
+        WallClock {
+            theme: Theme::from_config(config),
+            instructions: config.instructions.clone(),
+            digits: DigitOptions::from_config(config),
+            digit_state: DigitState::new(),
+            date_format: date_format(&config.clock),
+        }
+    }
+
+    fn date_line(&self, now: DateTime<Local>) -> Option<String> {
+        self.date_format.as_ref().map(|format| now.format(format).to_string())
+    }
+}
+
+/// The date format to show, falling back to the default when the configured
+/// one can't be written
+fn date_format(settings: &ClockSettings) -> Option<String> {
+    if !settings.show_date {
+        return None;
+    }
+    if is_valid_date_format(&settings.date_format) {
+        return Some(settings.date_format.clone());
+    }
+    warn!(format = settings.date_format, "invalid date format");
+    eprintln!("Invalid clock.date_format: {}. Using the default.", settings.date_format);
+    Some(ClockSettings::default().date_format)
+}
+
+impl Mode for WallClock {
+    fn refresh_rate(&self) -> Duration {
+        REFRESH_RATE
+    }
+
+    fn on_key(&mut self, key: KeyEvent, _now: Instant) -> Flow {
+        if is_quit_key(&key) {
+            Flow::Quit
+        } else {
+            Flow::Continue
+        }
+    }
+
+    fn animating(&self) -> bool {
+        self.digit_state.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    fn on_command(&mut self, command: Command, _now: Instant) -> Result<Flow, String> {
+        match command {
+            Command::Status | Command::Quit => Ok(Flow::Continue),
+            command => Err(format!("{} is not supported by the clock", command)),
+        }
+    }
+
+    fn status(&self, _now: Instant) -> Status {
+        let now = Local::now();
+        Status {
+            mode: "clock",
+            label: self.date_line(now),
+            time: now.format("%H:%M:%S").to_string(),
+            remaining: None,
+            elapsed: None,
+            paused: false,
+            finished: false,
+            prompt: None,
+        }
+    }
+
+    fn draw(&self, frame: &mut Screen, _now: Instant) {
+        let theme = &self.theme;
+        let header = instruction_lines(instructions(&self.instructions.clock, &[]));
+        let now = Local::now();
+        let time = now.format("%H:%M:%S").to_string();
+        match self.date_line(now) {
+            Some(date) => {
+                let caption = Line::styled(date, theme.ui_text);
+                ui::draw_timer_with_caption(
+                    frame,
+                    &header,
+                    theme.ui_text,
+                    &time,
+                    self.digits,
+                    &self.digit_state,
+                    theme.countdown,
+                    caption,
+                );
+            }
+            None => ui::draw_timer(
+                frame,
+                &header,
+                theme.ui_text,
+                &time,
+                self.digits,
+                &self.digit_state,
+                theme.countdown,
+            ),
+        }
+    }
+
+    fn exit_message(&self) -> String {
+        format!("Clock closed at {}.", Local::now().format("%H:%M"))
+    }
+}
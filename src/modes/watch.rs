@@ -0,0 +1,180 @@
+// src/modes/watch.rs
+//! Watch mode: a countdown whose target is read from a file (`--watch-file`)
+//!
+//! The file holds a time to count down, like `25:00` or `25m`, or a deadline
+//! like `at 17:30` or `at 2024-06-01 17:30`. Whenever its contents change the
+//! countdown starts over towards the new target, so a build script or cron
+//! job can retarget a wall display without restarting it.
+
+use super::{Countdown, Flow, Mode, Status, Summary};
+use crate::{clock, control::Command, parse_time_string, theme::Theme, ui::Screen};
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta};
+use crossterm::event::KeyEvent;
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// How often the file is read for changes
+const CHECK_EVERY: Duration = Duration::from_secs(1);
+
+/// What the watched file asks to count down to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// A length of time, in seconds
+    Length(u64),
+    /// A moment to count down to
+    Deadline(DateTime<Local>),
+}
+
+impl Target {
+    /// Seconds left to count down as of `now`
+    fn seconds_from(self, now: DateTime<Local>) -> u64 {
+        match self {
+            Target::Length(secs) => secs,
+            Target::Deadline(deadline) => (deadline - now).num_seconds().max(0) as u64,
+        }
+    }
+}
+
+/// Parse the contents of a watched file: a time like `25:00` or `25m`, or a
+/// deadline after `at`. A time of day alone means its next occurrence.
+pub fn parse_target(text: &str, now: DateTime<Local>) -> Result<Target, String> {
+    let text = text.trim();
+    match text.strip_prefix("at ") {
+        Some(deadline) => parse_deadline(deadline, now).map(Target::Deadline),
+        None => parse_time_string(text).map(Target::Length).map_err(str::to_string),
+    }
+}
+
+/// Parse a deadline like `17:30`, `17:30:15` or `2024-06-01 17:30`. A time
+/// of day alone means its next occurrence.
+pub fn parse_deadline(text: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let deadline = text.trim();
+    let time = NaiveTime::parse_from_str(deadline, "%H:%M:%S").or_else(|_| NaiveTime::parse_from_str(deadline, "%H:%M"));
+    if let Ok(time) = time {
+        let today = now.date_naive().and_time(time);
+        let next = if today > now.naive_local() { today } else { today + TimeDelta::days(1) };
+        return local(next);
+    }
+    if let Ok(moment) = DateTime::parse_from_rfc3339(deadline) {
+        return Ok(moment.with_timezone(&Local));
+    }
+    NaiveDateTime::parse_from_str(deadline, "%Y-%m-%d %H:%M")
+        .map_err(|_| format!("`{}` is not a time of day or a date and time", deadline))
+        .and_then(local)
+}
+
+fn local(moment: NaiveDateTime) -> Result<DateTime<Local>, String> {
+    moment
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| format!("{} doesn't exist in the local time zone", moment.format("%Y-%m-%d %H:%M")))
+}
+
+/// Builds the countdown for a target, set up the same way every time
+pub type Build = Box<dyn Fn(u64) -> Countdown>;
+
+/// A countdown restarted whenever the file it was read from changes
+pub struct Watched {
+    path: PathBuf,
+    build: Build,
+    countdown: Countdown,
+    /// The file's contents when it was last read
+    contents: String,
+    checked_at: Instant,
+    /// Theme picked with `t`, kept for the countdowns started later
+    theme: Option<Theme>,
+}
+
+impl Watched {
+    /// Read the target from `path` and start counting down to it
+    pub fn open(path: PathBuf, build: Build) -> Result<Self, String> {
+        let contents = fs::read_to_string(&path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        let target = parse_target(&contents, Local::now())?;
+        info!(path = %path.display(), ?target, "watching file");
+        let countdown = build(target.seconds_from(Local::now()));
+        Ok(Watched { path, build, countdown, contents, checked_at: clock::now(), theme: None })
+    }
+
+    /// Start over if the file now asks for something else
+    fn reload(&mut self) {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                // Editors often replace a file rather than write it, so it can
+                // be missing for a moment
+                warn!(path = %self.path.display(), error = %e, "could not read watched file");
+                return;
+            }
+        };
+        if contents == self.contents {
+            return;
+        }
+
+        match parse_target(&contents, Local::now()) {
+            Ok(target) => {
+                info!(?target, "watched file changed, retargeting");
+                self.countdown = (self.build)(target.seconds_from(Local::now()));
+                if let Some(theme) = &self.theme {
+                    self.countdown.set_theme(theme.clone());
+                }
+            }
+            Err(e) => warn!(error = %e, "watched file has no usable target, keeping the current one"),
+        }
+        self.contents = contents;
+    }
+}
+
+impl Mode for Watched {
+    fn refresh_rate(&self) -> Duration {
+        self.countdown.refresh_rate().min(CHECK_EVERY)
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Flow {
+        if now.saturating_duration_since(self.checked_at) >= CHECK_EVERY {
+            self.checked_at = now;
+            self.reload();
+        }
+        self.countdown.on_tick(now)
+    }
+
+    fn on_key(&mut self, key: KeyEvent, now: Instant) -> Flow {
+        self.countdown.on_key(key, now)
+    }
+
+    fn guards_quit(&self, now: Instant) -> bool {
+        self.countdown.guards_quit(now)
+    }
+
+    fn animating(&self) -> bool {
+        self.countdown.animating()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.countdown.set_theme(theme.clone());
+        self.theme = Some(theme);
+    }
+
+    fn on_command(&mut self, command: Command, now: Instant) -> Result<Flow, String> {
+        self.countdown.on_command(command, now)
+    }
+
+    fn status(&self, now: Instant) -> Status {
+        self.countdown.status(now)
+    }
+
+    fn draw(&self, frame: &mut Screen, now: Instant) {
+        self.countdown.draw(frame, now);
+    }
+
+    fn exit_message(&self) -> String {
+        self.countdown.exit_message()
+    }
+
+    fn summary(&self, now: Instant) -> Summary {
+        self.countdown.summary(now)
+    }
+}
@@ -0,0 +1,133 @@
+// src/ntp.rs
+//! Module for checking the system clock against a network time server
+//!
+//! An alarm rings when the system clock says so, so a clock that has drifted
+//! or was set wrong rings it early or late without any sign. `clockit alarms
+//! run` can ask an NTP server for the time once at startup (`clock_check`)
+//! and warn when the two disagree by more than `max_skew` seconds, or follow
+//! the server's time instead.
+
+use crate::config::ClockCheckSettings;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    io,
+    net::UdpSocket,
+    time::{Duration, SystemTime},
+};
+use tracing::{info, warn};
+
+/// Longest to wait for the server's answer
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Seconds from the NTP epoch (1900) to the Unix epoch (1970)
+const NTP_TO_UNIX: i64 = 2_208_988_800;
+
+/// What to do when the system clock is off (`clock_check.mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClockCheck {
+    /// Trust the system clock
+    #[default]
+    Off,
+    /// Say how far off it is
+    Warn,
+    /// Say how far off it is and follow the server's time
+    Adjust,
+}
+
+impl ClockCheck {
+    /// The names accepted in the config
+    pub const NAMES: [&'static str; 3] = ["off", "warn", "adjust"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "off" => Some(ClockCheck::Off),
+            "warn" => Some(ClockCheck::Warn),
+            "adjust" => Some(ClockCheck::Adjust),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(settings: &ClockCheckSettings) -> Self {
+        Self::from_name(&settings.mode).unwrap_or_else(|| {
+            warn!(mode = settings.mode, "unknown clock check mode");
+            eprintln!("Unknown clock check mode: {}. Using off.", settings.mode);
+            ClockCheck::Off
+        })
+    }
+}
+
+/// How far the server's clock is ahead of the system clock, asked with a
+/// single SNTP request
+pub fn offset(server: &str) -> io::Result<TimeDelta> {
+    let address = if server.contains(':') { server.to_string() } else { format!("{}:123", server) };
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    socket.connect(address)?;
+
+    // Version 3, client mode; everything else can be left empty
+    let mut request = [0u8; 48];
+    request[0] = 0x1b;
+    let sent = DateTime::<Utc>::from(SystemTime::now());
+    socket.send(&request)?;
+    let mut reply = [0u8; 48];
+    let read = socket.recv(&mut reply)?;
+    let received = DateTime::<Utc>::from(SystemTime::now());
+    if read < reply.len() || reply[0] & 0x07 != 4 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not an NTP server reply"));
+    }
+
+    // When the server got the request and when it sent the reply
+    let (Some(server_received), Some(server_sent)) = (timestamp(&reply[32..40]), timestamp(&reply[40..48])) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the server doesn't know the time"));
+    };
+    // The usual NTP estimate, taking the network delay as the same both ways
+    Ok(((server_received - sent) + (server_sent - received)) / 2)
+}
+
+/// A 64-bit NTP timestamp: seconds since 1900 and a binary fraction
+fn timestamp(bytes: &[u8]) -> Option<DateTime<Utc>> {
+    let seconds = u32::from_be_bytes(bytes[0..4].try_into().ok()?);
+    let fraction = u32::from_be_bytes(bytes[4..8].try_into().ok()?);
+    if seconds == 0 {
+        return None;
+    }
+    let nanos = (u64::from(fraction) * 1_000_000_000) >> 32;
+    DateTime::from_timestamp(i64::from(seconds) - NTP_TO_UNIX, nanos as u32)
+}
+
+/// Check the system clock as `settings` ask, returning how much to add to
+/// it to tell the time: zero unless the clock is off and the mode is adjust
+pub fn check(settings: &ClockCheckSettings) -> TimeDelta {
+    let mode = ClockCheck::from_config(settings);
+    if mode == ClockCheck::Off {
+        return TimeDelta::zero();
+    }
+
+    let offset = match offset(&settings.server) {
+        Ok(offset) => offset,
+        Err(e) => {
+            warn!(server = settings.server, error = %e, "could not check the clock");
+            println!("Couldn't check the clock against {}: {}.", settings.server, e);
+            return TimeDelta::zero();
+        }
+    };
+    info!(server = settings.server, offset_ms = offset.num_milliseconds(), "checked the clock");
+    if offset.abs() <= TimeDelta::seconds(settings.max_skew as i64) {
+        return TimeDelta::zero();
+    }
+
+    let seconds = offset.num_milliseconds().abs() as f64 / 1000.0;
+    let direction = if offset > TimeDelta::zero() { "behind" } else { "ahead of" };
+    warn!(server = settings.server, offset_ms = offset.num_milliseconds(), "system clock is off");
+    println!("The system clock is {:.1}s {} {}.", seconds, direction, settings.server);
+    match mode {
+        ClockCheck::Adjust => {
+            println!("Following {} instead, so alarms ring on time.", settings.server);
+            offset
+        }
+        _ => {
+            println!("Alarms will ring by the system clock. Set clock_check.mode to adjust to follow the server.");
+            TimeDelta::zero()
+        }
+    }
+}
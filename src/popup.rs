@@ -0,0 +1,127 @@
+// src/popup.rs
+//! Module for `clockit popup`: runs a timer in a tmux popup over the current
+//! pane, sized to fit its digits, that closes once the timer finishes
+//!
+//! The popup runs this same executable with the timer's arguments and
+//! `--exit-when-finished`, through `tmux display-popup -E`, which closes the
+//! popup when its command exits.
+
+use crate::{
+    config::{explicit_config_path, Config},
+    digit::{render_time, DigitOptions},
+    parse_time_string, sum_time_strings,
+};
+use std::{env, io, path::Path, process::Command};
+use tracing::{info, warn};
+
+/// Room around the digits for the popup's border and a margin
+const BORDER: usize = 4;
+
+/// Lines above and below the digits for the instructions, labels and prompts
+const CHROME_LINES: usize = 8;
+
+/// Narrowest popup, so the instructions and labels above the time fit
+const MIN_WIDTH: usize = 44;
+
+/// Run the timer described by `args` (e.g. `-c 5:00`) in a tmux popup, with
+/// the configuration file given with `--config` (`config_flag`), if any
+pub fn popup(args: &[String], config: &Config, config_flag: Option<&Path>) -> io::Result<()> {
+    if env::var_os("TMUX").is_none() {
+        println!("clockit popup only works inside tmux. Run the timer directly instead: clockit {}", args.join(" "));
+        return Ok(());
+    }
+
+    let (width, height) = popup_size(args, config);
+    let exe = env::current_exe()?;
+    // The popup runs under the tmux server, which doesn't share our environment
+    let command_line = command_line(&exe, explicit_config_path(config_flag).as_deref(), args);
+
+    let mut tmux = Command::new("tmux");
+    tmux.args(["display-popup", "-E", "-T", " clockit "])
+        .arg("-w")
+        .arg(width.to_string())
+        .arg("-h")
+        .arg(height.to_string());
+    if let Ok(dir) = env::current_dir() {
+        tmux.arg("-d").arg(dir);
+    }
+    tmux.arg(command_line.join(" "));
+    info!(width, height, ?args, "opening tmux popup");
+
+    match tmux.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => {
+            warn!(%status, "tmux display-popup failed");
+            println!("tmux could not open the popup ({}). Popups need tmux 3.2 or newer and an attached client.", status);
+            Ok(())
+        }
+        Err(e) => {
+            warn!(error = %e, "could not run tmux");
+            println!("Could not run tmux: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Columns and rows that fit the widest time the timer will show
+fn popup_size(args: &[String], config: &Config) -> (usize, usize) {
+    let digits = DigitOptions::from_config(config);
+    let art = render_time(&widest_time(args), &digits);
+    let scale = digits.scale.max(1);
+
+    let width = (art.width() * scale + BORDER).max(MIN_WIDTH);
+    let height = art.height() * scale + CHROME_LINES + BORDER;
+    (width, height)
+}
+
+/// The widest time the timer in `args` is likely to show: the countdown's
+/// starting time, or a stopwatch past its first ten minutes
+fn widest_time(args: &[String]) -> String {
+    // Several countdown times are added up, unless --list runs them in turn
+    let times: Vec<String> = args
+        .iter()
+        .enumerate()
+        .filter_map(|(i, arg)| match arg.as_str() {
+            "-c" | "--countdown" | "--presentation" => args.get(i + 1).cloned(),
+            _ => arg
+                .strip_prefix("--countdown=")
+                .or_else(|| arg.strip_prefix("--presentation="))
+                .map(str::to_string),
+        })
+        .collect();
+    let longest = if args.iter().any(|arg| arg == "--list") {
+        times.iter().filter_map(|time| parse_time_string(time).ok()).max()
+    } else {
+        sum_time_strings(&times).ok().filter(|_| !times.is_empty())
+    };
+    if let Some(secs) = longest {
+        return if secs >= 3600 { "00:00:00".to_string() } else { "00:00".to_string() };
+    }
+    if args.iter().any(|arg| arg == "-s" || arg == "--stopwatch") {
+        return "00:00.00".to_string();
+    }
+    "00:00".to_string()
+}
+
+/// Quote an argument for the shell tmux runs the popup's command with
+/// The command the popup runs: `exe` with `--config` and
+/// `--exit-when-finished` ahead of the timer's arguments, which may start with
+/// a subcommand that takes no top-level flags after it
+pub fn command_line(exe: &Path, config_path: Option<&Path>, args: &[String]) -> Vec<String> {
+    let mut command_line = vec![quote(&exe.to_string_lossy())];
+    if let Some(path) = config_path {
+        command_line.push("--config".to_string());
+        command_line.push(quote(&path.to_string_lossy()));
+    }
+    command_line.push("--exit-when-finished".to_string());
+    command_line.extend(args.iter().map(|arg| quote(arg)));
+    command_line
+}
+
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c)) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
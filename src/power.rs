@@ -0,0 +1,112 @@
+// src/power.rs
+//! Module for saving power on laptops running on battery
+//!
+//! With `power_save` on, or on `auto` while the computer runs on battery,
+//! the settings that keep the terminal redrawing are turned down before any
+//! timer starts: every mode redraws once a second, the separators stop
+//! blinking, and the animations and sub-second digits are left out.
+//!
+//! Battery power is read from `/sys/class/power_supply` on Linux and from
+//! `pmset` on macOS. Elsewhere `auto` never saves power.
+
+use crate::config::Config;
+use std::{path::Path, process::Command};
+use tracing::{info, warn};
+
+/// Slowest redraw kept while saving power, in milliseconds
+const REFRESH_RATE_MS: u64 = 1000;
+
+/// When to save power (`power_save`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerSave {
+    /// Only while running on battery
+    #[default]
+    Auto,
+    /// Always
+    On,
+    /// Never
+    Off,
+}
+
+impl PowerSave {
+    /// Names accepted for `power_save` in the configuration
+    pub const NAMES: [&'static str; 3] = ["auto", "on", "off"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "auto" => Some(PowerSave::Auto),
+            "on" => Some(PowerSave::On),
+            "off" => Some(PowerSave::Off),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        PowerSave::from_name(&config.power_save).unwrap_or_else(|| {
+            warn!(setting = config.power_save, "unknown power save setting");
+            eprintln!("Unknown power_save: {}. Using auto.", config.power_save);
+            PowerSave::default()
+        })
+    }
+}
+
+/// Turn down the settings that cost power, if the config asks for it now
+pub fn apply(config: &mut Config) {
+    let saving = match PowerSave::from_config(config) {
+        PowerSave::On => true,
+        PowerSave::Off => false,
+        PowerSave::Auto => on_battery(),
+    };
+    if !saving {
+        return;
+    }
+
+    info!("saving power");
+    config.countdown_refresh_rate = config.countdown_refresh_rate.max(REFRESH_RATE_MS);
+    config.stopwatch_refresh_rate = config.stopwatch_refresh_rate.max(REFRESH_RATE_MS);
+    config.pomodoro.refresh_rate = config.pomodoro.refresh_rate.max(REFRESH_RATE_MS);
+    config.stopwatch_precision = "seconds".to_string();
+    config.blink_separator = false;
+    config.color_mode = "static".to_string();
+    config.digit_transition = "none".to_string();
+    config.completion_animation = "none".to_string();
+    config.flash_screen = "off".to_string();
+}
+
+/// Whether the computer is running on battery right now
+pub fn on_battery() -> bool {
+    if cfg!(target_os = "macos") {
+        return pmset_on_battery();
+    }
+    sysfs_on_battery(Path::new("/sys/class/power_supply"))
+}
+
+/// On battery when there is a battery and no charger is plugged in
+fn sysfs_on_battery(supplies: &Path) -> bool {
+    let Ok(entries) = supplies.read_dir() else {
+        return false;
+    };
+
+    let mut battery = false;
+    for entry in entries.flatten() {
+        let read = |name: &str| std::fs::read_to_string(entry.path().join(name)).unwrap_or_default();
+        match read("type").trim() {
+            // Batteries in mice and keyboards report their scope as Device
+            "Battery" if read("scope").trim() != "Device" => battery = true,
+            "Mains" | "USB" if read("online").trim() == "1" => return false,
+            _ => {}
+        }
+    }
+    battery
+}
+
+/// macOS reports the power source on the first line of `pmset -g batt`
+fn pmset_on_battery() -> bool {
+    match Command::new("pmset").args(["-g", "batt"]).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"),
+        Err(e) => {
+            warn!(error = %e, "could not run pmset");
+            false
+        }
+    }
+}
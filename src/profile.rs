@@ -0,0 +1,58 @@
+// src/profile.rs
+//! Module for `--profile`: how long each frame takes to draw and how much
+//! it sends to the terminal, reported when the timer exits
+
+use std::{fmt, time::Duration};
+
+/// Timings collected over a run, one sample per frame drawn
+#[derive(Debug, Default, Clone)]
+pub struct FrameProfile {
+    frames: u64,
+    total_time: Duration,
+    worst_time: Duration,
+    total_bytes: u64,
+    most_bytes: u64,
+}
+
+impl FrameProfile {
+    /// Note a frame that took `time` to draw and wrote `bytes` to the terminal
+    pub fn record(&mut self, time: Duration, bytes: u64) {
+        self.frames += 1;
+        self.total_time += time;
+        self.worst_time = self.worst_time.max(time);
+        self.total_bytes += bytes;
+        self.most_bytes = self.most_bytes.max(bytes);
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn average_time(&self) -> Duration {
+        self.total_time.checked_div(self.frames as u32).unwrap_or_default()
+    }
+
+    pub fn average_bytes(&self) -> u64 {
+        self.total_bytes.checked_div(self.frames).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for FrameProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.frames == 0 {
+            return write!(f, "No frames drawn.");
+        }
+        writeln!(f, "Frames drawn:   {}", self.frames)?;
+        writeln!(
+            f,
+            "Render time:    {:.3} ms average, {:.3} ms worst",
+            millis(self.average_time()),
+            millis(self.worst_time)
+        )?;
+        write!(f, "Bytes written:  {} per frame on average, {} at most", self.average_bytes(), self.most_bytes)
+    }
+}
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,145 @@
+// src/progress.rs
+//! Module for showing how much of a timer has run, drawn around the digits
+//!
+//! `progress_style: bar` draws a bar along the bottom of the screen and
+//! `ring` an oval of braille dots around the digits, both filling as the
+//! time passes. They only draw on blank cells, so the digits and any text
+//! around them stay readable when the terminal is small.
+
+use crate::config::Config;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    Frame,
+};
+use std::f64::consts::TAU;
+use tracing::warn;
+
+/// How the share of a timer that has run is shown (`progress_style`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressStyle {
+    /// A bar along the bottom of the screen
+    Bar,
+    /// A ring around the digits, filling clockwise from the top
+    Ring,
+    /// Nothing, just the digits
+    #[default]
+    None,
+}
+
+impl ProgressStyle {
+    /// Names accepted for `progress_style` in the configuration
+    pub const NAMES: [&'static str; 3] = ["bar", "ring", "none"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bar" => Some(ProgressStyle::Bar),
+            "ring" => Some(ProgressStyle::Ring),
+            "none" => Some(ProgressStyle::None),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        ProgressStyle::from_name(&config.progress_style).unwrap_or_else(|| {
+            warn!(setting = config.progress_style, "unknown progress style, showing no progress");
+            ProgressStyle::default()
+        })
+    }
+}
+
+/// Style of the part still to run
+const REMAINING: Style = Style::new().fg(Color::DarkGray);
+
+/// Draw the share `done` (0 to 1) of a timer on the screen below the
+/// header, with the part done in the timer's `style`
+pub fn draw(frame: &mut Frame, progress: ProgressStyle, done: f64, header: &[Line], style: Style, ascii_only: bool) {
+    // A pulsing timer shouldn't turn the whole ring inside out
+    let style = style.remove_modifier(Modifier::REVERSED);
+    let area = frame.area();
+    let top = (header.len() as u16).min(area.height);
+    let area = Rect { y: area.y + top, height: area.height - top, ..area };
+    let buf = frame.buffer_mut();
+    let done = done.clamp(0.0, 1.0);
+    match progress {
+        ProgressStyle::Bar => render_bar(done, area, buf, style, ascii_only),
+        ProgressStyle::Ring if ascii_only => render_ascii_ring(done, area, buf, style),
+        ProgressStyle::Ring => render_ring(done, area, buf, style),
+        ProgressStyle::None => {}
+    }
+}
+
+/// Put `symbol` in a cell that nothing else has been drawn in
+fn set_blank(buf: &mut Buffer, x: u16, y: u16, symbol: char, style: Style) {
+    if let Some(cell) = buf.cell_mut((x, y)).filter(|cell| cell.symbol() == " ") {
+        cell.set_char(symbol).set_style(style);
+    }
+}
+
+/// A bar three fifths of the screen wide, on the line above the bottom one
+/// so it stays clear of the corners
+fn render_bar(done: f64, area: Rect, buf: &mut Buffer, style: Style, ascii_only: bool) {
+    if area.height < 3 {
+        return;
+    }
+    let width = area.width * 3 / 5;
+    let filled = (done * f64::from(width)).round() as u16;
+    let (full, empty) = if ascii_only { ('#', '-') } else { ('█', '░') };
+    let left = area.x + (area.width - width) / 2;
+    let y = area.bottom() - 2;
+    for i in 0..width {
+        let (symbol, cell_style) = if i < filled { (full, style) } else { (empty, REMAINING) };
+        set_blank(buf, left + i, y, symbol, cell_style);
+    }
+}
+
+/// The points of an oval filling `width` by `height`, going clockwise from
+/// the top, each with whether it is within the share `done`
+fn oval(width: u16, height: u16, done: f64) -> impl Iterator<Item = (u16, u16, bool)> {
+    let (rx, ry) = (f64::from(width.saturating_sub(1)) / 2.0, f64::from(height.saturating_sub(1)) / 2.0);
+    // Enough points that neighbouring ones touch
+    let points = 4 * (u32::from(width) + u32::from(height));
+    (0..points).map(move |i| {
+        let share = f64::from(i) / f64::from(points);
+        let angle = share * TAU;
+        let x = (rx + rx * angle.sin()).round() as u16;
+        let y = (ry - ry * angle.cos()).round() as u16;
+        (x, y, share < done)
+    })
+}
+
+/// Dot bits of a braille character, by column and row within the cell
+const BRAILLE_DOTS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// A ring of braille dots, two across and four down in each cell
+fn render_ring(done: f64, area: Rect, buf: &mut Buffer, style: Style) {
+    if area.width < 2 || area.height < 2 {
+        return;
+    }
+    let columns = usize::from(area.width);
+    // The dots in each cell, and whether any of them are in the part done
+    let mut cells = vec![(0u8, false); columns * usize::from(area.height)];
+    for (x, y, filled) in oval(area.width * 2, area.height * 4, done) {
+        let cell = &mut cells[usize::from(y / 4) * columns + usize::from(x / 2)];
+        cell.0 |= BRAILLE_DOTS[usize::from(x % 2)][usize::from(y % 4)];
+        cell.1 |= filled;
+    }
+    for (i, (dots, filled)) in cells.into_iter().enumerate() {
+        if dots == 0 {
+            continue;
+        }
+        let symbol = char::from_u32(0x2800 + u32::from(dots)).unwrap_or('.');
+        let (x, y) = (area.x + (i % columns) as u16, area.y + (i / columns) as u16);
+        set_blank(buf, x, y, symbol, if filled { style } else { REMAINING });
+    }
+}
+
+/// A ring of whole cells, for terminals without braille
+fn render_ascii_ring(done: f64, area: Rect, buf: &mut Buffer, style: Style) {
+    for (x, y, filled) in oval(area.width, area.height, done) {
+        let (symbol, cell_style) = if filled { ('o', style) } else { ('.', REMAINING) };
+        set_blank(buf, area.x + x, area.y + y, symbol, cell_style);
+    }
+}
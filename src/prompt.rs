@@ -0,0 +1,77 @@
+// src/prompt.rs
+//! Module for asking for a countdown time on the command line
+//!
+//! `clockit -c` without a time, or `clockit countdown` without any, asks for
+//! one instead of failing. The answer is read key by key in raw mode, so what
+//! it parses to, or why it doesn't, shows while it is typed.
+
+use crate::{format_duration, parse_time_string};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+use std::io::{self, stdout, IsTerminal, Write};
+
+/// Ask for a countdown time, returning None when it is cancelled or there is
+/// no terminal to ask on
+pub fn ask_countdown_time() -> io::Result<Option<String>> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Ok(None);
+    }
+
+    println!("How long should the countdown run? For example 5:00, 90, 25m or 1h30m.");
+    println!("Press Enter to start, or Esc to cancel.");
+    terminal::enable_raw_mode()?;
+    let answer = read_time();
+    terminal::disable_raw_mode()?;
+    println!();
+    answer
+}
+
+/// Read keys until a valid time is entered or the prompt is cancelled
+fn read_time() -> io::Result<Option<String>> {
+    let mut input = String::new();
+    loop {
+        draw(&input)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Enter if parse_time_string(input.trim()).is_ok_and(|secs| secs > 0) => {
+                return Ok(Some(input.trim().to_string()));
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+            _ => {}
+        }
+    }
+}
+
+/// Redraw the prompt line: the time typed so far, then what it parses to
+fn draw(input: &str) -> io::Result<()> {
+    let mut out = stdout();
+    queue!(out, cursor::MoveToColumn(0), Clear(ClearType::CurrentLine), Print("Time: "), Print(input))?;
+    // Keep the cursor after the input while the feedback follows it
+    queue!(out, cursor::SavePosition)?;
+    let text = input.trim();
+    if !text.is_empty() {
+        let feedback = match parse_time_string(text) {
+            Ok(0) => "  needs to be longer than zero".to_string().red(),
+            Ok(secs) => format!("  = {}", format_duration(secs)).dark_grey(),
+            Err(e) => format!("  {}", e).red(),
+        };
+        queue!(out, Print(feedback))?;
+    }
+    queue!(out, cursor::RestorePosition)?;
+    out.flush()
+}
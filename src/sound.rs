@@ -0,0 +1,330 @@
+// src/sound.rs
+//! Module for playing notification sounds without blocking the timer
+//!
+//! Each kind of event plays what `sounds` in the config picks for it: the
+//! terminal bell, one of the built-in tones, or a sound file. Tones and files
+//! are played by the first audio player found (afplay, paplay, pw-play,
+//! ffplay or aplay), so Clockit itself needs no audio libraries.
+
+use crate::config::SoundSettings;
+use std::{
+    env, fs,
+    io::{self, stdout, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc,
+};
+use tracing::{info, warn};
+
+/// Events that can trigger a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// A Pomodoro work session ended
+    WorkEnd,
+    /// A Pomodoro or Flowtime break ended
+    BreakEnd,
+    /// A kitchen timer, standup turn, plan block or stopwatch warm-up ran out,
+    /// or the last Pomodoro session ended
+    Complete,
+    /// A stopwatch reached its goal
+    GoalReached,
+    /// Another `--chime-every` interval passed
+    Chime,
+    /// A countdown crossed one of its alerts
+    Alert,
+    /// A metronome beat, or a second of a stopwatch warm-up
+    Beat,
+}
+
+/// A tone Clockit can play without any sound files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    /// One short high note
+    Beep,
+    /// Two rising notes
+    Chime,
+    /// A single bright note that rings out
+    Ding,
+    /// Three urgent beeps
+    Alarm,
+    /// A short tick, for the metronome
+    Click,
+}
+
+/// Samples per second of the generated tones
+const SAMPLE_RATE: u32 = 22_050;
+
+impl Tone {
+    /// Names accepted for the built-in tones in `sounds`
+    pub const NAMES: [&'static str; 5] = ["beep", "chime", "ding", "alarm", "click"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "beep" => Some(Tone::Beep),
+            "chime" => Some(Tone::Chime),
+            "ding" => Some(Tone::Ding),
+            "alarm" => Some(Tone::Alarm),
+            "click" => Some(Tone::Click),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Tone::Beep => "beep",
+            Tone::Chime => "chime",
+            Tone::Ding => "ding",
+            Tone::Alarm => "alarm",
+            Tone::Click => "click",
+        }
+    }
+
+    /// The notes as (frequency in Hz, length in ms), with 0 Hz for a rest
+    fn notes(self) -> &'static [(f32, u32)] {
+        match self {
+            Tone::Beep => &[(880.0, 180)],
+            Tone::Chime => &[(660.0, 220), (990.0, 380)],
+            Tone::Ding => &[(1320.0, 700)],
+            Tone::Alarm => &[(1000.0, 120), (0.0, 80), (1000.0, 120), (0.0, 80), (1000.0, 120)],
+            Tone::Click => &[(1800.0, 25)],
+        }
+    }
+
+    /// The tone as a 16-bit mono WAV file at `volume` percent
+    fn wav(self, volume: u8) -> Vec<u8> {
+        let amplitude = f32::from(i16::MAX) * 0.8 * f32::from(volume.min(100)) / 100.0;
+        let mut samples: Vec<i16> = Vec::new();
+        for &(frequency, millis) in self.notes() {
+            let count = SAMPLE_RATE * millis / 1000;
+            for i in 0..count {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                // Fade each note out so it ends without a click
+                let envelope = 1.0 - i as f32 / count as f32;
+                let value = (t * frequency * std::f32::consts::TAU).sin() * amplitude * envelope;
+                samples.push(value as i16);
+            }
+        }
+
+        let data_len = samples.len() as u32 * 2;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        wav
+    }
+}
+
+/// What to play for one kind of event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundSource {
+    /// Nothing at all
+    Silent,
+    /// The terminal bell, the one sound every terminal can make
+    Bell,
+    Tone(Tone),
+    File(PathBuf),
+}
+
+impl SoundSource {
+    /// Read a setting: `bell`, `none`, a built-in tone's name, or the path
+    /// of a sound file
+    pub fn from_setting(setting: &str) -> Result<Self, String> {
+        let setting = setting.trim();
+        match setting.to_lowercase().as_str() {
+            "" | "bell" => return Ok(SoundSource::Bell),
+            "none" => return Ok(SoundSource::Silent),
+            _ => {}
+        }
+        if let Some(tone) = Tone::from_name(setting) {
+            return Ok(SoundSource::Tone(tone));
+        }
+
+        let path = expand_home(setting);
+        if path.is_file() {
+            Ok(SoundSource::File(path))
+        } else {
+            Err(format!(
+                "`{}` is not bell, none, a built-in tone ({}) or a sound file",
+                setting,
+                Tone::NAMES.join(", ")
+            ))
+        }
+    }
+
+    /// The source set for `sound`, falling back to the bell when the setting
+    /// can't be used
+    fn for_sound(settings: &SoundSettings, sound: Sound) -> Self {
+        let setting = match sound {
+            Sound::WorkEnd => &settings.work_end,
+            Sound::BreakEnd => &settings.break_end,
+            Sound::Complete => &settings.complete,
+            Sound::GoalReached => &settings.goal,
+            Sound::Chime => &settings.chime,
+            Sound::Alert => &settings.alert,
+            Sound::Beat => &settings.beat,
+        };
+        SoundSource::from_setting(setting).unwrap_or_else(|e| {
+            warn!(?sound, error = %e, "unusable sound, ringing the bell");
+            SoundSource::Bell
+        })
+    }
+}
+
+/// A path with a leading `~` pointing into the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Handle for queueing sounds on the background playback thread
+#[derive(Clone)]
+pub struct SoundPlayer {
+    queue: Option<mpsc::Sender<SoundSource>>,
+    settings: SoundSettings,
+}
+
+impl SoundPlayer {
+    /// Start the playback thread, or return a silent player when sound is disabled
+    pub fn spawn(enabled: bool, settings: &SoundSettings) -> Self {
+        if !enabled {
+            return SoundPlayer::silent();
+        }
+
+        // Sounds play one after another, so two events at once stay distinguishable
+        let (tx, rx) = mpsc::channel();
+        let volume = settings.volume;
+        std::thread::spawn(move || {
+            let mut player = AudioPlayer::default();
+            for source in rx {
+                match source {
+                    SoundSource::Silent => {}
+                    SoundSource::Bell => ring_bell(),
+                    // The tone is already at the right volume
+                    SoundSource::Tone(tone) => match tone_file(tone, volume) {
+                        Ok(path) => player.play(&path, 100),
+                        Err(e) => {
+                            warn!(tone = tone.name(), error = %e, "could not write tone");
+                            ring_bell();
+                        }
+                    },
+                    SoundSource::File(path) => player.play(&path, volume),
+                }
+            }
+        });
+
+        SoundPlayer { queue: Some(tx), settings: settings.clone() }
+    }
+
+    /// A player that ignores every sound
+    pub fn silent() -> Self {
+        SoundPlayer { queue: None, settings: SoundSettings::default() }
+    }
+
+    /// Queue the sound configured for an event
+    pub fn play(&self, sound: Sound) {
+        if self.queue.is_some() {
+            self.play_source(SoundSource::for_sound(&self.settings, sound));
+        }
+    }
+
+    /// Queue a sound chosen outside `sounds`, such as an alarm's own
+    pub fn play_source(&self, source: SoundSource) {
+        if let Some(queue) = &self.queue {
+            let _ = queue.send(source);
+        }
+    }
+}
+
+fn ring_bell() {
+    let mut stdout = stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+/// A tone written out as a WAV file for the audio player, reused while the
+/// volume stays the same
+fn tone_file(tone: Tone, volume: u8) -> io::Result<PathBuf> {
+    let path = env::temp_dir().join(format!("clockit-{}-{}.wav", tone.name(), volume));
+    if !path.exists() {
+        fs::write(&path, tone.wav(volume))?;
+    }
+    Ok(path)
+}
+
+/// Programs that can play a sound file, in the order they are tried
+const PLAYERS: [&str; 5] = ["afplay", "paplay", "pw-play", "ffplay", "aplay"];
+
+/// Plays files with the first program in [`PLAYERS`] that is installed
+#[derive(Default)]
+struct AudioPlayer {
+    /// Index into PLAYERS of the program found, once one has been
+    found: Option<usize>,
+    /// Set once none of them could be run
+    missing: bool,
+}
+
+impl AudioPlayer {
+    /// Play `path` and wait for it to finish, ringing the bell if there is no
+    /// player to play it with
+    fn play(&mut self, path: &Path, volume: u8) {
+        if self.missing {
+            ring_bell();
+            return;
+        }
+
+        let start = self.found.unwrap_or(0);
+        for (index, program) in PLAYERS.iter().enumerate().skip(start) {
+            match player_command(program, path, volume).status() {
+                Ok(status) => {
+                    if self.found.is_none() {
+                        info!(player = program, "playing sounds");
+                        self.found = Some(index);
+                    }
+                    if !status.success() {
+                        warn!(player = program, path = ?path, %status, "could not play sound");
+                    }
+                    return;
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound && self.found.is_none() => continue,
+                Err(e) => {
+                    warn!(player = program, error = %e, "could not run audio player");
+                    return;
+                }
+            }
+        }
+
+        warn!("no audio player found, ringing the bell instead");
+        self.missing = true;
+        ring_bell();
+    }
+}
+
+/// The command playing `path` with `program` at `volume` percent
+fn player_command(program: &str, path: &Path, volume: u8) -> Command {
+    let fraction = f32::from(volume.min(100)) / 100.0;
+    let mut command = Command::new(program);
+    match program {
+        "afplay" => command.arg("-v").arg(fraction.to_string()),
+        "paplay" => command.arg(format!("--volume={}", (fraction * 65536.0) as u32)),
+        "pw-play" => command.arg(format!("--volume={}", fraction)),
+        "ffplay" => command.args(["-nodisp", "-autoexit", "-loglevel", "quiet", "-volume"]).arg(volume.min(100).to_string()),
+        // aplay has no volume of its own
+        _ => command.arg("-q"),
+    };
+    command.arg(path).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    command
+}
@@ -0,0 +1,129 @@
+// src/stats_cmd.rs
+//! Module for the `clockit stats` subcommands
+
+use crate::{
+    caps,
+    config::Config,
+    history::{self, SessionKind},
+    theme,
+};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use crossterm::style::{Color, Stylize};
+use std::{
+    collections::HashMap,
+    io::{self, stdout, IsTerminal},
+};
+
+/// Width of the weekday labels in front of each row
+const LABEL_WIDTH: usize = 4;
+
+/// Most weeks shown, about a year like GitHub's contribution graph
+const MAX_WEEKS: usize = 53;
+
+/// Print a calendar grid of focused minutes per day, one column per week
+pub fn heatmap(config: &Config, weeks: Option<usize>) -> io::Result<()> {
+    let today = Local::now().date_naive();
+
+    // Fit as many weeks as the terminal has room for, two columns per week
+    let columns = crossterm::terminal::size().map_or(80, |(columns, _)| columns as usize);
+    let weeks = weeks
+        .unwrap_or((columns.saturating_sub(LABEL_WIDTH) / 2).min(MAX_WEEKS))
+        .max(1);
+    let first_monday = today
+        - Duration::days(today.weekday().num_days_from_monday() as i64)
+        - Duration::weeks(weeks as i64 - 1);
+
+    // Sum up focused minutes for each day
+    let mut minutes: HashMap<NaiveDate, u64> = HashMap::new();
+    for session in history::load(Some(first_monday))? {
+        if session.kind == SessionKind::Work {
+            *minutes.entry(session.started_at.date_naive()).or_default() += session.duration_secs / 60;
+        }
+    }
+    let busiest = minutes.values().copied().max().unwrap_or(0);
+
+    let ascii_only = config.ascii_only || !caps::supports_unicode();
+    let glyphs: [&str; 5] = if ascii_only { [".", ":", "+", "*", "#"] } else { ["·", "░", "▒", "▓", "█"] };
+    let color = config.parse_color(&theme::color_scheme(config).pomodoro_work);
+    let painter = Painter { glyphs, color, enabled: stdout().is_terminal() };
+
+    println!("{}", month_labels(first_monday, weeks));
+    for (row, weekday) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let mut line = format!("{:<width$}", weekday, width = LABEL_WIDTH);
+        for week in 0..weeks {
+            let date = first_monday + Duration::days((week * 7 + row) as i64);
+            if date > today {
+                break;
+            }
+            let day_minutes = minutes.get(&date).copied().unwrap_or(0);
+            line.push_str(&painter.cell(level(day_minutes, busiest)));
+            line.push(' ');
+        }
+        println!("{}", line.trim_end());
+    }
+
+    let legend: Vec<String> = (0..glyphs.len()).map(|level| painter.cell(level)).collect();
+    println!("{:width$}Less {} More", "", legend.join(" "), width = LABEL_WIDTH);
+
+    let total: u64 = minutes.values().sum();
+    let days = minutes.values().filter(|&&day| day > 0).count();
+    println!();
+    println!(
+        "{}h {}m focused over {} days in the last {} weeks (busiest day: {} min)",
+        total / 60,
+        total % 60,
+        days,
+        weeks,
+        busiest
+    );
+
+    Ok(())
+}
+
+/// Draws heatmap cells, coloring them when printing to a terminal
+struct Painter {
+    glyphs: [&'static str; 5],
+    color: Color,
+    enabled: bool,
+}
+
+impl Painter {
+    fn cell(&self, level: usize) -> String {
+        let glyph = self.glyphs[level];
+        match (self.enabled, level) {
+            (false, _) => glyph.to_string(),
+            (true, 0) => glyph.dark_grey().to_string(),
+            (true, _) => glyph.with(self.color).to_string(),
+        }
+    }
+}
+
+/// Intensity from 0 (no focus) to 4 (the busiest day)
+fn level(minutes: u64, busiest: u64) -> usize {
+    if minutes == 0 || busiest == 0 {
+        0
+    } else {
+        (minutes * 4).div_ceil(busiest).clamp(1, 4) as usize
+    }
+}
+
+/// Month names placed above the first week of each month
+fn month_labels(first_monday: NaiveDate, weeks: usize) -> String {
+    let mut labels = " ".repeat(LABEL_WIDTH + weeks * 2);
+    let mut free_from = 0;
+
+    for week in 0..weeks {
+        let monday = first_monday + Duration::weeks(week as i64);
+        let new_month = monday.day() <= 7;
+        let column = LABEL_WIDTH + week * 2;
+        let name = monday.format("%b").to_string();
+
+        // Skip labels that would run into the previous one or off the end
+        if new_month && column >= free_from && column + name.len() <= labels.len() {
+            labels.replace_range(column..column + name.len(), &name);
+            free_from = column + name.len() + 1;
+        }
+    }
+
+    labels.trim_end().to_string()
+}
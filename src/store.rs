@@ -0,0 +1,206 @@
+// src/store.rs
+//! Module for timer state that persists between runs, such as saved
+//! stopwatches and suspended countdowns
+
+use crate::{config::Config, modes::Countdown};
+use chrono::{DateTime, Local};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::{info, warn};
+
+/// A stopwatch saved with `--persist`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct SavedStopwatch {
+    /// Accumulated elapsed time in milliseconds
+    elapsed_ms: u64,
+}
+
+/// A countdown saved with `--name`, to carry on with `clockit resume`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct SavedCountdown {
+    /// Full length of the countdown in seconds, including any time added
+    pub length_secs: u64,
+    /// Time left when it was saved, in milliseconds
+    pub remaining_ms: u64,
+    /// Whether the countdown was paused when it was saved
+    pub paused: bool,
+    pub saved_at: DateTime<Local>,
+}
+
+/// What happens to a suspended countdown's time while it isn't running
+/// (`countdown_resume`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResumeMode {
+    /// Pick up with exactly the time that was left
+    #[default]
+    Frozen,
+    /// Keep counting down in the meantime, as if it had never stopped
+    WallClock,
+}
+
+impl ResumeMode {
+    /// Names accepted for `countdown_resume` in the configuration
+    pub const NAMES: [&'static str; 2] = ["frozen", "wall_clock"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "frozen" => Some(ResumeMode::Frozen),
+            "wall_clock" => Some(ResumeMode::WallClock),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        ResumeMode::from_name(&config.countdown_resume).unwrap_or_else(|| {
+            warn!(mode = config.countdown_resume, "unknown countdown resume mode");
+            eprintln!("Unknown countdown_resume: {}. Using frozen.", config.countdown_resume);
+            ResumeMode::default()
+        })
+    }
+}
+
+impl SavedCountdown {
+    /// Time left at `now`. A countdown suspended while paused stays frozen
+    /// either way.
+    pub fn remaining(&self, mode: ResumeMode, now: DateTime<Local>) -> Duration {
+        let remaining = Duration::from_millis(self.remaining_ms);
+        if mode == ResumeMode::Frozen || self.paused {
+            return remaining;
+        }
+        // A clock set back since the save counts as no time passing
+        let away = (now - self.saved_at).to_std().unwrap_or_default();
+        remaining.saturating_sub(away)
+    }
+
+    /// Carry on with this countdown with `remaining` left, paused again if it
+    /// was paused when it was saved
+    pub fn resume(&self, remaining: Duration, config: &Config) -> Countdown {
+        let countdown = Countdown::new(self.length_secs, config).with_remaining(remaining);
+        if self.paused {
+            countdown.start_paused()
+        } else {
+            countdown
+        }
+    }
+}
+
+/// Get the directory Clockit keeps its state and logs in
+pub fn get_state_dir() -> io::Result<PathBuf> {
+    // Linux has a dedicated state directory, other platforms keep state with local data
+    match dirs::state_dir().or_else(dirs::data_local_dir) {
+        Some(dir) => Ok(dir.join("clockit")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Could not find state directory",
+        )),
+    }
+}
+
+fn get_stopwatches_path() -> io::Result<PathBuf> {
+    Ok(get_state_dir()?.join("stopwatches.yaml"))
+}
+
+fn get_countdowns_path() -> io::Result<PathBuf> {
+    Ok(get_state_dir()?.join("countdowns.yaml"))
+}
+
+/// Read the timers saved in the file at `path`, keyed by name
+fn load_saved<T: DeserializeOwned>(path: &Path) -> io::Result<BTreeMap<String, T>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    serde_yaml::from_str(&contents).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("could not parse {}: {}", path.display(), e),
+        )
+    })
+}
+
+fn save_saved<T: Serialize>(path: &Path, saved: &BTreeMap<String, T>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Write to a temporary file first so an interrupted save can't lose every timer
+    let yaml = serde_yaml::to_string(saved).map_err(io::Error::other)?;
+    let temp_path = path.with_extension("yaml.tmp");
+    fs::write(&temp_path, yaml)?;
+    fs::rename(&temp_path, path)
+}
+
+fn load_stopwatches() -> io::Result<BTreeMap<String, SavedStopwatch>> {
+    load_saved(&get_stopwatches_path()?)
+}
+
+fn save_stopwatches(stopwatches: &BTreeMap<String, SavedStopwatch>) -> io::Result<()> {
+    save_saved(&get_stopwatches_path()?, stopwatches)
+}
+
+/// Elapsed time saved under `name`, if there is any
+pub fn load_stopwatch(name: &str) -> io::Result<Option<Duration>> {
+    let stopwatches = load_stopwatches()?;
+    Ok(stopwatches.get(name).map(|saved| Duration::from_millis(saved.elapsed_ms)))
+}
+
+/// Save the elapsed time of a stopwatch under `name`
+pub fn save_stopwatch(name: &str, elapsed: Duration) -> io::Result<()> {
+    let mut stopwatches = load_stopwatches()?;
+    let elapsed_ms = elapsed.as_millis().try_into().unwrap_or(u64::MAX);
+    stopwatches.insert(name.to_string(), SavedStopwatch { elapsed_ms });
+    save_stopwatches(&stopwatches)?;
+    info!(name, elapsed_ms, "saved stopwatch");
+    Ok(())
+}
+
+/// Forget the stopwatch saved under `name`, returning whether there was one
+pub fn reset_stopwatch(name: &str) -> io::Result<bool> {
+    let mut stopwatches = load_stopwatches()?;
+    if stopwatches.remove(name).is_none() {
+        return Ok(false);
+    }
+    save_stopwatches(&stopwatches)?;
+    info!(name, "reset stopwatch");
+    Ok(true)
+}
+
+/// The countdown suspended under `name`, if there is one
+pub fn load_countdown(name: &str) -> io::Result<Option<SavedCountdown>> {
+    let countdowns: BTreeMap<String, SavedCountdown> = load_saved(&get_countdowns_path()?)?;
+    Ok(countdowns.get(name).copied())
+}
+
+/// Names of all suspended countdowns
+pub fn countdown_names() -> io::Result<Vec<String>> {
+    let countdowns: BTreeMap<String, SavedCountdown> = load_saved(&get_countdowns_path()?)?;
+    Ok(countdowns.into_keys().collect())
+}
+
+/// Save a countdown under `name`, replacing any saved before
+pub fn save_countdown(name: &str, countdown: SavedCountdown) -> io::Result<()> {
+    let path = get_countdowns_path()?;
+    let mut countdowns = load_saved(&path)?;
+    countdowns.insert(name.to_string(), countdown);
+    save_saved(&path, &countdowns)?;
+    info!(name, remaining_ms = countdown.remaining_ms, "saved countdown");
+    Ok(())
+}
+
+/// Forget the countdown saved under `name`, returning whether there was one
+pub fn forget_countdown(name: &str) -> io::Result<bool> {
+    let path = get_countdowns_path()?;
+    let mut countdowns: BTreeMap<String, SavedCountdown> = load_saved(&path)?;
+    if countdowns.remove(name).is_none() {
+        return Ok(false);
+    }
+    save_saved(&path, &countdowns)?;
+    info!(name, "forgot countdown");
+    Ok(true)
+}
@@ -0,0 +1,47 @@
+// src/sun.rs
+//! Module for telling whether the sun is up at a place on Earth
+//!
+//! Used by `theme_schedule` to switch between its day and night themes at
+//! sunrise and sunset. The sun's position comes from the low-precision
+//! formulas of the Astronomical Almanac, good to a fraction of a degree,
+//! which puts sunrise and sunset within a minute or two.
+
+use chrono::{DateTime, Utc};
+
+/// Height of the sun's center at sunrise and sunset, in degrees: its radius
+/// and the bending of its light by the atmosphere put it just below the horizon
+const HORIZON: f64 = -0.833;
+
+/// Julian date of 2000-01-01 12:00 UTC (J2000.0)
+const J2000: f64 = 2_451_545.0;
+
+/// Julian date of the Unix epoch
+const UNIX_EPOCH: f64 = 2_440_587.5;
+
+/// The sun's height above the horizon in degrees at `moment`, seen from
+/// `latitude` and `longitude` (degrees, north and east positive)
+pub fn elevation(moment: DateTime<Utc>, latitude: f64, longitude: f64) -> f64 {
+    let days = moment.timestamp() as f64 / 86_400.0 + UNIX_EPOCH - J2000;
+
+    // Where the sun is along the ecliptic
+    let mean_longitude = 280.460 + 0.985_647_4 * days;
+    let mean_anomaly = (357.528 + 0.985_600_3 * days).to_radians();
+    let ecliptic_longitude =
+        (mean_longitude + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+
+    // ...and so where it is in the sky
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+    let sidereal_time = (280.460_618_37 + 360.985_647_366_29 * days + longitude).to_radians();
+    let hour_angle = sidereal_time - right_ascension;
+
+    let latitude = latitude.to_radians();
+    let height = latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos();
+    height.asin().to_degrees()
+}
+
+/// Whether the sun has risen and not yet set at `moment`
+pub fn is_up(moment: DateTime<Utc>, latitude: f64, longitude: f64) -> bool {
+    elevation(moment, latitude, longitude) > HORIZON
+}
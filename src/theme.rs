@@ -0,0 +1,456 @@
+// src/theme.rs
+//! Module for resolving configured colors and built-in palettes into widget styles
+
+use crate::{
+    caps,
+    config::{self, ColorScheme, Config, ThemeScheduleSettings},
+    sun,
+};
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use ratatui::style::{Color, Modifier, Style};
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Time for the rainbow to go once around the color wheel
+const RAINBOW_CYCLE: Duration = Duration::from_secs(6);
+
+/// Time for the digits to pulse from bright to dim and back
+const PULSE_CYCLE: Duration = Duration::from_secs(2);
+
+/// Colors the rainbow steps through when the terminal can't show 24-bit color
+const RAINBOW_COLORS: [Color; 6] =
+    [Color::LightRed, Color::LightYellow, Color::LightGreen, Color::LightCyan, Color::LightBlue, Color::LightMagenta];
+
+/// A named, built-in color scheme
+struct Palette {
+    name: &'static str,
+    colors: [&'static str; 6],
+    /// Show text tags alongside colors so sessions can be told apart without them
+    session_tags: bool,
+}
+
+/// Palettes that can be selected with the `theme` config option.
+/// Colors are listed as countdown, stopwatch, times_up, ui_text, pomodoro_work, pomodoro_break.
+const PALETTES: [Palette; 4] = [
+    // Red-green color blindness: contrast blue against yellow instead
+    Palette {
+        name: "deuteranopia",
+        colors: ["cyan", "blue", "yellow", "grey", "yellow", "blue"],
+        session_tags: true,
+    },
+    // Reds look dark with protanopia, so avoid them entirely
+    Palette {
+        name: "protanopia",
+        colors: ["cyan", "blue", "white", "grey", "yellow", "dark_blue"],
+        session_tags: true,
+    },
+    // Blue-yellow color blindness: contrast red against cyan
+    Palette {
+        name: "tritanopia",
+        colors: ["cyan", "red", "magenta", "grey", "red", "cyan"],
+        session_tags: true,
+    },
+    // Dim reds for a display in a dark room, easy on eyes used to the dark
+    Palette {
+        name: "night",
+        colors: ["dark_red", "dark_red", "red", "dark_grey", "dark_red", "dark_yellow"],
+        session_tags: false,
+    },
+];
+
+impl Palette {
+    fn color_scheme(&self) -> ColorScheme {
+        let [countdown, stopwatch, times_up, ui_text, pomodoro_work, pomodoro_break] =
+            self.colors.map(str::to_string);
+        ColorScheme {
+            countdown,
+            stopwatch,
+            times_up,
+            ui_text,
+            pomodoro_work,
+            pomodoro_break,
+            separator: String::new(),
+            fraction: String::new(),
+            badge: String::new(),
+        }
+    }
+}
+
+/// Styles used by the different parts of the interface
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Style for countdown timer digits
+    pub countdown: Style,
+
+    /// Style for stopwatch digits
+    pub stopwatch: Style,
+
+    /// Style for the time's up banner and completion messages
+    pub times_up: Style,
+
+    /// Style for instructions and other UI text
+    pub ui_text: Style,
+
+    /// Style for Pomodoro work session digits
+    pub pomodoro_work: Style,
+
+    /// Style for Pomodoro break session digits
+    pub pomodoro_break: Style,
+
+    /// Tag Pomodoro sessions with [WORK]/[BREAK] so they don't rely on color alone
+    pub session_tags: bool,
+
+    /// Style for the state badges above the timer, like PAUSED
+    pub badge: Style,
+
+    /// Start the badges with an icon, where the terminal can show one
+    pub badge_icons: bool,
+}
+
+/// The colors of the theme selected in the config, falling back to the configured colors
+pub fn color_scheme(config: &Config) -> ColorScheme {
+    find_palette(config).map_or_else(|| config.colors.clone(), Palette::color_scheme)
+}
+
+fn find_palette(config: &Config) -> Option<&'static Palette> {
+    let name = config.theme.to_lowercase();
+    let palette = PALETTES.iter().find(|palette| palette.name == name);
+    if palette.is_none() && name != "default" {
+        // Themes are looked up again mid-run, for the `t` key and `--then` steps
+        warn!(theme = %config.theme, "unknown theme, using the configured colors");
+    }
+    palette
+}
+
+impl Theme {
+    /// Build the theme selected in the config, falling back to the configured
+    /// colors
+    pub fn from_config(config: &Config) -> Self {
+        let palette = find_palette(config);
+        let colors = palette.map_or_else(|| config.colors.clone(), Palette::color_scheme);
+        let color = |name: &str| -> Color { config.parse_color(name).into() };
+        let times_up = color(&colors.times_up);
+        let badge = if config.colors.badge.trim().is_empty() { times_up } else { color(&config.colors.badge) };
+
+        Theme {
+            countdown: fg(color(&colors.countdown)),
+            stopwatch: fg(color(&colors.stopwatch)),
+            times_up: fg(times_up).add_modifier(Modifier::BOLD),
+            ui_text: fg(color(&colors.ui_text)),
+            pomodoro_work: fg(color(&colors.pomodoro_work)),
+            pomodoro_break: fg(color(&colors.pomodoro_break)),
+            session_tags: palette.is_some_and(|palette| palette.session_tags),
+            badge: fg(badge).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            badge_icons: !config.ascii_only && caps::supports_unicode(),
+        }
+    }
+}
+
+/// Whether `name` is a theme that can be selected in the config
+pub fn is_known_theme(name: &str) -> bool {
+    let name = name.to_lowercase();
+    theme_names().any(|theme| theme == name)
+}
+
+/// Every theme, in the order the `t` key goes through them
+pub fn theme_names() -> impl Iterator<Item = &'static str> {
+    std::iter::once("default").chain(PALETTES.iter().map(|palette| palette.name))
+}
+
+/// Switches between the themes while a timer runs, and offers to keep the
+/// last one picked once it exits
+#[derive(Debug)]
+pub struct ThemeSwitch {
+    config: Config,
+    /// The file given with `--config`, which the picked theme is saved to
+    config_flag: Option<PathBuf>,
+    /// Save the theme picked without asking
+    save: bool,
+    /// The theme on screen, from the config, the schedule or the `t` key
+    showing: String,
+    /// Theme picked with the `t` key, which takes over for the rest of the run
+    picked: Option<&'static str>,
+}
+
+impl ThemeSwitch {
+    pub fn new(config: &Config, config_flag: Option<&Path>, save: bool) -> Self {
+        ThemeSwitch {
+            config: config.clone(),
+            config_flag: config_flag.map(Path::to_path_buf),
+            save,
+            showing: config.theme.to_lowercase(),
+            picked: None,
+        }
+    }
+
+    /// Whether a theme has been picked with the `t` key
+    pub fn picked(&self) -> bool {
+        self.picked.is_some()
+    }
+
+    /// Move on to the theme after the one on screen, returning it
+    pub fn next_theme(&mut self) -> Theme {
+        let names: Vec<&'static str> = theme_names().collect();
+        // An unknown theme in the config shows the configured colors, like default
+        let index = names.iter().position(|name| *name == self.showing).unwrap_or(0);
+        let next = names[(index + 1) % names.len()];
+        info!(theme = next, "switching theme");
+        self.picked = Some(next);
+        self.show(next)
+    }
+
+    /// Note that `name` is now on screen, returning its theme
+    fn show(&mut self, name: &str) -> Theme {
+        self.showing = name.to_lowercase();
+        Theme::from_config(&Config { theme: self.showing.clone(), ..self.config.clone() })
+    }
+
+    /// Save the theme picked with `t` to the configuration file, if it isn't
+    /// the one there already, asking first unless `--save-theme` was given
+    pub fn offer_save(&self) -> io::Result<()> {
+        let Some(picked) = self.picked.filter(|picked| !self.config.theme.eq_ignore_ascii_case(picked)) else {
+            return Ok(());
+        };
+        if !self.save {
+            if !io::stdin().is_terminal() {
+                return Ok(());
+            }
+            print!("Save the {} theme to the configuration file? [y/N] ", picked);
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            io::stdin().lock().read_line(&mut answer)?;
+            if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                return Ok(());
+            }
+        }
+
+        let path = config::save_setting(self.config_flag.as_deref(), "theme", picked)?;
+        info!(theme = picked, path = %path.display(), "saved theme");
+        println!("Saved theme {} to {}", picked, path.display());
+        Ok(())
+    }
+}
+
+/// How often the schedule is checked for the switch between day and night
+const SCHEDULE_CHECK: Duration = Duration::from_secs(30);
+
+/// What decides when it is day (`theme_schedule.switch`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayStart {
+    /// Day starts at the first time and night at the second
+    Clock(NaiveTime, NaiveTime),
+    /// Day is while the sun is up at this latitude and longitude
+    Sun(f64, f64),
+}
+
+impl DayStart {
+    /// Read the settings: a switch like `07:00/19:00`, or an empty switch and
+    /// a latitude and longitude
+    pub fn from_settings(settings: &ThemeScheduleSettings) -> Result<Self, String> {
+        let switch = settings.switch.trim();
+        if switch.is_empty() {
+            return match (settings.latitude, settings.longitude) {
+                (Some(latitude), Some(longitude)) if latitude.abs() <= 90.0 && longitude.abs() <= 180.0 => {
+                    Ok(DayStart::Sun(latitude, longitude))
+                }
+                (Some(_), Some(_)) => Err("latitude must be within ±90 and longitude within ±180".to_string()),
+                _ => Err("an empty switch needs a latitude and longitude to follow the sun".to_string()),
+            };
+        }
+        let time = |text: &str| {
+            let text = text.trim();
+            NaiveTime::parse_from_str(text, "%H:%M").map_err(|_| format!("`{}` is not a time like 07:00", text))
+        };
+        let (day, night) = switch.split_once('/').ok_or_else(|| format!("`{}` is not like 07:00/19:00", switch))?;
+        Ok(DayStart::Clock(time(day)?, time(night)?))
+    }
+
+    /// Whether it is day at `now`
+    pub fn is_day(self, now: DateTime<Local>) -> bool {
+        match self {
+            DayStart::Clock(day, night) => {
+                let time = now.time();
+                if day <= night {
+                    day <= time && time < night
+                } else {
+                    // Day runs through midnight
+                    time >= day || time < night
+                }
+            }
+            DayStart::Sun(latitude, longitude) => sun::is_up(now.with_timezone(&Utc), latitude, longitude),
+        }
+    }
+}
+
+/// Switches between a day and a night theme as the time of day passes
+/// (`theme_schedule`), unless another was picked with `t`
+#[derive(Debug)]
+pub struct ThemeSchedule {
+    config: Config,
+    start: DayStart,
+    /// Themes by day and by night
+    themes: (String, String),
+    /// Whether the theme in use is the day theme
+    day: bool,
+    checked_at: Option<Instant>,
+}
+
+impl ThemeSchedule {
+    /// The schedule in the config, if it names a day or night theme, setting
+    /// `config.theme` to the one for now so the timer starts with it
+    pub fn from_config(config: &mut Config) -> Option<Self> {
+        let settings = &config.theme_schedule;
+        if settings.day.trim().is_empty() && settings.night.trim().is_empty() {
+            return None;
+        }
+        let start = DayStart::from_settings(settings)
+            // Timers are built with the screen up, so this can only be logged;
+            // `clockit config validate` points it out
+            .map_err(|e| warn!(error = %e, "unusable theme schedule, keeping the theme"))
+            .ok()?;
+        let theme = |name: &str| if name.trim().is_empty() { config.theme.clone() } else { name.trim().to_string() };
+        let themes = (theme(&settings.day), theme(&settings.night));
+        let day = start.is_day(Local::now());
+        config.theme = if day { themes.0.clone() } else { themes.1.clone() };
+        Some(ThemeSchedule { config: config.clone(), start, themes, day, checked_at: None })
+    }
+
+    /// The theme to switch to, if day has turned to night or night to day
+    /// since the last check at most [`SCHEDULE_CHECK`] ago. `themes` is told
+    /// about the switch, and stops it once a theme was picked with `t`.
+    pub fn update(&mut self, now: Instant, themes: Option<&mut ThemeSwitch>) -> Option<Theme> {
+        if self.checked_at.is_some_and(|at| now.saturating_duration_since(at) < SCHEDULE_CHECK) {
+            return None;
+        }
+        self.checked_at = Some(now);
+        let day = self.start.is_day(Local::now());
+        // A theme picked with t holds for the rest of the run
+        if day == self.day || themes.as_ref().is_some_and(|themes| themes.picked()) {
+            return None;
+        }
+        self.day = day;
+        self.config.theme = if day { self.themes.0.clone() } else { self.themes.1.clone() };
+        info!(theme = self.config.theme, day, "scheduled theme switch");
+        match themes {
+            Some(themes) => Some(themes.show(&self.config.theme)),
+            None => Some(Theme::from_config(&self.config)),
+        }
+    }
+}
+
+fn fg(color: Color) -> Style {
+    Style::default().fg(color)
+}
+
+/// How the color of the big digits changes from frame to frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Always the configured color
+    #[default]
+    Static,
+    /// Cycle through the hues of the rainbow
+    Rainbow,
+    /// Fade the configured color between bright and dim
+    Pulse,
+}
+
+impl ColorMode {
+    /// Names accepted for `color_mode` in the configuration
+    pub const NAMES: [&'static str; 3] = ["static", "rainbow", "pulse"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "static" => Some(ColorMode::Static),
+            "rainbow" => Some(ColorMode::Rainbow),
+            "pulse" => Some(ColorMode::Pulse),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        // Read again for each step of a `--then` chain, over the running timer
+        ColorMode::from_name(&config.color_mode).unwrap_or_else(|| {
+            warn!(mode = config.color_mode, "unknown color mode, using static");
+            ColorMode::default()
+        })
+    }
+
+    /// The digit style for a frame drawn `elapsed` after the animation
+    /// started. With `true_color` the colors change smoothly; otherwise the
+    /// rainbow steps through the basic colors and the pulse switches between
+    /// normal and dim.
+    pub fn style(self, style: Style, true_color: bool, elapsed: Duration) -> Style {
+        match self {
+            ColorMode::Static => style,
+            ColorMode::Rainbow => {
+                let turn = cycle_position(elapsed, RAINBOW_CYCLE);
+                if true_color {
+                    style.fg(hue_color(turn * 360.0))
+                } else {
+                    style.fg(RAINBOW_COLORS[(turn * RAINBOW_COLORS.len() as f64) as usize % RAINBOW_COLORS.len()])
+                }
+            }
+            ColorMode::Pulse => {
+                // Brightest at the start of each cycle, dimmest halfway through
+                let wave = (cycle_position(elapsed, PULSE_CYCLE) * std::f64::consts::TAU).cos();
+                match style.fg.and_then(rgb).filter(|_| true_color) {
+                    Some((r, g, b)) => {
+                        let brightness = 0.65 + 0.35 * wave;
+                        let scale = |channel: u8| (channel as f64 * brightness).round() as u8;
+                        style.fg(Color::Rgb(scale(r), scale(g), scale(b)))
+                    }
+                    None if wave < 0.0 => style.add_modifier(Modifier::DIM),
+                    None => style,
+                }
+            }
+        }
+    }
+}
+
+/// How far through its current cycle `elapsed` is, from 0 up to 1
+fn cycle_position(elapsed: Duration, cycle: Duration) -> f64 {
+    (elapsed.as_millis() % cycle.as_millis()) as f64 / cycle.as_millis() as f64
+}
+
+/// A fully saturated color with the given hue in degrees
+fn hue_color(hue: f64) -> Color {
+    let sector = hue / 60.0;
+    let rising = (255.0 * (1.0 - (sector % 2.0 - 1.0).abs())).round() as u8;
+    let (r, g, b) = match sector as u32 {
+        0 => (255, rising, 0),
+        1 => (rising, 255, 0),
+        2 => (0, 255, rising),
+        3 => (0, rising, 255),
+        4 => (rising, 0, 255),
+        _ => (255, 0, rising),
+    };
+    Color::Rgb(r, g, b)
+}
+
+/// Approximate RGB values of a color, to dim it smoothly
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    Some(match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        _ => return None,
+    })
+}
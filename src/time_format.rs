@@ -0,0 +1,181 @@
+// src/time_format.rs
+//! Module for the `time_format` option: strftime-like patterns that decide
+//! how each mode writes out its time
+//!
+//! `%H`, `%M` and `%S` are hours, minutes and seconds with a leading zero;
+//! `%h`, `%m` and `%s` leave it off. `%D` is days, `%c` hundredths of a second
+//! and `%%` a percent sign. Whatever is inside square brackets is only shown
+//! when one of its fields isn't zero, so `[%h:]%M:%S` hides the hours for the
+//! first hour. The largest field in a pattern holds all of the time above it,
+//! e.g. `%M:%S` shows 90 minutes as 90:00.
+
+use std::time::Duration;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Unit {
+    Centiseconds,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl Unit {
+    /// Length of one of this unit in hundredths of a second
+    fn centiseconds(self) -> u64 {
+        match self {
+            Unit::Centiseconds => 1,
+            Unit::Seconds => 100,
+            Unit::Minutes => 60 * 100,
+            Unit::Hours => 60 * 60 * 100,
+            Unit::Days => 24 * 60 * 60 * 100,
+        }
+    }
+
+    /// How many of this unit make up the next larger one
+    fn per_next(self) -> u64 {
+        match self {
+            Unit::Centiseconds => 100,
+            Unit::Seconds | Unit::Minutes => 60,
+            Unit::Hours => 24,
+            Unit::Days => u64::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Literal(String),
+    Field { unit: Unit, padded: bool },
+    /// Shown only when a field inside isn't zero
+    Optional(Vec<Piece>),
+}
+
+/// A parsed `time_format` pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormat {
+    pieces: Vec<Piece>,
+    /// Field that holds all of the time above it
+    largest: Unit,
+    smallest: Unit,
+}
+
+impl TimeFormat {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut optional: Option<Vec<Piece>> = None;
+        let mut chars = pattern.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => {
+                    let piece = match chars.next() {
+                        Some('D') => Piece::Field { unit: Unit::Days, padded: false },
+                        Some('H') => Piece::Field { unit: Unit::Hours, padded: true },
+                        Some('h') => Piece::Field { unit: Unit::Hours, padded: false },
+                        Some('M') => Piece::Field { unit: Unit::Minutes, padded: true },
+                        Some('m') => Piece::Field { unit: Unit::Minutes, padded: false },
+                        Some('S') => Piece::Field { unit: Unit::Seconds, padded: true },
+                        Some('s') => Piece::Field { unit: Unit::Seconds, padded: false },
+                        Some('c') => Piece::Field { unit: Unit::Centiseconds, padded: true },
+                        Some('%') => Piece::Literal("%".to_string()),
+                        Some(other) => return Err(format!("unknown field `%{}`", other)),
+                        None => return Err("`%` at the end needs a field letter after it".to_string()),
+                    };
+                    push(optional.as_mut().unwrap_or(&mut pieces), piece);
+                }
+                '[' if optional.is_none() => optional = Some(Vec::new()),
+                '[' => return Err("square brackets can't be nested".to_string()),
+                ']' => match optional.take() {
+                    Some(inner) => pieces.push(Piece::Optional(inner)),
+                    None => return Err("`]` without a `[` before it".to_string()),
+                },
+                c => push(optional.as_mut().unwrap_or(&mut pieces), Piece::Literal(c.to_string())),
+            }
+        }
+        if optional.is_some() {
+            return Err("`[` without a `]` after it".to_string());
+        }
+
+        let fields = units(&pieces);
+        let (Some(&largest), Some(&smallest)) = (fields.iter().max(), fields.iter().min()) else {
+            return Err("the pattern has no time fields".to_string());
+        };
+        Ok(TimeFormat { pieces, largest, smallest })
+    }
+
+    /// The pattern in `pattern`, or None to keep the mode's built-in format.
+    /// A pattern that can't be used is reported and ignored.
+    pub fn from_setting(pattern: &str, mode: &str) -> Option<Self> {
+        if pattern.trim().is_empty() {
+            return None;
+        }
+        match TimeFormat::parse(pattern) {
+            Ok(format) => Some(format),
+            Err(e) => {
+                warn!(mode, pattern, error = %e, "invalid time format, using the default");
+                None
+            }
+        }
+    }
+
+    pub fn format(&self, time: Duration) -> String {
+        let total = time.as_millis() as u64 / 10;
+        let mut text = String::new();
+        self.write(&self.pieces, total, &mut text);
+        text
+    }
+
+    /// Format time left, rounded up so zero only shows once it has run out
+    pub fn format_remaining(&self, remaining: Duration) -> String {
+        let step = self.smallest.centiseconds() * 10;
+        let millis = remaining.as_millis() as u64;
+        self.format(Duration::from_millis(millis.div_ceil(step) * step))
+    }
+
+    fn value(&self, unit: Unit, total: u64) -> u64 {
+        let count = total / unit.centiseconds();
+        if unit == self.largest {
+            count
+        } else {
+            count % unit.per_next()
+        }
+    }
+
+    fn write(&self, pieces: &[Piece], total: u64, text: &mut String) {
+        for piece in pieces {
+            match piece {
+                Piece::Literal(literal) => text.push_str(literal),
+                Piece::Field { unit, padded: true } => text.push_str(&format!("{:02}", self.value(*unit, total))),
+                Piece::Field { unit, padded: false } => text.push_str(&self.value(*unit, total).to_string()),
+                Piece::Optional(inner) => {
+                    if units(inner).into_iter().any(|unit| self.value(unit, total) > 0) {
+                        self.write(inner, total, text);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Add a piece, joining literal text into one piece
+fn push(pieces: &mut Vec<Piece>, piece: Piece) {
+    if let (Some(Piece::Literal(last)), Piece::Literal(next)) = (pieces.last_mut(), &piece) {
+        last.push_str(next);
+    } else {
+        pieces.push(piece);
+    }
+}
+
+/// Every unit shown by the pieces, including inside optional sections
+fn units(pieces: &[Piece]) -> Vec<Unit> {
+    pieces
+        .iter()
+        .flat_map(|piece| match piece {
+            Piece::Field { unit, .. } => vec![*unit],
+            Piece::Optional(inner) => units(inner),
+            Piece::Literal(_) => Vec::new(),
+        })
+        .collect()
+}
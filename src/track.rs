@@ -0,0 +1,68 @@
+// src/track.rs
+//! Module for time tracked against projects with `clockit track`
+//!
+//! Like the history, each entry is appended as one JSON object per line.
+
+use crate::store;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+use tracing::{info, warn};
+
+/// Time spent on a project in one run of `clockit track`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackedTime {
+    pub project: String,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    /// Time tracked, not counting pauses
+    pub duration_secs: u64,
+}
+
+/// Get the path to the tracked time file
+pub fn get_tracked_path() -> io::Result<PathBuf> {
+    Ok(store::get_state_dir()?.join("tracked.jsonl"))
+}
+
+/// Append tracked time to the file
+pub fn record(entry: &TrackedTime) -> io::Result<()> {
+    let path = get_tracked_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new().create(true).append(true).open(&path)?.write_all(line.as_bytes())?;
+    info!(project = %entry.project, duration_secs = entry.duration_secs, "recorded tracked time");
+    Ok(())
+}
+
+/// Load all time tracked since the start of `since`
+pub fn load(since: NaiveDate) -> io::Result<Vec<TrackedTime>> {
+    let path = get_tracked_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let mut entries = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<TrackedTime>(line) {
+            Ok(entry) if entry.started_at.date_naive() >= since => entries.push(entry),
+            Ok(_) => {}
+            Err(e) => {
+                warn!(line = index + 1, error = %e, "skipping unreadable tracked time");
+                eprintln!("Skipping unreadable tracked time on line {}: {}", index + 1, e);
+            }
+        }
+    }
+    Ok(entries)
+}
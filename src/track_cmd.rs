@@ -0,0 +1,43 @@
+// src/track_cmd.rs
+//! Module for the `clockit track report` subcommand
+
+use crate::{modes::hours_minutes, track};
+use chrono::{Days, Local, NaiveDate};
+use std::{collections::BTreeMap, io, time::Duration};
+
+/// Print the hours tracked on each project for each of the last `days` days
+pub fn report(days: u64) -> io::Result<()> {
+    let today = Local::now().date_naive();
+    let since = today - Days::new(days.max(1) - 1);
+
+    // Time per project for each day, and in total
+    let mut by_day: BTreeMap<NaiveDate, BTreeMap<String, u64>> = BTreeMap::new();
+    let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+    for entry in track::load(since)? {
+        let day = by_day.entry(entry.started_at.date_naive()).or_default();
+        *day.entry(entry.project.clone()).or_default() += entry.duration_secs;
+        *totals.entry(entry.project).or_default() += entry.duration_secs;
+    }
+
+    if totals.is_empty() {
+        println!("No time tracked since {}. Start with `clockit track --project NAME`.", since);
+        return Ok(());
+    }
+
+    let width = totals.keys().map(|project| project.chars().count()).max().unwrap_or(0);
+    let print_projects = |projects: &BTreeMap<String, u64>| {
+        for (project, secs) in projects {
+            println!("  {:<width$}  {:>7}", project, hours_minutes(Duration::from_secs(*secs)), width = width);
+        }
+    };
+
+    // Most recent day first
+    for (date, projects) in by_day.iter().rev() {
+        println!("{}", date.format("%Y-%m-%d %a"));
+        print_projects(projects);
+    }
+    println!();
+    println!("Total since {}", since.format("%Y-%m-%d"));
+    print_projects(&totals);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// src/tui.rs
+//! Module for entering and leaving the full-screen terminal interface
+
+use crate::caps;
+use crossterm::{
+    cursor,
+    event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::{
+    io::{self, stdout, Stdout, Write},
+    panic,
+};
+use tracing::error;
+
+/// The terminal type used by all timer modes
+pub type Tui = Terminal<CrosstermBackend<TermWriter>>;
+
+/// Stdout, counting the bytes written through it
+pub struct TermWriter {
+    out: Stdout,
+    /// Bytes sent to the terminal while drawing, for `--profile`
+    written: u64,
+}
+
+impl TermWriter {
+    /// Total bytes drawn to the terminal so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+}
+
+impl Write for TermWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.out.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Switch to raw mode and the alternate screen, returning a ratatui terminal.
+/// Terminals without an alternate screen are drawn over and cleared.
+pub fn init() -> io::Result<Tui> {
+    terminal::enable_raw_mode()?;
+    if caps::supports_alternate_screen() {
+        execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+    } else {
+        execute!(stdout(), Clear(ClearType::All), cursor::Hide)?;
+    }
+    Terminal::new(CrosstermBackend::new(TermWriter { out: stdout(), written: 0 }))
+}
+
+/// Leave the alternate screen and restore the terminal to its normal state
+pub fn restore() -> io::Result<()> {
+    if caps::supports_alternate_screen() {
+        execute!(stdout(), cursor::Show, LeaveAlternateScreen)?;
+    } else {
+        execute!(stdout(), Clear(ClearType::All), cursor::MoveTo(0, 0), cursor::Show)?;
+    }
+    terminal::disable_raw_mode()
+}
+
+/// Put the terminal back before a panic message is printed, so a crash
+/// doesn't leave the shell in raw mode with the cursor hidden
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // Leaving the alternate screen is harmless if it was never entered
+        let _ = restore();
+        error!(panic = %info, "clockit panicked");
+        default_hook(info);
+        eprintln!(
+            "\nClockit crashed. Please report this at https://github.com/ioloej42/clockit/issues, \
+             including the message above (run with RUST_BACKTRACE=1 for more detail)."
+        );
+    }));
+}
+
+/// Whether the terminal can report keys being let go (the kitty keyboard protocol)
+pub fn supports_key_release() -> bool {
+    terminal::supports_keyboard_enhancement().unwrap_or(false)
+}
+
+/// Ask the terminal to report keys being let go as well as pressed
+pub fn report_key_release() -> io::Result<()> {
+    let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES | KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+    execute!(stdout(), PushKeyboardEnhancementFlags(flags))
+}
+
+/// Go back to reporting key presses only
+pub fn stop_key_release() -> io::Result<()> {
+    execute!(stdout(), PopKeyboardEnhancementFlags)
+}
@@ -0,0 +1,671 @@
+// src/ui.rs
+//! Module for drawing the timer screens with ratatui widgets
+
+use crate::{
+    clock,
+    config::Config,
+    digit::{self, BannerFont, DigitOptions, Part, RenderedTime, Transition},
+    locale::{fill, messages},
+    theme::Theme,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Widget},
+    Frame,
+};
+use std::{
+    cell::{Cell, RefCell, RefMut},
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// Names accepted for `fade_chrome` in the configuration
+pub const FADE_CHROME_NAMES: [&str; 3] = ["off", "dim", "hide"];
+
+/// How the instructions and other text around the timer are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Chrome {
+    #[default]
+    Shown,
+    Dimmed,
+    Hidden,
+}
+
+/// Fading the instructions out after a while without a key press
+#[derive(Debug, Clone, Copy)]
+pub struct ChromeFade {
+    /// How the instructions look once faded
+    faded: Chrome,
+    after: Duration,
+}
+
+impl ChromeFade {
+    /// The configured fade, or None when the instructions always show
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let faded = match config.fade_chrome.to_lowercase().as_str() {
+            "off" => return None,
+            "dim" => Chrome::Dimmed,
+            "hide" => Chrome::Hidden,
+            other => {
+                warn!(fade = other, "unknown chrome fade");
+                eprintln!("Unknown fade_chrome: {}. Not fading the instructions.", other);
+                return None;
+            }
+        };
+        Some(ChromeFade { faded, after: Duration::from_secs(config.fade_chrome_after.max(1)) })
+    }
+
+    /// How the instructions look when the last key was pressed `idle` ago
+    pub fn chrome(self, idle: Duration) -> Chrome {
+        if idle >= self.after {
+            self.faded
+        } else {
+            Chrome::Shown
+        }
+    }
+}
+
+/// A frame being drawn, along with how the event loop wants the text around
+/// the timer drawn in it. Everything else goes straight to the frame.
+pub struct Screen<'a, 'b> {
+    frame: &'a mut Frame<'b>,
+    chrome: Chrome,
+}
+
+impl<'a, 'b> Screen<'a, 'b> {
+    pub fn new(frame: &'a mut Frame<'b>, chrome: Chrome) -> Self {
+        Screen { frame, chrome }
+    }
+}
+
+impl<'b> Deref for Screen<'_, 'b> {
+    type Target = Frame<'b>;
+
+    fn deref(&self) -> &Frame<'b> {
+        self.frame
+    }
+}
+
+impl<'b> DerefMut for Screen<'_, 'b> {
+    fn deref_mut(&mut self) -> &mut Frame<'b> {
+        self.frame
+    }
+}
+
+/// Draw instructions or other text around the timer, dimmed or left out
+/// while the chrome is faded
+pub fn render_chrome<W: Widget>(frame: &mut Screen, widget: W, area: Rect) {
+    match frame.chrome {
+        Chrome::Shown => frame.render_widget(widget, area),
+        Chrome::Dimmed => {
+            frame.render_widget(widget, area);
+            frame.buffer_mut().set_style(area, Style::default().fg(Color::DarkGray));
+        }
+        Chrome::Hidden => {}
+    }
+}
+
+/// How long a digit takes to change with `digit_transition`
+const TRANSITION_TIME: Duration = Duration::from_millis(200);
+
+/// Time between frames while a digit is changing
+pub const TRANSITION_FRAME: Duration = Duration::from_millis(40);
+
+/// The time last drawn in big digits, and the digits in it still changing:
+/// for each character, the digit it changed from and when
+#[derive(Debug, Default)]
+struct Changes {
+    text: Vec<char>,
+    changed: Vec<Option<(char, Instant)>>,
+}
+
+impl Changes {
+    /// Note the digits that differ from the time drawn last, returning how far
+    /// through its transition each changing digit is at `now`
+    fn update(&mut self, text: &str, now: Instant) -> Vec<Option<(char, f64)>> {
+        let parts: Vec<(char, Part)> = digit::time_parts(text).collect();
+        if parts.len() != self.text.len() {
+            // The digits moved, so there is nothing to change from
+            self.changed = vec![None; parts.len()];
+        } else {
+            for (i, (old, (new, part))) in self.text.iter().zip(&parts).enumerate() {
+                // Hundredths change too often to animate
+                if old != new && old.is_ascii_digit() && new.is_ascii_digit() && *part == Part::Digits {
+                    self.changed[i] = Some((*old, now));
+                }
+            }
+        }
+        self.text = parts.into_iter().map(|(c, _)| c).collect();
+
+        self.changed
+            .iter_mut()
+            .map(|change| {
+                let (old, since) = (*change)?;
+                let progress = now.saturating_duration_since(since).as_secs_f64() / TRANSITION_TIME.as_secs_f64();
+                if progress >= 1.0 {
+                    *change = None;
+                    return None;
+                }
+                Some((old, progress))
+            })
+            .collect()
+    }
+}
+
+/// What a timer's big digits keep from one frame to the next: the cells
+/// drawn last, whose memory the next frame reuses, the digits still
+/// changing with `digit_transition`, and when `color_mode` started
+/// animating them. Each timer on screen keeps its own.
+#[derive(Debug, Default)]
+pub struct DigitState {
+    rendered: RefCell<RenderedTime>,
+    changes: RefCell<Changes>,
+    first_drawn: Cell<Option<Instant>>,
+}
+
+impl DigitState {
+    pub fn new() -> Self {
+        DigitState::default()
+    }
+
+    /// Whether a digit drawn in the last frame is still changing, so the
+    /// next frame should come sooner
+    pub fn animating(&self) -> bool {
+        self.changes.borrow().changed.iter().any(Option::is_some)
+    }
+
+    /// Render `text` at the standard size for the frame drawn at `now`, with
+    /// any digits changing part way through, ready to be fitted to the space
+    /// it is drawn in
+    fn render(&self, text: &str, options: &DigitOptions, now: Instant) -> RefMut<'_, RenderedTime> {
+        let mut rendered = self.rendered.borrow_mut();
+        match options.transition {
+            Transition::None => rendered.render(text, options, &[]),
+            _ => {
+                let changing = self.changes.borrow_mut().update(text, now);
+                rendered.render(text, options, &changing);
+            }
+        }
+        rendered
+    }
+
+    /// How long the colors have been animating at `now`, counted from the
+    /// first frame so the rainbow and pulse start from their beginning
+    fn animation_time(&self, now: Instant) -> Duration {
+        let start = *self.first_drawn.get().get_or_insert(now);
+        self.first_drawn.set(Some(start));
+        now.saturating_duration_since(start)
+    }
+}
+
+/// Widget that draws a time string (like "12:34") in large ASCII art digits,
+/// centered in the area it is given
+pub struct BigDigits<'a> {
+    text: &'a str,
+    options: DigitOptions,
+    state: &'a DigitState,
+    style: Style,
+}
+
+impl<'a> BigDigits<'a> {
+    pub fn new(text: &'a str, options: DigitOptions, state: &'a DigitState) -> Self {
+        BigDigits { text, options, state, style: Style::default() }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Widget for BigDigits<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let now = clock::now();
+        let mut rendered = self.state.render(self.text, &self.options, now);
+        let fits = fit_digits(&mut rendered, self.text, &self.options, area.width, area.height);
+        let animated = self.state.animation_time(now);
+        let drawn = render_digits(&rendered, &self.options, self.style, animated, area, buf);
+        if !fits {
+            render_too_small(drawn, area, buf);
+        }
+    }
+}
+
+/// Scale digits rendered at the standard size up to the largest scale, up to
+/// the configured one, that fits the given size. Falls back to a single line
+/// of plain text when even the standard digits don't fit, returning whether
+/// they did.
+fn fit_digits(rendered: &mut RenderedTime, text: &str, options: &DigitOptions, width: u16, height: u16) -> bool {
+    let max_scale = if options.auto_fit { digit::MAX_SCALE } else { options.scale };
+    let scale = (1..=max_scale).rev().find(|scale| {
+        rendered.width() * scale <= width as usize && rendered.height() * scale <= height as usize
+    });
+
+    match scale {
+        Some(scale) => rendered.scale(scale),
+        None => rendered.render_plain(text),
+    }
+    scale.is_some()
+}
+
+/// Say why the time is plain text on the line below it, where `time` is
+/// the line it was drawn on, if there is room within `area`
+fn render_too_small(time: Rect, area: Rect, buf: &mut Buffer) {
+    let y = time.bottom();
+    if y >= area.bottom() {
+        return;
+    }
+    let hint = messages().too_small.as_str();
+    let width = (hint.chars().count() as u16).min(area.width);
+    let x = area.x + (area.width - width) / 2;
+    buf.set_stringn(x, y, hint, usize::from(width), Style::default().fg(Color::DarkGray));
+}
+
+/// Draw rendered digits centered in `area`, with the separators and hundredths
+/// styled as configured and colors `animated` that long, clipping whatever
+/// doesn't fit. Returns where they went.
+fn render_digits(
+    rendered: &RenderedTime,
+    options: &DigitOptions,
+    style: Style,
+    animated: Duration,
+    area: Rect,
+    buf: &mut Buffer,
+) -> Rect {
+    let area = centered(area, rendered.width() as u16, rendered.height() as u16);
+    // Work out each part's style once a frame rather than for every cell
+    let [digits, separator, fraction] =
+        [Part::Digits, Part::Separator, Part::Fraction].map(|part| options.style(part, style, animated));
+    for (y, row) in (area.y..area.bottom()).zip(rendered.cells()) {
+        for (x, cell) in (area.x..area.right()).zip(row) {
+            let style = match cell.part {
+                Part::Digits => digits,
+                Part::Separator => separator,
+                Part::Fraction => fraction,
+            };
+            let style = if cell.hidden { style.add_modifier(Modifier::HIDDEN) } else { style };
+            buf[(x, y)].set_char(cell.symbol).set_style(style);
+        }
+    }
+    area
+}
+
+/// Widget that draws a block of pre-rendered text lines centered in its area
+pub struct Banner<'a, S: AsRef<str>> {
+    lines: &'a [S],
+    style: Style,
+}
+
+impl<'a, S: AsRef<str>> Banner<'a, S> {
+    pub fn new(lines: &'a [S]) -> Self {
+        Banner { lines, style: Style::default() }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl<S: AsRef<str>> Widget for Banner<'_, S> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = self
+            .lines
+            .iter()
+            .map(|line| line.as_ref().chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+        let height = self.lines.len() as u16;
+        let area = centered(area, width, height);
+
+        // Anything that doesn't fit the area is clipped rather than wrapped
+        for (i, line) in self.lines.iter().take(area.height as usize).enumerate() {
+            buf.set_stringn(area.x, area.y + i as u16, line.as_ref(), area.width as usize, self.style);
+        }
+    }
+}
+
+/// Returns a rectangle of the given size centered in `area`, clamped to fit
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Draw the header lines at the top of the frame
+fn draw_header(frame: &mut Screen, header: &[Line], style: Style) {
+    let [header_area, _] = Layout::vertical([
+        Constraint::Length(header.len() as u16),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    render_chrome(frame, Paragraph::new(header.to_vec()).style(style), header_area);
+}
+
+/// Text in big letters like the digits, with `banner_font: large` and when
+/// it fits in `width` columns
+pub fn large_text(text: &str, digits: DigitOptions, width: u16) -> Option<Vec<String>> {
+    if digits.banner_font != BannerFont::Large {
+        return None;
+    }
+    // Letters run together without a gap, where digits don't
+    digit::render_text(text, digits.spacing.max(1))
+        .filter(|lines| lines.iter().all(|line| line.chars().count() <= usize::from(width)))
+}
+
+/// Frame a line of text in a box, to make it stand out like a name card
+pub fn framed(text: &str, ascii_only: bool) -> Vec<String> {
+    let width = text.chars().count() + 2;
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = if ascii_only {
+        ['+', '+', '+', '+', '-', '|']
+    } else {
+        ['╔', '╗', '╚', '╝', '═', '║']
+    };
+    let edge = horizontal.to_string().repeat(width);
+    vec![
+        format!("{}{}{}", top_left, edge, top_right),
+        format!("{} {} {}", vertical, text, vertical),
+        format!("{}{}{}", bottom_left, edge, bottom_right),
+    ]
+}
+
+/// A state worth seeing at a glance, shown above the timer the same way in
+/// every mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    Paused,
+    /// Running past its goal
+    Overtime,
+    /// The Pomodoro cycle under way, out of how many (0 for no end)
+    Cycle(u64, u64),
+}
+
+impl Badge {
+    fn text(self, icons: bool) -> String {
+        let messages = messages();
+        let (icon, label) = match self {
+            Badge::Paused => ("⏸", messages.paused.clone()),
+            Badge::Overtime => ("⏱", messages.overtime.clone()),
+            Badge::Cycle(cycle, 0) => ("🔁", fill(&messages.cycle, &[("cycle", &cycle.to_string())])),
+            Badge::Cycle(cycle, cycles) => (
+                "🔁",
+                fill(&messages.cycle_of, &[("cycle", &cycle.to_string()), ("cycles", &cycles.to_string())]),
+            ),
+        };
+        if icons {
+            format!(" {} {} ", icon, label.to_uppercase())
+        } else {
+            format!(" {} ", label.to_uppercase())
+        }
+    }
+}
+
+/// A line of badges for the header, or None when there are none to show
+pub fn status_line(badges: &[Badge], theme: &Theme) -> Option<Line<'static>> {
+    if badges.is_empty() {
+        return None;
+    }
+    let mut spans = Vec::new();
+    for (i, badge) in badges.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(badge.text(theme.badge_icons), theme.badge));
+    }
+    Some(Line::from(spans))
+}
+
+/// Draw a short line of text in the bottom right corner
+pub fn draw_corner(frame: &mut Screen, text: &str, style: Style) {
+    let [_, corner] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    render_chrome(frame, Paragraph::new(text).style(style).right_aligned(), corner);
+}
+
+/// Ask whether to quit just above the bottom line, over whatever the mode
+/// drew there, while the event loop waits for the answer
+pub fn draw_quit_prompt(frame: &mut Frame) {
+    let text = format!(" {} ", messages().confirm_quit);
+    let width = (text.chars().count() as u16).min(frame.area().width);
+    let [_, row, _] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1)]).areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(row);
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(text).style(Style::default().add_modifier(Modifier::REVERSED)), area);
+}
+
+/// Draw a short line of text in the bottom left corner
+pub fn draw_left_corner(frame: &mut Screen, text: &str, style: Style) {
+    let [_, corner] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    render_chrome(frame, Paragraph::new(text).style(style), corner);
+}
+
+/// Draw a running timer: header text at the top and the big digits centered
+pub fn draw_timer(
+    frame: &mut Screen,
+    header: &[Line],
+    header_style: Style,
+    time: &str,
+    digits: DigitOptions,
+    state: &DigitState,
+    time_style: Style,
+) {
+    draw_header(frame, header, header_style);
+    let area = frame.area();
+    frame.render_widget(BigDigits::new(time, digits, state).style(time_style), area);
+}
+
+/// Draw a running timer with a line of text centered below the big digits
+#[allow(clippy::too_many_arguments)]
+pub fn draw_timer_with_caption(
+    frame: &mut Screen,
+    header: &[Line],
+    header_style: Style,
+    time: &str,
+    digits: DigitOptions,
+    state: &DigitState,
+    time_style: Style,
+    caption: Line,
+) {
+    draw_timer_with_panel(frame, header, header_style, time, digits, state, time_style, vec![caption]);
+}
+
+/// Draw the summary of a finished run: its title above the figures, lined
+/// up in a block in the middle of the screen
+pub fn draw_summary(frame: &mut Frame, title: &str, figures: &[String]) {
+    let mut lines = vec![Line::styled(title.to_string(), Style::default().add_modifier(Modifier::BOLD)), Line::raw("")];
+    lines.extend(figures.iter().map(|figure| Line::raw(figure.clone())));
+    lines.push(Line::raw(""));
+    lines.push(Line::raw(messages().any_key_to_exit.as_str()));
+
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let [area] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(lines.len() as u16)]).flex(Flex::Center).areas(area);
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Draw a running timer with a few lines of text centered below the big digits
+#[allow(clippy::too_many_arguments)]
+pub fn draw_timer_with_panel(
+    frame: &mut Screen,
+    header: &[Line],
+    header_style: Style,
+    time: &str,
+    digits: DigitOptions,
+    state: &DigitState,
+    time_style: Style,
+    panel: Vec<Line>,
+) {
+    draw_header(frame, header, header_style);
+    let now = clock::now();
+    let mut rendered = state.render(time, &digits, now);
+    let area = frame.area();
+    let animated = state.animation_time(now);
+    render_timer_panel(frame, area, &mut rendered, animated, time, digits, time_style, panel);
+}
+
+/// Draw a running timer with lines of text below the big digits and a list
+/// beside them, such as what comes next. The list is left out when the
+/// terminal is too narrow for it and the standard digits.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_timer_with_sidebar(
+    frame: &mut Screen,
+    header: &[Line],
+    header_style: Style,
+    time: &str,
+    digits: DigitOptions,
+    state: &DigitState,
+    time_style: Style,
+    panel: Vec<Line>,
+    sidebar: Vec<Line>,
+) {
+    draw_header(frame, header, header_style);
+
+    let area = frame.area();
+    // A column of space on each side of the list
+    let sidebar_width = sidebar.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+    let now = clock::now();
+    let mut rendered = state.render(time, &digits, now);
+    let animated = state.animation_time(now);
+    let digits_width = rendered.width() as u16;
+    if sidebar.is_empty() || area.width < digits_width + sidebar_width {
+        render_timer_panel(frame, area, &mut rendered, animated, time, digits, time_style, panel);
+        return;
+    }
+
+    let [timer_area, sidebar_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(sidebar_width)]).areas(area);
+    render_timer_panel(frame, timer_area, &mut rendered, animated, time, digits, time_style, panel);
+
+    // Keep the list clear of the header
+    let top = (header.len() as u16).min(sidebar_area.height);
+    let sidebar_area = Rect { y: sidebar_area.y + top, height: sidebar_area.height - top, ..sidebar_area };
+    let height = (sidebar.len() as u16).min(sidebar_area.height);
+    frame.render_widget(Paragraph::new(sidebar), centered(sidebar_area, sidebar_width - 2, height));
+}
+
+/// Draw a list of timers, one per line, centered between the header and a
+/// footer of up to two lines, such as what is being typed
+pub fn draw_list(frame: &mut Screen, header: &[Line], header_style: Style, list: Vec<Line>, footer: Vec<Line>) {
+    let [header_area, list_area, footer_area] = Layout::vertical([
+        Constraint::Length(header.len() as u16),
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    render_chrome(frame, Paragraph::new(header.to_vec()).style(header_style), header_area);
+
+    let width = list.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let [list_area] = Layout::vertical([Constraint::Length(list.len() as u16)])
+        .flex(Flex::Center)
+        .areas(list_area);
+    frame.render_widget(Paragraph::new(list), centered(list_area, width, list_area.height));
+    frame.render_widget(Paragraph::new(footer), footer_area);
+}
+
+/// Draw the big digits, rendered at the standard size, with the panel's
+/// lines below them, centered in `area`
+#[allow(clippy::too_many_arguments)]
+fn render_timer_panel(
+    frame: &mut Frame,
+    area: Rect,
+    rendered: &mut RenderedTime,
+    animated: Duration,
+    time: &str,
+    digits: DigitOptions,
+    time_style: Style,
+    panel: Vec<Line>,
+) {
+    // Leave room for the panel and the blank line above it
+    let panel_height = panel.len() as u16;
+    let fits = fit_digits(rendered, time, &digits, area.width, area.height.saturating_sub(panel_height + 1));
+    let [digits_area, panel_area] = Layout::vertical([
+        Constraint::Length(rendered.height() as u16),
+        Constraint::Length(panel_height),
+    ])
+    .spacing(1)
+    .flex(Flex::Center)
+    .areas(area);
+
+    let drawn = render_digits(rendered, &digits, time_style, animated, digits_area, frame.buffer_mut());
+    if !fits {
+        render_too_small(drawn, area, frame.buffer_mut());
+    }
+    frame.render_widget(Paragraph::new(panel).centered(), panel_area);
+}
+
+/// Draw a single-line message centered on screen below the instructions
+pub fn draw_message(frame: &mut Screen, instructions: &str, ui_style: Style, message: &str, message_style: Style) {
+    draw_banner(frame, instructions, ui_style, &[message], message_style);
+}
+
+/// Draw a multi-line banner centered on screen below the instructions
+pub fn draw_banner<S: AsRef<str>>(
+    frame: &mut Screen,
+    instructions: &str,
+    ui_style: Style,
+    lines: &[S],
+    banner_style: Style,
+) {
+    draw_banner_with_header(frame, &[Line::raw(instructions.to_string())], ui_style, lines, banner_style);
+}
+
+/// Draw a multi-line banner centered on screen below header lines
+pub fn draw_banner_with_header<S: AsRef<str>>(
+    frame: &mut Screen,
+    header: &[Line],
+    ui_style: Style,
+    lines: &[S],
+    banner_style: Style,
+) {
+    draw_header(frame, header, ui_style);
+    let area = frame.area();
+    frame.render_widget(Banner::new(lines).style(banner_style), area);
+}
+
+/// Draw a running timer under a title, such as a framed name, with a line of
+/// text centered below the big digits
+#[allow(clippy::too_many_arguments)]
+pub fn draw_titled_timer<S: AsRef<str>>(
+    frame: &mut Screen,
+    header: &[Line],
+    header_style: Style,
+    title: &[S],
+    title_style: Style,
+    time: &str,
+    digits: DigitOptions,
+    state: &DigitState,
+    time_style: Style,
+    caption: Line,
+) {
+    draw_header(frame, header, header_style);
+
+    // Leave room for the title and caption, each with a blank line between
+    let area = frame.area();
+    let reserved = title.len() as u16 + 3;
+    let now = clock::now();
+    let mut rendered = state.render(time, &digits, now);
+    let fits = fit_digits(&mut rendered, time, &digits, area.width, area.height.saturating_sub(reserved));
+    let [title_area, digits_area, caption_area] = Layout::vertical([
+        Constraint::Length(title.len() as u16),
+        Constraint::Length(rendered.height() as u16),
+        Constraint::Length(1),
+    ])
+    .spacing(1)
+    .flex(Flex::Center)
+    .areas(area);
+
+    frame.render_widget(Banner::new(title).style(title_style), title_area);
+    let animated = state.animation_time(now);
+    let drawn = render_digits(&rendered, &digits, time_style, animated, digits_area, frame.buffer_mut());
+    if !fits {
+        render_too_small(drawn, area, frame.buffer_mut());
+    }
+    frame.render_widget(Paragraph::new(caption).centered(), caption_area);
+}
@@ -0,0 +1,219 @@
+//! Headless harness: drives a mode through the real event loop with key
+//! presses and control commands scripted at virtual times, drawing every
+//! frame into an in-memory terminal
+//!
+//! Virtual time comes from a [`MockClock`] that jumps straight from one tick
+//! or scripted input to the next, so a test covering minutes of timer time
+//! runs in milliseconds. Make the script before the mode, so the mode starts
+//! on the script's clock.
+
+#![allow(dead_code)]
+
+use clockit::{
+    app::{self, EventSource, Input, Observers, Render, RenderContext},
+    clock::{Clock, MockClock, Ticker},
+    control::{Command, ControlRequest, Reply},
+    modes::Mode,
+    ui::{Chrome, Screen},
+};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, style::Modifier, Terminal};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+
+/// Terminal size used for every test
+pub const WIDTH: u16 = 80;
+pub const HEIGHT: u16 = 24;
+
+enum Scripted {
+    Key(KeyEvent),
+    Command(Command),
+}
+
+/// Input to play at virtual times, ticking at the mode's refresh rate in between
+pub struct Script {
+    clock: MockClock,
+    start: Instant,
+    end: Instant,
+    ticker: Ticker,
+    /// Sorted by time; inputs at the same time keep the order they were added in
+    inputs: Vec<(Duration, Scripted)>,
+    replies: Vec<(Duration, oneshot::Receiver<Reply>)>,
+}
+
+impl Script {
+    /// A script that runs for `length` of virtual time unless the mode quits
+    /// first. Its clock is installed for timers created after it.
+    pub fn new(length: Duration) -> Self {
+        let clock = MockClock::new();
+        clock.install();
+        let start = clock.now();
+        Script {
+            clock,
+            start,
+            end: start + length,
+            ticker: Ticker::new(start, Duration::ZERO),
+            inputs: Vec::new(),
+            replies: Vec::new(),
+        }
+    }
+
+    /// Press `code` at `at`
+    pub fn key(self, at: Duration, code: KeyCode) -> Self {
+        self.input(at, Scripted::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    /// Send a control command at `at`
+    pub fn command(self, at: Duration, command: Command) -> Self {
+        self.input(at, Scripted::Command(command))
+    }
+
+    fn input(mut self, at: Duration, input: Scripted) -> Self {
+        self.inputs.push((at, input));
+        self.inputs.sort_by_key(|(at, _)| *at);
+        self
+    }
+}
+
+impl EventSource for Script {
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    async fn next(&mut self, refresh_rate: Duration) -> io::Result<Option<Input>> {
+        let tick_due = self.ticker.due(refresh_rate, self.clock.now());
+
+        let input_due = self.inputs.first().is_some_and(|(at, _)| self.start + *at <= tick_due.min(self.end));
+        if input_due {
+            let (at, input) = self.inputs.remove(0);
+            self.clock.sleep_until(self.start + at).await;
+            return Ok(Some(match input {
+                Scripted::Key(key) => Input::Terminal(Event::Key(key)),
+                Scripted::Command(command) => {
+                    let (reply, reply_rx) = oneshot::channel();
+                    self.replies.push((at, reply_rx));
+                    Input::Control(ControlRequest { command, reply })
+                }
+            }));
+        }
+
+        if tick_due > self.end {
+            return Ok(None);
+        }
+        self.clock.sleep_until(tick_due).await;
+        self.ticker.ticked(self.clock.now());
+        Ok(Some(Input::Tick))
+    }
+}
+
+/// Keeps every frame drawn, with the virtual time it was drawn at
+struct Recorder {
+    terminal: Terminal<TestBackend>,
+    start: Instant,
+    frames: Vec<(Duration, Buffer)>,
+}
+
+impl Render for Recorder {
+    fn render<M: Mode>(&mut self, mode: &M, now: Instant, context: RenderContext) -> io::Result<()> {
+        self.terminal.render(mode, now, context)?;
+        self.frames.push((now - self.start, self.terminal.backend().buffer().clone()));
+        Ok(())
+    }
+}
+
+/// What a script did to a mode
+pub struct Recording {
+    frames: Vec<(Duration, Buffer)>,
+    replies: Vec<(Duration, Reply)>,
+    /// Virtual time the run ended at
+    pub ended_at: Duration,
+    /// The instant the run ended at, for asking the mode about its state
+    pub end: Instant,
+}
+
+impl Recording {
+    /// The screen as last drawn at or before `at`, one line per row
+    pub fn screen_at(&self, at: Duration) -> String {
+        let (_, buffer) = self
+            .frames
+            .iter()
+            .rev()
+            .find(|(drawn_at, _)| *drawn_at <= at)
+            .unwrap_or_else(|| panic!("nothing drawn by {:?}", at));
+        screen_text(buffer)
+    }
+
+    /// The last screen drawn
+    pub fn last_screen(&self) -> String {
+        screen_text(&self.frames.last().expect("nothing drawn").1)
+    }
+
+    /// When the last frame was drawn
+    pub fn last_frame_at(&self) -> Duration {
+        self.frames.last().map_or(Duration::ZERO, |(at, _)| *at)
+    }
+
+    /// The reply to the command sent at `at`
+    pub fn reply_at(&self, at: Duration) -> &Reply {
+        &self.replies.iter().find(|(sent_at, _)| *sent_at == at).expect("no command sent then").1
+    }
+}
+
+/// The text on screen as it would be seen, with hidden cells left blank
+fn screen_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let row: String = (area.left()..area.right())
+                .map(|x| &buffer[(x, y)])
+                .map(|cell| if cell.modifier.contains(Modifier::HIDDEN) { " " } else { cell.symbol() })
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The screen after drawing `mode` once at `now` on a terminal of the given size
+pub fn draw_once<M: Mode>(mode: &M, now: Instant, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("test terminal");
+    terminal.draw(|frame| mode.draw(&mut Screen::new(frame, Chrome::Shown), now)).expect("draw failed");
+    screen_text(terminal.backend().buffer())
+}
+
+/// Run `mode` through the event loop with `script`, recording every frame
+pub async fn play<M: Mode>(mode: &mut M, script: Script) -> Recording {
+    play_with(mode, script, Observers::default()).await
+}
+
+/// Like [`play`], with `observers` set up as a real run would
+pub async fn play_with<M: Mode>(mode: &mut M, mut script: Script, mut observers: Observers) -> Recording {
+    let mut recorder = Recorder {
+        terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("test terminal"),
+        start: script.start,
+        frames: Vec::new(),
+    };
+    app::event_loop(&mut recorder, mode, &mut script, &mut observers)
+        .await
+        .expect("event loop failed");
+
+    let replies = script
+        .replies
+        .into_iter()
+        .map(|(at, mut reply)| (at, reply.try_recv().expect("command not answered")))
+        .collect();
+    let end = script.clock.now();
+    Recording {
+        frames: recorder.frames,
+        replies,
+        ended_at: end - script.start,
+        end,
+    }
+}
+
+pub fn secs(secs: f64) -> Duration {
+    Duration::from_secs_f64(secs)
+}
@@ -0,0 +1,336 @@
+//! End-to-end tests of the timer modes, driven by scripted input through
+//! the real event loop
+
+mod harness;
+
+use clockit::{
+    app::Observers,
+    config::Config,
+    control::{Command, Reply},
+    modes::{Countdown, Metronome, Mode, MultiStopwatch, Pomodoro, Sequence, Stopwatch},
+    sound::SoundPlayer,
+    store::{ResumeMode, SavedCountdown},
+    MAX_TIME_SECS,
+};
+use chrono::{Local, TimeDelta};
+use crossterm::event::KeyCode;
+use harness::{draw_once, play, play_with, secs, Script};
+use std::{env, fs, process, time::Instant};
+
+/// The default configuration, without the blinking separator so frames
+/// can be compared whenever they were drawn
+fn config() -> Config {
+    Config { blink_separator: false, ..Config::default() }
+}
+
+#[tokio::test]
+async fn countdown_freezes_while_paused() {
+    let script = Script::new(secs(10.0)).command(secs(3.0), Command::Pause);
+    let mut countdown = Countdown::new(60, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(matches!(recording.reply_at(secs(3.0)), Reply::Ok));
+    assert!(recording.screen_at(secs(3.5)).contains("PAUSED"));
+    assert_eq!(recording.screen_at(secs(3.5)), recording.screen_at(secs(10.0)));
+    assert_ne!(recording.screen_at(secs(1.0)), recording.screen_at(secs(3.5)));
+    assert_eq!(countdown.status(recording.end).time, "0:57");
+}
+
+#[tokio::test]
+async fn countdown_resumes_where_it_paused() {
+    let script = Script::new(secs(10.0))
+        .command(secs(2.0), Command::Pause)
+        .command(secs(5.0), Command::Resume);
+    let mut countdown = Countdown::new(60, &config());
+    let recording = play(&mut countdown, script).await;
+
+    let status = countdown.status(recording.end);
+    assert!(!status.paused);
+    assert_eq!(status.time, "0:53");
+}
+
+#[tokio::test]
+async fn countdown_saved_while_paused_resumes_paused() {
+    let saved = SavedCountdown {
+        length_secs: 60,
+        remaining_ms: 40_000,
+        paused: true,
+        saved_at: Local::now() - TimeDelta::minutes(5),
+    };
+    let yaml = serde_yaml::to_string(&saved).unwrap();
+    let saved: SavedCountdown = serde_yaml::from_str(&yaml).unwrap();
+    let remaining = saved.remaining(ResumeMode::WallClock, Local::now());
+    let script = Script::new(secs(10.0)).command(secs(6.0), Command::Resume);
+    let mut countdown = saved.resume(remaining, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(recording.screen_at(secs(1.0)).contains("PAUSED"));
+    assert_eq!(recording.screen_at(secs(1.0)), recording.screen_at(secs(5.5)));
+    let status = countdown.status(recording.end);
+    assert!(!status.paused);
+    assert_eq!(status.time, "0:36");
+}
+
+#[tokio::test]
+async fn number_keys_start_a_preset_once_time_is_up() {
+    let script = Script::new(secs(20.0)).key(secs(8.0), KeyCode::Char('2'));
+    let mut countdown = Countdown::new(5, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(recording.screen_at(secs(7.0)).contains("2) 10:00"));
+    let status = countdown.status(recording.end);
+    assert!(!status.finished);
+    assert_eq!(status.time, "9:48");
+    // The 5 seconds of the first countdown count along with the preset's
+    assert_eq!(countdown.summary(recording.end).focused, status.remaining.map(|left| secs(605.0) - left));
+}
+
+#[tokio::test]
+async fn the_progress_bar_fills_as_the_countdown_runs() {
+    let script = Script::new(secs(30.0));
+    let config = Config { progress_style: "bar".to_string(), ..config() };
+    let mut countdown = Countdown::new(60, &config);
+    let recording = play(&mut countdown, script).await;
+
+    let start = recording.screen_at(secs(1.0));
+    assert!(start.matches('█').count() < start.matches('░').count());
+    let halfway = recording.last_screen();
+    assert_eq!(halfway.matches('█').count(), halfway.matches('░').count());
+}
+
+#[tokio::test]
+async fn pauses_and_added_time_add_up() {
+    let script = Script::new(secs(20.0))
+        .command(secs(2.0), Command::Pause)
+        .command(secs(4.0), Command::Add(30))
+        .command(secs(5.0), Command::Toggle)
+        .command(secs(8.0), Command::Toggle)
+        .command(secs(8.0), Command::Toggle)
+        .command(secs(12.0), Command::Pause)
+        .command(secs(15.0), Command::Resume);
+    let mut countdown = Countdown::new(60, &config());
+    let recording = play(&mut countdown, script).await;
+
+    // 20 seconds less 6 spent paused, out of 90
+    assert_eq!(countdown.status(recording.end).time, "1:16");
+    assert_eq!(countdown.length().as_secs(), 90);
+}
+
+#[tokio::test]
+async fn adding_the_most_time_over_and_over_does_not_overflow() {
+    let script = (1..10).fold(Script::new(secs(10.0)), |script, at| {
+        script.command(secs(f64::from(at)), Command::Add(MAX_TIME_SECS))
+    });
+    let mut countdown = Countdown::new(60, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(matches!(recording.reply_at(secs(9.0)), Reply::Ok));
+    assert!(!countdown.status(recording.end).finished);
+}
+
+#[tokio::test]
+async fn quitting_a_running_countdown_asks_first() {
+    let script = Script::new(secs(60.0))
+        .key(secs(1.0), KeyCode::Char('q'))
+        .key(secs(2.0), KeyCode::Char('n'))
+        .key(secs(3.0), KeyCode::Char('q'))
+        .key(secs(4.0), KeyCode::Char('y'));
+    let mut countdown = Countdown::new(60, &config());
+    let observers = Observers { confirm_quit: true, ..Observers::default() };
+    let recording = play_with(&mut countdown, script, observers).await;
+
+    assert!(recording.screen_at(secs(1.5)).contains("Really quit? y/n"));
+    assert!(!recording.screen_at(secs(2.5)).contains("Really quit?"));
+    assert_eq!(recording.ended_at, secs(4.0));
+}
+
+#[tokio::test]
+async fn countdown_reaches_times_up() {
+    let script = Script::new(secs(6.0));
+    let mut countdown = Countdown::new(5, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(countdown.status(recording.end).finished);
+    assert!(!recording.screen_at(secs(4.0)).contains("Press any key"));
+}
+
+#[tokio::test]
+async fn delayed_countdown_waits_before_it_starts() {
+    let script = Script::new(secs(6.0));
+    let mut countdown = Countdown::new(5, &config()).with_delay(secs(3.0));
+    let recording = play(&mut countdown, script).await;
+
+    assert!(recording.screen_at(secs(1.0)).contains("Starts at"));
+    assert!(!recording.last_screen().contains("Starts at"));
+    assert_eq!(countdown.status(recording.end).remaining, Some(secs(2.0)));
+}
+
+#[test]
+fn tiny_terminal_shows_the_time_as_plain_text() {
+    let now = Instant::now();
+    let countdown = Countdown::new(300, &config());
+    let screen = draw_once(&countdown, now, 14, 4);
+
+    assert!(screen.contains("5:00"));
+    assert!(screen.contains("Terminal too"));
+}
+
+#[tokio::test]
+async fn finished_countdown_sums_up_as_json() {
+    let script = Script::new(secs(6.0));
+    let mut countdown = Countdown::new(5, &config());
+    let recording = play(&mut countdown, script).await;
+
+    let status = countdown.status(recording.end);
+    let json = countdown.summary(recording.end).to_json(status.mode, status.finished);
+    assert_eq!(json["mode"], "countdown");
+    assert_eq!(json["requested_secs"], 5.0);
+    assert_eq!(json["completed"], true);
+    assert!(json["laps"].is_null());
+}
+
+#[tokio::test]
+async fn stopwatch_sums_up_laps_and_pauses() {
+    let script = Script::new(secs(10.0))
+        .key(secs(2.0), KeyCode::Char('l'))
+        .command(secs(4.0), Command::Pause)
+        .command(secs(6.0), Command::Resume)
+        .key(secs(7.0), KeyCode::Char('l'));
+    let mut stopwatch = Stopwatch::new(&config());
+    let recording = play(&mut stopwatch, script).await;
+
+    let summary = stopwatch.summary(recording.end);
+    assert_eq!(summary.laps, Some(2));
+    assert_eq!(summary.interruptions, Some(1));
+    assert_eq!(summary.focused, Some(secs(8.0)));
+}
+
+#[tokio::test]
+async fn stopwatch_starts_once_the_warmup_is_over() {
+    let script = Script::new(secs(5.0)).key(secs(1.0), KeyCode::Char('l'));
+    let mut stopwatch = Stopwatch::new(&config()).with_warmup(3, SoundPlayer::silent());
+    let recording = play(&mut stopwatch, script).await;
+
+    assert!(recording.screen_at(secs(1.5)).contains("Get ready"));
+    assert!(!recording.last_screen().contains("Get ready"));
+    let summary = stopwatch.summary(recording.end);
+    assert_eq!(summary.focused, Some(secs(2.0)));
+    assert_eq!(summary.laps, Some(0));
+}
+
+#[tokio::test]
+async fn starting_a_stopwatch_stops_the_one_running() {
+    let script = Script::new(secs(10.0))
+        .key(secs(1.0), KeyCode::Char('1'))
+        .key(secs(4.0), KeyCode::Char('2'))
+        .key(secs(6.0), KeyCode::Char(' '))
+        .key(secs(8.0), KeyCode::Char(' '));
+    let labels = vec!["task a".to_string(), "task b".to_string()];
+    let mut stopwatches = MultiStopwatch::new(&config(), labels);
+    let recording = play(&mut stopwatches, script).await;
+
+    // task a ran from 1 to 4, task b from 4 to 6 and again from 8
+    let screen = recording.last_screen();
+    assert!(screen.contains("task a      0:03"));
+    assert!(screen.contains("task b      0:04"));
+    let status = stopwatches.status(recording.end);
+    assert_eq!(status.label.as_deref(), Some("task b"));
+    assert_eq!(status.time, "0:07");
+}
+
+#[tokio::test]
+async fn metronome_follows_a_tapped_tempo() {
+    // Two taps half a second apart, either side of the eleventh beat at 60 bpm
+    let script = Script::new(secs(12.0)).key(secs(9.6), KeyCode::Char(' ')).key(secs(10.1), KeyCode::Char(' '));
+    let mut metronome = Metronome::new(&config(), 60, 4, SoundPlayer::silent());
+    let recording = play(&mut metronome, script).await;
+
+    assert!(recording.screen_at(secs(9.5)).contains("Bar 3, beat 2 of 4"));
+    // The second tap is the twelfth beat, and three more follow at 120 bpm
+    let status = metronome.status(recording.end);
+    assert_eq!(status.label.as_deref(), Some("Bar 4, beat 3/4"));
+    assert_eq!(status.time, "120 bpm");
+}
+
+#[tokio::test]
+async fn interruption_notes_go_to_the_focus_log() {
+    let log = env::temp_dir().join(format!("clockit-notes-{}.md", process::id()));
+    let mut script = Script::new(secs(65.0)).key(secs(10.0), KeyCode::Char('i'));
+    // Typing carries on over the quit key
+    for c in "quick call".chars() {
+        script = script.key(secs(10.0), KeyCode::Char(c));
+    }
+    let script = script.key(secs(11.0), KeyCode::Enter);
+    let config = Config { record_history: false, ..config() };
+    let sequence = Sequence::from_config(&config, secs(60.0), secs(30.0));
+    let mut pomodoro = Pomodoro::new(&config, sequence, 1, SoundPlayer::silent()).with_focus_log(log.clone());
+    let recording = play(&mut pomodoro, script).await;
+
+    assert!(recording.screen_at(secs(10.5)).contains("> quick call_"));
+    assert!(!recording.screen_at(secs(30.0)).contains("quick call"));
+    let journal = fs::read_to_string(&log).unwrap();
+    fs::remove_file(&log).unwrap();
+    assert!(journal.lines().nth(1).is_some_and(|line| line.ends_with("interrupted: quick call")));
+}
+
+#[tokio::test]
+async fn quit_key_ends_the_run() {
+    let script = Script::new(secs(60.0)).key(secs(2.0), KeyCode::Char('q'));
+    let mut stopwatch = Stopwatch::new(&config());
+    let recording = play(&mut stopwatch, script).await;
+
+    assert_eq!(recording.ended_at, secs(2.0));
+    assert!(recording.last_frame_at() < secs(2.0));
+    assert_eq!(stopwatch.elapsed(recording.end), secs(2.0));
+}
+
+#[tokio::test]
+async fn other_keys_keep_the_timer_running() {
+    let script = Script::new(secs(3.0)).key(secs(1.0), KeyCode::Char('x'));
+    let mut stopwatch = Stopwatch::new(&config());
+    let recording = play(&mut stopwatch, script).await;
+
+    assert_eq!(recording.ended_at, secs(3.0));
+    assert_ne!(recording.screen_at(secs(1.0)), recording.last_screen());
+}
+
+#[tokio::test]
+async fn commands_that_dont_apply_are_refused() {
+    let script = Script::new(secs(5.0)).command(secs(4.0), Command::Add(60));
+    let mut countdown = Countdown::new(2, &config());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(matches!(recording.reply_at(secs(4.0)), Reply::Error(_)));
+}
+
+#[tokio::test]
+async fn laps_show_best_worst_and_average() {
+    let script = Script::new(secs(10.0))
+        .key(secs(3.0), KeyCode::Char('l'))
+        .key(secs(5.0), KeyCode::Char('l'))
+        .key(secs(9.0), KeyCode::Char('l'));
+    let mut stopwatch = Stopwatch::new(&config());
+    let recording = play(&mut stopwatch, script).await;
+
+    assert!(!recording.screen_at(secs(2.5)).contains("Lap"));
+    assert!(recording.screen_at(secs(5.5)).contains("Lap 2: 0:02.00  -0:01.00"));
+    let screen = recording.last_screen();
+    assert!(screen.contains("Lap 3: 0:04.00  +0:02.00"));
+    assert!(screen.contains("Best 0:02.00 (lap 2)"));
+    assert!(screen.contains("Worst 0:04.00 (lap 3)"));
+    assert!(screen.contains("Avg 0:03.00"));
+}
+
+#[tokio::test]
+async fn digits_roll_into_the_next_time() {
+    let script = Script::new(secs(3.0));
+    let mut still = Countdown::new(60, &config());
+    let settled = play(&mut still, script).await;
+
+    // Part way through changing from 0:59 to 0:58, then settled
+    let script = Script::new(secs(3.0));
+    let mut rolling = Countdown::new(60, &Config { digit_transition: "roll".to_string(), ..config() });
+    let recording = play(&mut rolling, script).await;
+    assert_ne!(recording.screen_at(secs(1.3)), settled.screen_at(secs(1.3)));
+    assert_eq!(recording.screen_at(secs(1.5)), settled.screen_at(secs(1.5)));
+}
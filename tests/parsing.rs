@@ -0,0 +1,109 @@
+//! Properties of the time, Pomodoro settings and control command parsers,
+//! checked against generated input, and of the command lines clockit builds
+//! to run itself
+
+use clockit::{control::Command, parse_pomodoro_config, parse_time_string, popup, sum_time_strings, MAX_TIME_SECS};
+use proptest::prelude::*;
+use std::{path::Path, process};
+
+/// The seconds in `hours:minutes:seconds`, without any chance of overflow
+fn exact_total(hours: u64, minutes: u64, seconds: u64) -> u128 {
+    hours as u128 * 3600 + minutes as u128 * 60 + seconds as u128
+}
+
+proptest! {
+    #[test]
+    fn clock_times_round_trip(total in 0..=MAX_TIME_SECS) {
+        let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+        prop_assert_eq!(parse_time_string(&format!("{}:{:02}:{:02}", hours, minutes, seconds)), Ok(total));
+        prop_assert_eq!(parse_time_string(&format!("{}:{:02}", total / 60, seconds)), Ok(total));
+        prop_assert_eq!(parse_time_string(&total.to_string()), Ok(total));
+    }
+
+    #[test]
+    fn fields_past_59_carry_over(hours in 0..1000u64, minutes in 0..10_000u64, seconds in 0..100_000u64) {
+        let total = exact_total(hours, minutes, seconds) as u64;
+        prop_assert_eq!(parse_time_string(&format!("{}:{}:{}", hours, minutes, seconds)), Ok(total));
+        prop_assert_eq!(parse_time_string(&format!(" {} : {} ", minutes, seconds)), Ok(total - hours * 3600));
+    }
+
+    #[test]
+    fn huge_times_are_rejected_not_wrapped(hours in any::<u64>(), minutes in any::<u64>(), seconds in any::<u64>()) {
+        let result = parse_time_string(&format!("{}:{}:{}", hours, minutes, seconds));
+        if exact_total(hours, minutes, seconds) <= MAX_TIME_SECS as u128 {
+            prop_assert_eq!(result, Ok(exact_total(hours, minutes, seconds) as u64));
+        } else {
+            prop_assert_eq!(result, Err("Time is too long"));
+        }
+    }
+
+    #[test]
+    fn units_add_up_like_fields(hours in 0..1000u64, minutes in 0..10_000u64, seconds in 0..100_000u64) {
+        let total = exact_total(hours, minutes, seconds) as u64;
+        prop_assert_eq!(parse_time_string(&format!("{}h{}m{}s", hours, minutes, seconds)), Ok(total));
+        let times = [format!("{}h", hours), format!("{}:00", minutes), seconds.to_string()];
+        prop_assert_eq!(sum_time_strings(&times), Ok(total));
+    }
+
+    #[test]
+    fn empty_fields_are_rejected(fields in prop::collection::vec(prop::option::of(0..100u64), 1..=3)) {
+        prop_assume!(fields.iter().any(Option::is_none));
+        let text: Vec<String> = fields.iter().map(|field| field.map(|n| n.to_string()).unwrap_or_default()).collect();
+        prop_assert!(parse_time_string(&text.join(":")).is_err());
+    }
+
+    #[test]
+    fn any_text_parses_or_errors(text in "\\PC*") {
+        let _ = parse_time_string(&text);
+        let _ = parse_pomodoro_config(&text);
+    }
+
+    #[test]
+    fn pomodoro_settings_round_trip(work in 1..=MAX_TIME_SECS / 60, rest in 1..=MAX_TIME_SECS / 60, cycles in any::<u64>()) {
+        prop_assert_eq!(parse_pomodoro_config(&format!("{}/{}/{}", work, rest, cycles)), Ok((work, rest, cycles)));
+        prop_assert_eq!(parse_pomodoro_config(&format!("{}//{}", work, cycles)), Ok((work, 5, cycles)));
+        prop_assert_eq!(parse_pomodoro_config(&work.to_string()), Ok((work, 5, 0)));
+    }
+
+    #[test]
+    fn bad_pomodoro_settings_are_reported(good in 1..1000u64, bad in "-[0-9]{1,3}|[a-z]{1,5}|[1-9][0-9]{20,25}", position in 0..3usize) {
+        let mut parts = [good.to_string(), good.to_string(), good.to_string()];
+        parts[position] = bad;
+        prop_assert!(parse_pomodoro_config(&parts.join("/")).is_err());
+    }
+
+    #[test]
+    fn sessions_must_last_more_than_zero(good in 1..1000u64, position in 0..2usize) {
+        let mut parts = [good.to_string(), good.to_string(), good.to_string()];
+        parts[position] = "0".to_string();
+        prop_assert!(parse_pomodoro_config(&parts.join("/")).is_err());
+    }
+
+    #[test]
+    fn added_time_is_capped_like_typed_time(secs in any::<u64>()) {
+        let parsed = format!("add {}", secs).parse::<Command>();
+        prop_assert_eq!(parsed.is_ok(), secs <= MAX_TIME_SECS);
+    }
+}
+
+#[test]
+fn pomodoro_settings_with_too_many_parts_are_reported() {
+    assert!(parse_pomodoro_config("25/5/4/1").is_err());
+}
+
+#[test]
+fn cycles_may_be_zero_for_endless_sessions() {
+    assert_eq!(parse_pomodoro_config("50/10/0"), Ok((50, 10, 0)));
+}
+
+#[test]
+fn popup_command_lines_parse() {
+    let exe = Path::new(env!("CARGO_BIN_EXE_clockit"));
+    for args in [&["-c", "5:00"][..], &["countdown", "5"], &["track", "--project", "x"], &["resume", "tea"]] {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let command_line = popup::command_line(exe, Some(Path::new("/tmp/clockit.yaml")), &args);
+        // Asking for help stops clockit once the arguments have parsed
+        let parsed = process::Command::new(exe).args(&command_line[1..]).arg("--help").output().unwrap();
+        assert!(parsed.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&parsed.stderr));
+    }
+}
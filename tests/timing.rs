@@ -0,0 +1,119 @@
+//! Tests of behaviour that depends on the passing of time, run on a mock
+//! clock so they finish instantly
+
+mod harness;
+
+use clockit::{
+    alerts::{parse_checkpoint, Alerts},
+    clock::{Clock, MockClock},
+    config::{AlertSettings, Config},
+    modes::{Countdown, Mode, Pomodoro, Sequence},
+    sound::SoundPlayer,
+    sun,
+};
+use crossterm::event::KeyCode;
+use harness::{play, secs, Script};
+
+/// The default configuration, without anything written to the history
+fn config() -> Config {
+    Config { record_history: false, ..Config::default() }
+}
+
+#[tokio::test]
+async fn separator_blinks_each_half_second() {
+    let script = Script::new(secs(2.0));
+    let mut countdown = Countdown::new(60, &Config { blink_separator: true, ..config() });
+    let recording = play(&mut countdown, script).await;
+
+    assert_ne!(recording.screen_at(secs(0.2)), recording.screen_at(secs(0.7)));
+    assert_eq!(recording.screen_at(secs(0.2)), recording.screen_at(secs(0.4)));
+    assert_eq!(recording.screen_at(secs(0.7)), recording.screen_at(secs(0.9)));
+}
+
+#[tokio::test]
+async fn alert_message_appears_once_crossed() {
+    let config = config();
+    let script = Script::new(secs(40.0));
+    let settings = AlertSettings { message: Some("Halfway there".to_string()), ..AlertSettings::at("50%") };
+    let alerts = Alerts::new(&[settings], |name| config.parse_color(name).into());
+    let mut countdown = Countdown::new(60, &config).with_alerts(alerts, SoundPlayer::silent());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(!recording.screen_at(secs(29.5)).contains("Halfway there"));
+    assert!(recording.screen_at(secs(30.5)).contains("Halfway there"));
+    assert!(recording.last_screen().contains("Halfway there"));
+}
+
+#[tokio::test]
+async fn checkpoints_show_what_comes_next() {
+    let config = config();
+    let script = Script::new(secs(50.0));
+    let checkpoints = [parse_checkpoint("45=Section A").unwrap(), parse_checkpoint("20=Review").unwrap()];
+    let alerts = Alerts::new(&checkpoints, |name| config.parse_color(name).into());
+    let mut countdown = Countdown::new(60, &config).with_alerts(alerts, SoundPlayer::silent());
+    let recording = play(&mut countdown, script).await;
+
+    assert!(recording.screen_at(secs(10.5)).contains("Next: Section A in 0:04"));
+    let screen = recording.screen_at(secs(20.5));
+    assert!(screen.contains("Section A") && screen.contains("Next: Review in 0:19"));
+    let screen = recording.last_screen();
+    assert!(screen.contains("Review") && !screen.contains("Next:"));
+}
+
+#[tokio::test]
+async fn pomodoro_moves_from_work_to_break() {
+    let config = config();
+    let clock = MockClock::new();
+    clock.install();
+    let sequence = Sequence::from_config(&config, secs(60.0), secs(30.0));
+    let mut pomodoro = Pomodoro::new(&config, sequence, 2, SoundPlayer::silent());
+
+    clock.advance(secs(59.0));
+    pomodoro.on_tick(clock.now());
+    let status = pomodoro.status(clock.now());
+    assert_eq!(status.time, "0:01");
+    assert!(status.prompt.is_none());
+
+    clock.advance(secs(1.0));
+    pomodoro.on_tick(clock.now());
+    assert!(pomodoro.status(clock.now()).prompt.is_some_and(|prompt| prompt.contains("Complete!")));
+}
+
+#[tokio::test]
+async fn pomodoro_break_starts_after_keys() {
+    let config = config();
+    let script = Script::new(secs(70.0)).key(secs(61.0), KeyCode::Enter).key(secs(62.0), KeyCode::Enter);
+    let sequence = Sequence::from_config(&config, secs(60.0), secs(30.0));
+    let mut pomodoro = Pomodoro::new(&config, sequence, 2, SoundPlayer::silent());
+    let recording = play(&mut pomodoro, script).await;
+
+    let status = pomodoro.status(recording.end);
+    assert!(status.label.is_some_and(|label| label.starts_with("Break")));
+    assert_eq!(status.time, "0:22");
+}
+
+#[tokio::test]
+async fn pomodoro_carries_on_without_keys() {
+    let mut config = config();
+    config.pomodoro.auto_continue = 5;
+    let script = Script::new(secs(75.0));
+    let sequence = Sequence::from_config(&config, secs(60.0), secs(30.0));
+    let mut pomodoro = Pomodoro::new(&config, sequence, 2, SoundPlayer::silent());
+    let recording = play(&mut pomodoro, script).await;
+
+    assert!(recording.screen_at(secs(62.0)).contains("Continuing in 0:0"));
+    let status = pomodoro.status(recording.end);
+    assert!(status.label.is_some_and(|label| label.starts_with("Break")));
+    assert_eq!(status.time, "0:20");
+}
+
+#[test]
+fn sun_rises_and_sets_in_london_at_midsummer() {
+    // Sunrise is at 03:43 UTC on the solstice, and sunset at 20:21
+    let (latitude, longitude) = (51.5, -0.13);
+    let at = |time: &str| format!("2026-06-21T{}:00Z", time).parse().unwrap();
+    assert!(!sun::is_up(at("03:38"), latitude, longitude));
+    assert!(sun::is_up(at("03:48"), latitude, longitude));
+    assert!(sun::is_up(at("20:16"), latitude, longitude));
+    assert!(!sun::is_up(at("20:26"), latitude, longitude));
+}